@@ -0,0 +1,146 @@
+//! Suggestion benchmarks over realistic nonconvex scenes.
+//!
+//! The crate carries no external dependencies, so this is a plain
+//! `harness = false` binary (`cargo bench`) timing with
+//! `std::time::Instant`. Scenes are deterministic: obstacle fields of
+//! 10–200 collision constraints, mixed discrete snap grids, and a
+//! multi-object distribution — the shapes that actually dominate
+//! interactive documents, not the trivial single-box case.
+//!
+//! Alongside time we report what the search *returned*: the split of
+//! suggestion qualities and the worst residual violation, so a "speed
+//! up" that silently degrades answers shows in the same table.
+
+use newton_core::bounds::Bounds;
+use newton_core::constraint::{
+    BoxConstraint, CollisionConstraint, ConstraintSystem, DiscreteConstraint,
+};
+use newton_core::linalg::Vector;
+use newton_core::multi::DistributionConstraint;
+use newton_core::rank::RankingCriteria;
+use newton_core::suggest::{suggest, SuggestionQuality};
+
+fn v(x: f64, y: f64) -> Vector {
+    Vector::new(vec![x, y])
+}
+
+/// Deterministic obstacle field: `count` square obstacles laid out on
+/// a coprime lattice walk inside a 1000x1000 canvas.
+fn obstacle_field(count: usize) -> ConstraintSystem {
+    let mut sys = ConstraintSystem::new(2);
+    sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(1000.0, 1000.0))));
+    for i in 0..count {
+        let x = ((i * 137) % 900) as f64 + 20.0;
+        let y = ((i * 251) % 900) as f64 + 20.0;
+        sys.add(CollisionConstraint::new(Bounds::new(
+            v(x, y),
+            v(x + 40.0, y + 40.0),
+        )));
+    }
+    sys
+}
+
+/// Obstacle field plus a coarse snap grid, the common "snap to grid
+/// among furniture" document.
+fn mixed_field(count: usize) -> ConstraintSystem {
+    let mut sys = obstacle_field(count);
+    let mut points = Vec::new();
+    for gx in 0..10 {
+        for gy in 0..10 {
+            points.push(v(gx as f64 * 100.0 + 50.0, gy as f64 * 100.0 + 50.0));
+        }
+    }
+    sys.add(DiscreteConstraint::new(points));
+    sys
+}
+
+/// Four objects distributed along x in an 8-dimensional stacked state.
+fn multi_object() -> (ConstraintSystem, Vector, Vector) {
+    let mut sys = ConstraintSystem::new(8);
+    sys.add(DistributionConstraint::centers(4, 2, 0, 0.5));
+    let current = Vector::new(vec![0.0, 0.0, 100.0, 0.0, 200.0, 0.0, 300.0, 0.0]);
+    let mut intent = current.clone();
+    intent.set(2, 170.0);
+    (sys, current, intent)
+}
+
+struct Outcome {
+    median_us: f64,
+    worst_us: f64,
+    exact: usize,
+    projected: usize,
+    best_effort: usize,
+    worst_violation: f64,
+}
+
+/// Runs `calls` suggest calls over a deterministic sweep of intents
+/// and summarises time and answer quality.
+fn run(system: &ConstraintSystem, current: &Vector, intents: &[Vector]) -> Outcome {
+    let criteria = RankingCriteria::default();
+    let mut times = Vec::with_capacity(intents.len());
+    let mut outcome = Outcome {
+        median_us: 0.0,
+        worst_us: 0.0,
+        exact: 0,
+        projected: 0,
+        best_effort: 0,
+        worst_violation: 0.0,
+    };
+    for intent in intents {
+        let started = std::time::Instant::now();
+        let r = suggest(system, current, intent, &criteria);
+        times.push(started.elapsed().as_secs_f64() * 1e6);
+        match r.quality {
+            SuggestionQuality::Exact => outcome.exact += 1,
+            SuggestionQuality::Projected | SuggestionQuality::Coarse => outcome.projected += 1,
+            SuggestionQuality::BestEffort => outcome.best_effort += 1,
+        }
+        outcome.worst_violation = outcome
+            .worst_violation
+            .max((-system.margin(&r.position)).max(0.0));
+    }
+    times.sort_by(f64::total_cmp);
+    outcome.median_us = times[times.len() / 2];
+    outcome.worst_us = *times.last().unwrap();
+    outcome
+}
+
+/// Deterministic sweep of intents across the canvas, deliberately
+/// including points inside obstacles and outside the canvas.
+fn intent_sweep(calls: usize) -> Vec<Vector> {
+    (0..calls)
+        .map(|i| {
+            v(
+                ((i * 193) % 1200) as f64 - 100.0,
+                ((i * 389) % 1200) as f64 - 100.0,
+            )
+        })
+        .collect()
+}
+
+fn report(name: &str, o: &Outcome) {
+    println!(
+        "{name:<28} median {:>8.1}µs  worst {:>9.1}µs  exact {:>3}  projected {:>3}  best-effort {:>3}  worst-violation {:.2e}",
+        o.median_us, o.worst_us, o.exact, o.projected, o.best_effort, o.worst_violation
+    );
+}
+
+fn main() {
+    let calls = 64;
+    let intents = intent_sweep(calls);
+    let current = v(500.0, 500.0);
+
+    for count in [10, 50, 200] {
+        let sys = obstacle_field(count);
+        report(&format!("obstacles/{count}"), &run(&sys, &current, &intents));
+    }
+    for count in [10, 50] {
+        let sys = mixed_field(count);
+        report(&format!("mixed-discrete/{count}"), &run(&sys, &current, &intents));
+    }
+    {
+        let (sys, current, intent) = multi_object();
+        let intents = vec![intent; 16];
+        report("multi-object/4x2d", &run(&sys, &current, &intents));
+    }
+}
@@ -0,0 +1,264 @@
+//! Authoring-time constraint diagnostics.
+//!
+//! A contradictory constraint set does not fail loudly at runtime — it
+//! just makes every suggestion come back best-effort, which reads as a
+//! mysteriously stuck object. [`analyze`] inspects a system's structure
+//! (via [`Constraint::as_any`](crate::constraint::Constraint::as_any))
+//! before any gesture runs and reports pairwise contradictions,
+//! redundancies, and degenerate constraints with enough context for a
+//! document author to act on. Analysis is best-effort: constraint
+//! types it does not recognise are simply skipped, never misreported.
+
+use crate::bounds::Bounds;
+use crate::constraint::{BoxConstraint, ConstraintSystem, DiscreteConstraint, HalfspaceConstraint};
+use crate::linalg::Vector;
+
+/// What kind of problem a report describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictKind {
+    /// The constraints cannot all hold: the feasible set is empty.
+    Contradiction,
+    /// One constraint is implied by another and only slows convergence.
+    Redundancy,
+    /// A single constraint admits (almost) no freedom on its own.
+    Degenerate,
+}
+
+/// One diagnostic, naming the constraints involved by their system
+/// index.
+#[derive(Debug, Clone)]
+pub struct ConflictReport {
+    pub kind: ConflictKind,
+    /// Indices of the constraints involved, ascending.
+    pub constraints: Vec<usize>,
+    /// Human-readable explanation for the authoring UI.
+    pub detail: String,
+}
+
+/// Smallest value of `normal · x` over `bounds` — the box's support in
+/// the `-normal` direction.
+fn box_support_min(bounds: &Bounds, normal: &Vector) -> f64 {
+    (0..bounds.dim())
+        .map(|i| {
+            let n = normal.get(i);
+            if n >= 0.0 {
+                n * bounds.min().get(i)
+            } else {
+                n * bounds.max().get(i)
+            }
+        })
+        .sum()
+}
+
+/// Inspects the system's structure and reports contradictions,
+/// redundancies, and degenerate constraints. Reports are ordered by
+/// the first constraint index involved; unrecognised constraint types
+/// are skipped.
+pub fn analyze(system: &ConstraintSystem) -> Vec<ConflictReport> {
+    let mut reports = Vec::new();
+    let constraints = system.constraints();
+
+    // Single-constraint degeneracies.
+    for (i, c) in constraints.iter().enumerate() {
+        let any = c.as_any();
+        if let Some(b) = any.downcast_ref::<BoxConstraint>() {
+            let flat = (0..b.bounds().dim())
+                .any(|d| b.bounds().max().get(d) - b.bounds().min().get(d) < crate::EPSILON);
+            if flat {
+                reports.push(ConflictReport {
+                    kind: ConflictKind::Degenerate,
+                    constraints: vec![i],
+                    detail: format!("box constraint {i} has a zero-width extent"),
+                });
+            }
+        } else if let Some(d) = any.downcast_ref::<DiscreteConstraint>() {
+            if d.points().len() == 1 {
+                reports.push(ConflictReport {
+                    kind: ConflictKind::Degenerate,
+                    constraints: vec![i],
+                    detail: format!("discrete constraint {i} pins the state to a single point"),
+                });
+            }
+        }
+    }
+
+    // Pairwise structure.
+    for i in 0..constraints.len() {
+        for j in (i + 1)..constraints.len() {
+            let a = constraints[i].as_any();
+            let b = constraints[j].as_any();
+
+            if let (Some(ba), Some(bb)) = (
+                a.downcast_ref::<BoxConstraint>(),
+                b.downcast_ref::<BoxConstraint>(),
+            ) {
+                if !ba.bounds().intersects(bb.bounds()) {
+                    reports.push(ConflictReport {
+                        kind: ConflictKind::Contradiction,
+                        constraints: vec![i, j],
+                        detail: format!("box constraints {i} and {j} are disjoint"),
+                    });
+                } else if contains_bounds(bb.bounds(), ba.bounds()) {
+                    reports.push(ConflictReport {
+                        kind: ConflictKind::Redundancy,
+                        constraints: vec![i, j],
+                        detail: format!("box constraint {j} is implied by {i}"),
+                    });
+                } else if contains_bounds(ba.bounds(), bb.bounds()) {
+                    reports.push(ConflictReport {
+                        kind: ConflictKind::Redundancy,
+                        constraints: vec![i, j],
+                        detail: format!("box constraint {i} is implied by {j}"),
+                    });
+                }
+                continue;
+            }
+
+            if let (Some(ha), Some(hb)) = (
+                a.downcast_ref::<HalfspaceConstraint>(),
+                b.downcast_ref::<HalfspaceConstraint>(),
+            ) {
+                let (na, nb) = (ha.normal(), hb.normal());
+                let (ua, ub) = (
+                    na.scale(1.0 / na.norm()),
+                    nb.scale(1.0 / nb.norm()),
+                );
+                let (oa, ob) = (ha.offset() / na.norm(), hb.offset() / nb.norm());
+                if ua.distance(&ub.scale(-1.0)) < crate::EPSILON.sqrt() && oa + ob < 0.0 {
+                    reports.push(ConflictReport {
+                        kind: ConflictKind::Contradiction,
+                        constraints: vec![i, j],
+                        detail: format!(
+                            "halfspaces {i} and {j} oppose with a gap of {:.3}",
+                            -(oa + ob)
+                        ),
+                    });
+                } else if ua.distance(&ub) < crate::EPSILON.sqrt() {
+                    let (loose, tight) = if oa >= ob { (i, j) } else { (j, i) };
+                    reports.push(ConflictReport {
+                        kind: ConflictKind::Redundancy,
+                        constraints: vec![i, j],
+                        detail: format!("halfspace {loose} is implied by {tight}"),
+                    });
+                }
+                continue;
+            }
+
+            // Box entirely outside a halfspace (either pairing order).
+            let pairing = match (
+                a.downcast_ref::<BoxConstraint>(),
+                b.downcast_ref::<HalfspaceConstraint>(),
+            ) {
+                (Some(bx), Some(h)) => Some((bx, h)),
+                _ => match (
+                    b.downcast_ref::<BoxConstraint>(),
+                    a.downcast_ref::<HalfspaceConstraint>(),
+                ) {
+                    (Some(bx), Some(h)) => Some((bx, h)),
+                    _ => None,
+                },
+            };
+            if let Some((bx, h)) = pairing {
+                if box_support_min(bx.bounds(), h.normal()) > h.offset() + crate::EPSILON {
+                    reports.push(ConflictReport {
+                        kind: ConflictKind::Contradiction,
+                        constraints: vec![i, j],
+                        detail: format!("box and halfspace constraints {i} and {j} are disjoint"),
+                    });
+                }
+            }
+        }
+    }
+    reports
+}
+
+/// True when `outer` contains `inner` entirely.
+fn contains_bounds(outer: &Bounds, inner: &Bounds) -> bool {
+    outer.contains(inner.min()) && outer.contains(inner.max())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    fn boxed(x0: f64, y0: f64, x1: f64, y1: f64) -> BoxConstraint {
+        BoxConstraint::new(Bounds::new(v(x0, y0), v(x1, y1)))
+    }
+
+    #[test]
+    fn disjoint_boxes_contradict() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(boxed(0.0, 0.0, 10.0, 10.0));
+        sys.add(boxed(20.0, 20.0, 30.0, 30.0));
+        let reports = analyze(&sys);
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].kind, ConflictKind::Contradiction);
+        assert_eq!(reports[0].constraints, vec![0, 1]);
+    }
+
+    #[test]
+    fn nested_boxes_are_redundant_not_conflicting() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(boxed(0.0, 0.0, 100.0, 100.0));
+        sys.add(boxed(10.0, 10.0, 20.0, 20.0));
+        let reports = analyze(&sys);
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].kind, ConflictKind::Redundancy);
+        // The outer box is the implied one.
+        assert!(reports[0].detail.contains("constraint 0 is implied"));
+    }
+
+    #[test]
+    fn opposing_halfspaces_with_a_gap_contradict() {
+        let mut sys = ConstraintSystem::new(2);
+        // x <= 10 and x >= 20.
+        sys.add(HalfspaceConstraint::new(v(1.0, 0.0), 10.0));
+        sys.add(HalfspaceConstraint::new(v(-1.0, 0.0), -20.0));
+        let reports = analyze(&sys);
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].kind, ConflictKind::Contradiction);
+    }
+
+    #[test]
+    fn parallel_halfspaces_flag_the_looser_as_redundant() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(HalfspaceConstraint::new(v(1.0, 0.0), 10.0));
+        sys.add(HalfspaceConstraint::new(v(2.0, 0.0), 100.0));
+        let reports = analyze(&sys);
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].kind, ConflictKind::Redundancy);
+        assert!(reports[0].detail.contains("halfspace 1 is implied by 0"));
+    }
+
+    #[test]
+    fn box_outside_halfspace_contradicts() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(boxed(50.0, 0.0, 60.0, 10.0));
+        sys.add(HalfspaceConstraint::new(v(1.0, 0.0), 10.0));
+        let reports = analyze(&sys);
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].kind, ConflictKind::Contradiction);
+    }
+
+    #[test]
+    fn degenerate_constraints_are_reported() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(boxed(0.0, 0.0, 10.0, 0.0));
+        sys.add(DiscreteConstraint::new(vec![v(5.0, 0.0)]));
+        let reports = analyze(&sys);
+        let kinds: Vec<ConflictKind> = reports.iter().map(|r| r.kind).collect();
+        assert_eq!(kinds, vec![ConflictKind::Degenerate, ConflictKind::Degenerate]);
+    }
+
+    #[test]
+    fn compatible_constraints_are_silent() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(boxed(0.0, 0.0, 100.0, 100.0));
+        sys.add(HalfspaceConstraint::new(v(0.0, 1.0), 50.0));
+        assert!(analyze(&sys).is_empty());
+    }
+}
@@ -0,0 +1,223 @@
+//! Axis-aligned bounding regions.
+
+use crate::linalg::Vector;
+
+/// An axis-aligned box given by per-dimension minimum and maximum
+/// corners. Used both as a feasible region ([`crate::constraint::BoxConstraint`])
+/// and as an obstacle footprint ([`crate::constraint::CollisionConstraint`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bounds {
+    min: Vector,
+    max: Vector,
+}
+
+impl Bounds {
+    /// Creates bounds from corner vectors. Panics if the dimensions
+    /// disagree or any minimum exceeds the corresponding maximum.
+    pub fn new(min: Vector, max: Vector) -> Self {
+        assert_eq!(min.dim(), max.dim(), "dimension mismatch in Bounds");
+        for i in 0..min.dim() {
+            assert!(
+                min.get(i) <= max.get(i),
+                "Bounds min exceeds max in dimension {i}"
+            );
+        }
+        Bounds { min, max }
+    }
+
+    /// Number of dimensions.
+    pub fn dim(&self) -> usize {
+        self.min.dim()
+    }
+
+    /// Minimum corner.
+    pub fn min(&self) -> &Vector {
+        &self.min
+    }
+
+    /// Maximum corner.
+    pub fn max(&self) -> &Vector {
+        &self.max
+    }
+
+    /// Geometric center.
+    pub fn center(&self) -> Vector {
+        self.min.lerp(&self.max, 0.5)
+    }
+
+    /// True if the point lies inside or on the boundary.
+    pub fn contains(&self, point: &Vector) -> bool {
+        assert_eq!(point.dim(), self.dim(), "dimension mismatch in contains");
+        (0..self.dim()).all(|i| point.get(i) >= self.min.get(i) && point.get(i) <= self.max.get(i))
+    }
+
+    /// Smallest bounds containing a non-empty set of points. Panics on
+    /// an empty slice or mismatched dimensions.
+    pub fn from_points(points: &[Vector]) -> Bounds {
+        assert!(!points.is_empty(), "Bounds::from_points requires at least one point");
+        let d = points[0].dim();
+        let mut min = points[0].clone();
+        let mut max = points[0].clone();
+        for p in &points[1..] {
+            assert_eq!(p.dim(), d, "dimension mismatch in from_points");
+            min = min.component_min(p);
+            max = max.component_max(p);
+        }
+        Bounds { min, max }
+    }
+
+    /// Smallest bounds containing both operands.
+    pub fn union(&self, other: &Bounds) -> Bounds {
+        assert_eq!(self.dim(), other.dim(), "dimension mismatch in union");
+        Bounds {
+            min: self.min.component_min(&other.min),
+            max: self.max.component_max(&other.max),
+        }
+    }
+
+    /// Bounds grown by per-axis amounts on the low and high sides
+    /// independently. Negative amounts shrink; panics if a dimension
+    /// would invert.
+    pub fn expanded(&self, low: &Vector, high: &Vector) -> Bounds {
+        assert_eq!(low.dim(), self.dim(), "dimension mismatch in expanded");
+        assert_eq!(high.dim(), self.dim(), "dimension mismatch in expanded");
+        Bounds::new(self.min.sub(low), self.max.add(high))
+    }
+
+    /// Bounds grown by the same amount on every side.
+    pub fn inflated(&self, amount: f64) -> Bounds {
+        let grow = Vector::new(vec![amount; self.dim()]);
+        self.expanded(&grow, &grow)
+    }
+
+    /// Nearest point on the *boundary* of the bounds. Unlike
+    /// [`clamp`](Self::clamp), interior points are pushed out to the
+    /// nearest face rather than returned unchanged.
+    pub fn closest_point_on_boundary(&self, point: &Vector) -> Vector {
+        if !self.contains(point) {
+            return self.clamp(point);
+        }
+        let mut best_axis = 0;
+        let mut best_dist = f64::INFINITY;
+        let mut best_target = 0.0;
+        for i in 0..self.dim() {
+            let lo = point.get(i) - self.min.get(i);
+            let hi = self.max.get(i) - point.get(i);
+            if lo < best_dist {
+                best_dist = lo;
+                best_axis = i;
+                best_target = self.min.get(i);
+            }
+            if hi < best_dist {
+                best_dist = hi;
+                best_axis = i;
+                best_target = self.max.get(i);
+            }
+        }
+        let mut out = point.clone();
+        out.set(best_axis, best_target);
+        out
+    }
+
+    /// True if the two bounds overlap (sharing a face counts).
+    pub fn intersects(&self, other: &Bounds) -> bool {
+        assert_eq!(self.dim(), other.dim(), "dimension mismatch in intersects");
+        (0..self.dim())
+            .all(|i| self.min.get(i) <= other.max.get(i) && other.min.get(i) <= self.max.get(i))
+    }
+
+    /// The overlap of two bounds, or `None` when they are disjoint.
+    pub fn intersection(&self, other: &Bounds) -> Option<Bounds> {
+        if !self.intersects(other) {
+            return None;
+        }
+        Some(Bounds::new(
+            self.min.component_max(&other.min),
+            self.max.component_min(&other.max),
+        ))
+    }
+
+    /// Nearest point inside the bounds (component-wise clamp).
+    pub fn clamp(&self, point: &Vector) -> Vector {
+        assert_eq!(point.dim(), self.dim(), "dimension mismatch in clamp");
+        point.component_max(&self.min).component_min(&self.max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit_square() -> Bounds {
+        Bounds::new(Vector::zeros(2), Vector::new(vec![1.0, 1.0]))
+    }
+
+    #[test]
+    fn contains_and_clamp() {
+        let b = unit_square();
+        assert!(b.contains(&Vector::new(vec![0.5, 0.5])));
+        assert!(b.contains(&Vector::new(vec![1.0, 0.0])));
+        assert!(!b.contains(&Vector::new(vec![1.5, 0.5])));
+        assert_eq!(
+            b.clamp(&Vector::new(vec![2.0, -1.0])),
+            Vector::new(vec![1.0, 0.0])
+        );
+    }
+
+    #[test]
+    fn center() {
+        assert_eq!(unit_square().center(), Vector::new(vec![0.5, 0.5]));
+    }
+
+    #[test]
+    fn from_points_is_tight() {
+        let b = Bounds::from_points(&[
+            Vector::new(vec![1.0, 5.0]),
+            Vector::new(vec![-2.0, 3.0]),
+            Vector::new(vec![0.0, 7.0]),
+        ]);
+        assert_eq!(b.min(), &Vector::new(vec![-2.0, 3.0]));
+        assert_eq!(b.max(), &Vector::new(vec![1.0, 7.0]));
+    }
+
+    #[test]
+    fn union_covers_both() {
+        let a = unit_square();
+        let b = Bounds::new(Vector::new(vec![2.0, -1.0]), Vector::new(vec![3.0, 0.5]));
+        let u = a.union(&b);
+        assert_eq!(u.min(), &Vector::new(vec![0.0, -1.0]));
+        assert_eq!(u.max(), &Vector::new(vec![3.0, 1.0]));
+    }
+
+    #[test]
+    fn asymmetric_expansion() {
+        let b = unit_square().expanded(
+            &Vector::new(vec![1.0, 0.0]),
+            &Vector::new(vec![0.0, 2.0]),
+        );
+        assert_eq!(b.min(), &Vector::new(vec![-1.0, 0.0]));
+        assert_eq!(b.max(), &Vector::new(vec![1.0, 3.0]));
+        assert_eq!(unit_square().inflated(0.5).min(), &Vector::new(vec![-0.5, -0.5]));
+    }
+
+    #[test]
+    #[should_panic(expected = "min exceeds max")]
+    fn shrinking_past_inversion_panics() {
+        unit_square().inflated(-0.6);
+    }
+
+    #[test]
+    fn boundary_point_pushes_interior_out() {
+        let b = unit_square();
+        // Interior point nearest the left face.
+        assert_eq!(
+            b.closest_point_on_boundary(&Vector::new(vec![0.2, 0.5])),
+            Vector::new(vec![0.0, 0.5])
+        );
+        // Exterior points fall back to the clamp.
+        assert_eq!(
+            b.closest_point_on_boundary(&Vector::new(vec![2.0, 0.5])),
+            Vector::new(vec![1.0, 0.5])
+        );
+    }
+}
@@ -0,0 +1,343 @@
+//! Incremental reuse of suggestion work.
+//!
+//! Revalidating a whole scene after an edit is O(objects ×
+//! constraints), but most edits touch one object: everything else would
+//! recompute exactly the answer it already has. [`SuggestCache`] stores
+//! each object's last response together with the inputs that produced
+//! it and the constraint system's [revision](
+//! crate::constraint::ConstraintSystem::revision); a lookup whose
+//! revision and inputs match returns the stored answer without touching
+//! a single constraint, so a one-object edit costs one search plus
+//! O(changes) cache probes instead of a full re-solve.
+//!
+//! The cache keys on positions and revision only, not on the ranking
+//! criteria — criteria hold boxed scorers and cannot be compared.
+//! Callers that switch criteria for an object must
+//! [`invalidate`](SuggestCache::invalidate) it first.
+//!
+//! One level down, [`ProjectionCache`] memoises raw projections the
+//! same way, keyed on the input point and the constraint set itself
+//! rather than an object identity.
+
+use std::collections::HashMap;
+
+use crate::constraint::ConstraintSystem;
+use crate::linalg::Vector;
+use crate::object::ObjectId;
+use crate::project::{project_dykstra, ProjectionOptions, ProjectionResult};
+use crate::rank::RankingCriteria;
+use crate::suggest::{suggest, SuggestResponse};
+
+struct CacheEntry {
+    revision: u64,
+    current: Vector,
+    intent: Vector,
+    response: SuggestResponse,
+}
+
+/// Hit/miss counters, for instrumentation and tests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+/// Per-object memo of the last suggestion, invalidated by constraint
+/// edits (via the system revision) or explicitly per object.
+#[derive(Default)]
+pub struct SuggestCache {
+    entries: HashMap<ObjectId, CacheEntry>,
+    stats: CacheStats,
+}
+
+impl SuggestCache {
+    pub fn new() -> Self {
+        SuggestCache::default()
+    }
+
+    /// [`suggest`] with memoisation: returns the stored response when
+    /// the system revision and both positions match the last call for
+    /// `object`, otherwise computes, stores, and returns a fresh one.
+    pub fn suggest(
+        &mut self,
+        system: &ConstraintSystem,
+        object: ObjectId,
+        current: &Vector,
+        intent: &Vector,
+        criteria: &RankingCriteria,
+    ) -> SuggestResponse {
+        if let Some(entry) = self.entries.get(&object) {
+            if entry.revision == system.revision()
+                && entry.current == *current
+                && entry.intent == *intent
+            {
+                self.stats.hits += 1;
+                return entry.response.clone();
+            }
+        }
+        self.stats.misses += 1;
+        let response = suggest(system, current, intent, criteria);
+        self.entries.insert(
+            object,
+            CacheEntry {
+                revision: system.revision(),
+                current: current.clone(),
+                intent: intent.clone(),
+                response: response.clone(),
+            },
+        );
+        response
+    }
+
+    /// Drops the stored answer for one object (e.g. after changing its
+    /// ranking criteria). Returns whether one was stored.
+    pub fn invalidate(&mut self, object: ObjectId) -> bool {
+        self.entries.remove(&object).is_some()
+    }
+
+    /// Drops every stored answer.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Number of objects with a stored answer.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+}
+
+/// Input points closer than this quantum share a cache slot: drag
+/// loops re-query bit-identical pointer positions, and anything inside
+/// a millionth of a unit is the same query for interaction purposes.
+const POINT_QUANTUM: f64 = 1e-6;
+
+/// LRU memo of full projections, keyed by the quantized input point
+/// and a structural fingerprint of the constraint set, for the
+/// hot-path case where the pointer has not moved but the projection is
+/// asked for again every frame.
+///
+/// The fingerprint hashes each constraint's [`structural_key`](
+/// crate::constraint::Constraint::structural_key); a system containing
+/// any constraint that opts out of keying cannot be fingerprinted and
+/// is computed uncached (a miss that stores nothing), so a stale entry
+/// can never be returned for a constraint the fingerprint cannot see.
+pub struct ProjectionCache {
+    capacity: usize,
+    /// Most recently used last; linear scans are fine at the small
+    /// capacities drag loops need.
+    entries: Vec<(ProjectionKey, ProjectionResult)>,
+    stats: CacheStats,
+}
+
+#[derive(PartialEq, Eq)]
+struct ProjectionKey {
+    fingerprint: u64,
+    point: Vec<i64>,
+    max_iterations: usize,
+    tolerance: u64,
+}
+
+impl ProjectionCache {
+    /// Panics on a zero capacity.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "ProjectionCache requires a capacity");
+        ProjectionCache {
+            capacity,
+            entries: Vec::new(),
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// [`project_dykstra`] with memoisation: an exact repeat (same
+    /// fingerprint, same quantized point, same options) returns the
+    /// stored result and refreshes its recency; anything else computes,
+    /// stores, and evicts the least recently used entry when full.
+    pub fn project(
+        &mut self,
+        system: &ConstraintSystem,
+        point: &Vector,
+        options: &ProjectionOptions,
+    ) -> ProjectionResult {
+        let Some(fingerprint) = fingerprint(system) else {
+            self.stats.misses += 1;
+            return project_dykstra(system, point, options);
+        };
+        let key = ProjectionKey {
+            fingerprint,
+            point: quantize(point),
+            max_iterations: options.max_iterations,
+            tolerance: options.tolerance.to_bits(),
+        };
+        if let Some(i) = self.entries.iter().position(|(k, _)| *k == key) {
+            self.stats.hits += 1;
+            let entry = self.entries.remove(i);
+            let result = entry.1.clone();
+            self.entries.push(entry);
+            return result;
+        }
+        self.stats.misses += 1;
+        let result = project_dykstra(system, point, options);
+        if self.entries.len() == self.capacity {
+            self.entries.remove(0);
+        }
+        self.entries.push((key, result.clone()));
+        result
+    }
+
+    /// Drops every stored projection.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Number of stored projections.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+}
+
+/// Structural fingerprint of the constraint set, or `None` when any
+/// constraint opts out of structural keying.
+fn fingerprint(system: &ConstraintSystem) -> Option<u64> {
+    crate::fingerprint::fingerprint_system(system)
+}
+
+fn quantize(point: &Vector) -> Vec<i64> {
+    point
+        .as_slice()
+        .iter()
+        .map(|x| (x / POINT_QUANTUM).round() as i64)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bounds::Bounds;
+    use crate::constraint::BoxConstraint;
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    fn canvas() -> ConstraintSystem {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(100.0, 100.0))));
+        sys
+    }
+
+    #[test]
+    fn repeated_query_hits_the_cache() {
+        let sys = canvas();
+        let mut cache = SuggestCache::new();
+        let criteria = RankingCriteria::default();
+        let a = cache.suggest(&sys, 1, &v(0.0, 0.0), &v(150.0, 50.0), &criteria);
+        let b = cache.suggest(&sys, 1, &v(0.0, 0.0), &v(150.0, 50.0), &criteria);
+        assert_eq!(a.position, b.position);
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 1 });
+    }
+
+    #[test]
+    fn constraint_edit_invalidates_but_only_via_revision() {
+        let mut sys = canvas();
+        let mut cache = SuggestCache::new();
+        let criteria = RankingCriteria::default();
+        cache.suggest(&sys, 1, &v(0.0, 0.0), &v(150.0, 50.0), &criteria);
+        // Shrink the canvas: the old answer (clamped to x = 100) is
+        // stale and must be recomputed.
+        sys.remove(0);
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(50.0, 50.0))));
+        let r = cache.suggest(&sys, 1, &v(0.0, 0.0), &v(150.0, 50.0), &criteria);
+        assert!((r.position.get(0) - 50.0).abs() < 1e-6);
+        assert_eq!(cache.stats().misses, 2);
+    }
+
+    #[test]
+    fn unaffected_objects_keep_their_answers() {
+        let sys = canvas();
+        let mut cache = SuggestCache::new();
+        let criteria = RankingCriteria::default();
+        cache.suggest(&sys, 1, &v(0.0, 0.0), &v(10.0, 10.0), &criteria);
+        cache.suggest(&sys, 2, &v(0.0, 0.0), &v(20.0, 20.0), &criteria);
+        // Object 1 moves; object 2's entry is untouched.
+        cache.suggest(&sys, 1, &v(10.0, 10.0), &v(30.0, 30.0), &criteria);
+        cache.suggest(&sys, 2, &v(0.0, 0.0), &v(20.0, 20.0), &criteria);
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 3 });
+    }
+
+    #[test]
+    fn projection_repeats_hit_within_the_quantum() {
+        let sys = canvas();
+        let mut cache = ProjectionCache::new(4);
+        let options = ProjectionOptions::default();
+        let a = cache.project(&sys, &v(150.0, 50.0), &options);
+        // Bit-identical repeat and a sub-quantum wiggle both hit.
+        let b = cache.project(&sys, &v(150.0, 50.0), &options);
+        let c = cache.project(&sys, &(v(150.0, 50.0).add(&v(1e-8, 0.0))), &options);
+        assert_eq!(a.point, b.point);
+        assert_eq!(a.point, c.point);
+        assert_eq!(cache.stats(), CacheStats { hits: 2, misses: 1 });
+        // A real move misses.
+        cache.project(&sys, &v(150.0, 60.0), &options);
+        assert_eq!(cache.stats().misses, 2);
+    }
+
+    #[test]
+    fn projection_cache_sees_constraint_edits() {
+        let mut sys = canvas();
+        let mut cache = ProjectionCache::new(4);
+        let options = ProjectionOptions::default();
+        cache.project(&sys, &v(150.0, 50.0), &options);
+        // Same system shape, different parameters: the fingerprint
+        // changes and the stale answer is not reused.
+        sys.remove(0);
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(50.0, 50.0))));
+        let r = cache.project(&sys, &v(150.0, 50.0), &options);
+        assert!((r.point.get(0) - 50.0).abs() < 1e-6);
+        assert_eq!(cache.stats(), CacheStats { hits: 0, misses: 2 });
+    }
+
+    #[test]
+    fn projection_cache_evicts_least_recently_used() {
+        let sys = canvas();
+        let mut cache = ProjectionCache::new(2);
+        let options = ProjectionOptions::default();
+        cache.project(&sys, &v(110.0, 0.0), &options);
+        cache.project(&sys, &v(120.0, 0.0), &options);
+        // Refresh the first entry, then insert a third: the second is
+        // the one evicted.
+        cache.project(&sys, &v(110.0, 0.0), &options);
+        cache.project(&sys, &v(130.0, 0.0), &options);
+        assert_eq!(cache.len(), 2);
+        cache.project(&sys, &v(110.0, 0.0), &options);
+        cache.project(&sys, &v(120.0, 0.0), &options);
+        assert_eq!(cache.stats(), CacheStats { hits: 2, misses: 4 });
+    }
+
+    #[test]
+    fn invalidate_forces_recompute() {
+        let sys = canvas();
+        let mut cache = SuggestCache::new();
+        let criteria = RankingCriteria::default();
+        cache.suggest(&sys, 5, &v(0.0, 0.0), &v(10.0, 10.0), &criteria);
+        assert!(cache.invalidate(5));
+        assert!(!cache.invalidate(5));
+        cache.suggest(&sys, 5, &v(0.0, 0.0), &v(10.0, 10.0), &criteria);
+        assert_eq!(cache.stats().misses, 2);
+    }
+}
@@ -0,0 +1,2839 @@
+//! Constraints and the systems that hold them.
+//!
+//! A constraint describes a feasible set in configuration space. The
+//! engine never solves constraints symbolically; it only ever asks the
+//! three geometric questions on the [`Constraint`] trait: membership,
+//! projection, and signed distance. Everything else — Dykstra
+//! projection, suggestion search, ranking — is built from those.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::bounds::Bounds;
+use crate::linalg::Vector;
+use crate::rank::RankingCriteria;
+
+/// Shared handle to a constraint. Systems hold these so documents can
+/// share constraint objects without cloning their payloads.
+pub type ConstraintRef = Arc<dyn Constraint>;
+
+/// A feasible set in configuration space.
+///
+/// Sign convention for [`signed_distance`](Constraint::signed_distance):
+/// positive inside the feasible set (distance to the boundary, i.e. the
+/// slack available), negative outside (distance back to feasibility),
+/// zero on the boundary.
+pub trait Constraint: Send + Sync {
+    /// Dimension of the space this constraint lives in.
+    fn dim(&self) -> usize;
+
+    /// True if the point satisfies the constraint.
+    fn contains(&self, point: &Vector) -> bool;
+
+    /// Nearest feasible point. For points already feasible this is the
+    /// identity.
+    fn project(&self, point: &Vector) -> Vector;
+
+    /// Signed distance to the boundary of the feasible set.
+    ///
+    /// The default is conservative: it reports zero slack for feasible
+    /// points. Concrete constraints override this with an exact
+    /// interior distance where one is cheap to compute.
+    fn signed_distance(&self, point: &Vector) -> f64 {
+        if self.contains(point) {
+            0.0
+        } else {
+            -point.distance(&self.project(point))
+        }
+    }
+
+    /// Unit outward normal of the boundary nearest `point` — the
+    /// direction that leaves the feasible set — when it is cheap to
+    /// compute exactly. The default opts out, and consumers
+    /// ([`crate::sensitivity`], sliding resolution) fall back to
+    /// finite-difference gradients of the signed distance, so this is
+    /// purely an accuracy/cost upgrade for shapes that know their
+    /// normals. `None` is also the right answer at genuinely degenerate
+    /// points (an obstacle's centre, say).
+    fn boundary_normal(&self, point: &Vector) -> Option<Vector> {
+        let _ = point;
+        None
+    }
+
+    /// A feasible point well inside the constraint, when one is
+    /// cheaply known — strictly interior where the shape has an
+    /// interior at all. The suggestion search uses these as last-resort
+    /// anchors when every intent-local candidate is infeasible, and
+    /// relaxation passes use them as safe starting iterates. The
+    /// default opts out; shapes without a cheap answer stay out rather
+    /// than guessing.
+    fn interior_point(&self) -> Option<Vector> {
+        None
+    }
+
+    /// The concrete value behind the trait object. Lets authoring-time
+    /// passes ([`crate::analyze`], [`crate::simplify`]) and host apps
+    /// recover concrete types from a [`ConstraintRef`] — finding the
+    /// [`BoxConstraint`] in a system to read its bounds, say — where
+    /// the three geometric questions cannot expose the structure. The
+    /// runtime projection/suggestion path never downcasts.
+    fn as_any(&self) -> &dyn std::any::Any;
+
+    /// Hash of the constraint's exact structure (its parameters, bit
+    /// for bit), or `None` for types that opt out. Two constraints of
+    /// the same concrete type with equal keys are structurally
+    /// identical; [`ConstraintSystem::dedup`] uses this to drop the
+    /// duplicates UI code commonly produces. The default opts out, so
+    /// unhashable constraints are never deduplicated by mistake.
+    fn structural_key(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// Hashes a sequence of already-bit-converted parts, for
+/// `structural_key` implementations. Uses the crate's stable hasher
+/// ([`crate::fingerprint::Fingerprint64`]) rather than the standard
+/// one, so keys — and everything fingerprinted from them — agree
+/// across processes and toolchains.
+pub(crate) fn hash_structure(parts: impl IntoIterator<Item = u64>) -> u64 {
+    let mut fp = crate::fingerprint::Fingerprint64::new();
+    for part in parts {
+        fp.write_u64(part);
+    }
+    fp.finish()
+}
+
+/// The bit patterns of a vector's components, for structural hashing.
+pub(crate) fn vector_bits(v: &Vector) -> impl Iterator<Item = u64> + '_ {
+    v.as_slice().iter().map(|x| x.to_bits())
+}
+
+/// Validation helper for the `try_new` constructors: every component
+/// is a finite number.
+pub(crate) fn all_finite(v: &Vector) -> bool {
+    v.is_finite()
+}
+
+/// Both corners of `bounds` are finite.
+pub(crate) fn finite_bounds(bounds: &Bounds) -> bool {
+    all_finite(bounds.min()) && all_finite(bounds.max())
+}
+
+/// Keep the state inside an axis-aligned box.
+#[derive(Debug, Clone)]
+pub struct BoxConstraint {
+    bounds: Bounds,
+}
+
+impl BoxConstraint {
+    pub fn new(bounds: Bounds) -> Self {
+        BoxConstraint { bounds }
+    }
+
+    /// Validating constructor for untrusted document data: rejects
+    /// non-finite corners instead of letting them flow into
+    /// projections.
+    pub fn try_new(bounds: Bounds) -> Result<Self, crate::error::NewtonError> {
+        if !finite_bounds(&bounds) {
+            return Err(crate::error::NewtonError::InvalidParameter(
+                "box bounds must be finite",
+            ));
+        }
+        Ok(BoxConstraint::new(bounds))
+    }
+
+    pub fn bounds(&self) -> &Bounds {
+        &self.bounds
+    }
+}
+
+impl Constraint for BoxConstraint {
+    fn dim(&self) -> usize {
+        self.bounds.dim()
+    }
+
+    fn contains(&self, point: &Vector) -> bool {
+        self.bounds.contains(point)
+    }
+
+    fn project(&self, point: &Vector) -> Vector {
+        self.bounds.clamp(point)
+    }
+
+    fn signed_distance(&self, point: &Vector) -> f64 {
+        if self.bounds.contains(point) {
+            // Slack is the smallest distance to any face.
+            (0..self.dim())
+                .map(|i| {
+                    let lo = point.get(i) - self.bounds.min().get(i);
+                    let hi = self.bounds.max().get(i) - point.get(i);
+                    lo.min(hi)
+                })
+                .fold(f64::INFINITY, f64::min)
+        } else {
+            -point.distance(&self.bounds.clamp(point))
+        }
+    }
+
+    fn boundary_normal(&self, point: &Vector) -> Option<Vector> {
+        if !self.bounds.contains(point) {
+            // Outside: straight back along the clamp direction.
+            return point.sub(&self.bounds.clamp(point)).normalized();
+        }
+        // Inside: outward through the nearest face.
+        let surface = self.bounds.closest_point_on_boundary(point);
+        let mut normal = Vector::zeros(self.dim());
+        for i in 0..self.dim() {
+            if surface.get(i) != point.get(i) {
+                let toward_max = surface.get(i) >= self.bounds.max().get(i);
+                normal.set(i, if toward_max { 1.0 } else { -1.0 });
+                return Some(normal);
+            }
+        }
+        // The point already sits on a face; read the face off the
+        // bounds directly.
+        for i in 0..self.dim() {
+            if point.get(i) <= self.bounds.min().get(i) {
+                normal.set(i, -1.0);
+                return Some(normal);
+            }
+            if point.get(i) >= self.bounds.max().get(i) {
+                normal.set(i, 1.0);
+                return Some(normal);
+            }
+        }
+        None
+    }
+
+    fn interior_point(&self) -> Option<Vector> {
+        Some(self.bounds.center())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn structural_key(&self) -> Option<u64> {
+        Some(hash_structure(
+            vector_bits(self.bounds.min()).chain(vector_bits(self.bounds.max())),
+        ))
+    }
+}
+
+/// Keep the state in the halfspace `normal · x <= offset`.
+#[derive(Debug, Clone)]
+pub struct HalfspaceConstraint {
+    normal: Vector,
+    offset: f64,
+}
+
+impl HalfspaceConstraint {
+    /// Panics if `normal` is the zero vector.
+    pub fn new(normal: Vector, offset: f64) -> Self {
+        assert!(
+            normal.norm() > crate::EPSILON,
+            "HalfspaceConstraint requires a nonzero normal"
+        );
+        HalfspaceConstraint { normal, offset }
+    }
+
+    /// Validating constructor: a degenerate (zero or non-finite)
+    /// normal or a non-finite offset is an error, not a panic.
+    pub fn try_new(normal: Vector, offset: f64) -> Result<Self, crate::error::NewtonError> {
+        use crate::error::NewtonError;
+        if !all_finite(&normal) || !offset.is_finite() {
+            return Err(NewtonError::InvalidParameter(
+                "halfspace parameters must be finite",
+            ));
+        }
+        if normal.norm() <= crate::EPSILON {
+            return Err(NewtonError::InvalidParameter(
+                "halfspace normal must be nonzero",
+            ));
+        }
+        Ok(HalfspaceConstraint::new(normal, offset))
+    }
+
+    pub fn normal(&self) -> &Vector {
+        &self.normal
+    }
+
+    pub fn offset(&self) -> f64 {
+        self.offset
+    }
+}
+
+impl Constraint for HalfspaceConstraint {
+    fn dim(&self) -> usize {
+        self.normal.dim()
+    }
+
+    fn contains(&self, point: &Vector) -> bool {
+        self.normal.dot(point) <= self.offset + crate::EPSILON
+    }
+
+    fn project(&self, point: &Vector) -> Vector {
+        let excess = self.normal.dot(point) - self.offset;
+        if excess <= 0.0 {
+            point.clone()
+        } else {
+            let n2 = self.normal.dot(&self.normal);
+            point.sub(&self.normal.scale(excess / n2))
+        }
+    }
+
+    fn signed_distance(&self, point: &Vector) -> f64 {
+        (self.offset - self.normal.dot(point)) / self.normal.norm()
+    }
+
+    fn boundary_normal(&self, _point: &Vector) -> Option<Vector> {
+        self.normal.normalized()
+    }
+
+    fn interior_point(&self) -> Option<Vector> {
+        // One unit inside the boundary, along the normal through the
+        // origin.
+        let unit = self.normal.normalized()?;
+        let on_boundary = self.normal.scale(self.offset / self.normal.dot(&self.normal));
+        Some(on_boundary.sub(&unit))
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn structural_key(&self) -> Option<u64> {
+        Some(hash_structure(
+            vector_bits(&self.normal).chain([self.offset.to_bits()]),
+        ))
+    }
+}
+
+/// Keep the state *outside* an axis-aligned obstacle, optionally with a
+/// safety margin around it.
+///
+/// The feasible set is the complement of the (inflated) obstacle, which
+/// makes this constraint nonconvex: alternating projections may cycle,
+/// and the suggestion search treats it with candidate sampling rather
+/// than relying on projection alone.
+#[derive(Debug, Clone)]
+pub struct CollisionConstraint {
+    obstacle: Bounds,
+    margin: f64,
+}
+
+impl CollisionConstraint {
+    pub fn new(obstacle: Bounds) -> Self {
+        CollisionConstraint { obstacle, margin: 0.0 }
+    }
+
+    /// Obstacle inflated by `margin` on every side.
+    pub fn with_margin(obstacle: Bounds, margin: f64) -> Self {
+        assert!(margin >= 0.0, "collision margin must be non-negative");
+        CollisionConstraint { obstacle, margin }
+    }
+
+    /// Validating [`with_margin`](Self::with_margin): non-finite
+    /// corners, and negative or non-finite margins, are errors.
+    pub fn try_with_margin(
+        obstacle: Bounds,
+        margin: f64,
+    ) -> Result<Self, crate::error::NewtonError> {
+        use crate::error::NewtonError;
+        if !finite_bounds(&obstacle) {
+            return Err(NewtonError::InvalidParameter("obstacle bounds must be finite"));
+        }
+        if !margin.is_finite() || margin < 0.0 {
+            return Err(NewtonError::InvalidParameter(
+                "collision margin must be finite and non-negative",
+            ));
+        }
+        Ok(CollisionConstraint::with_margin(obstacle, margin))
+    }
+
+    pub fn obstacle(&self) -> &Bounds {
+        &self.obstacle
+    }
+
+    pub fn margin(&self) -> f64 {
+        self.margin
+    }
+
+    /// Obstacle bounds grown by the margin.
+    fn inflated(&self) -> Bounds {
+        if self.margin == 0.0 {
+            self.obstacle.clone()
+        } else {
+            self.obstacle.inflated(self.margin)
+        }
+    }
+}
+
+impl Constraint for CollisionConstraint {
+    fn dim(&self) -> usize {
+        self.obstacle.dim()
+    }
+
+    fn contains(&self, point: &Vector) -> bool {
+        !self.inflated().contains(point)
+    }
+
+    fn project(&self, point: &Vector) -> Vector {
+        let region = self.inflated();
+        if !region.contains(point) {
+            return point.clone();
+        }
+        // Push out through the nearest face. For an interior point of
+        // an axis-aligned box this *is* the true nearest exterior
+        // point: every boundary point is at least as far away as the
+        // nearest face plane, so an edge or corner target is never
+        // closer than the perpendicular face push.
+        region.closest_point_on_boundary(point)
+    }
+
+    fn signed_distance(&self, point: &Vector) -> f64 {
+        let region = self.inflated();
+        if region.contains(point) {
+            -point.distance(&self.project(point))
+        } else {
+            point.distance(&region.clamp(point))
+        }
+    }
+
+    fn boundary_normal(&self, point: &Vector) -> Option<Vector> {
+        let region = self.inflated();
+        if !region.contains(point) {
+            // Feasible side: leaving feasibility means moving toward
+            // the obstacle.
+            return region.clamp(point).sub(point).normalized();
+        }
+        // Inside the obstacle: toward the nearest face, which is the
+        // direction [`project`](Constraint::project) exits through —
+        // negated, since that exit direction *restores* feasibility.
+        region
+            .closest_point_on_boundary(point)
+            .sub(point)
+            .normalized()
+            .map(|n| n.scale(-1.0))
+    }
+
+    fn interior_point(&self) -> Option<Vector> {
+        // One unit diagonally outside the obstacle's minimum corner.
+        let region = self.inflated();
+        Some(region.min().sub(&Vector::new(vec![1.0; self.dim()])))
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn structural_key(&self) -> Option<u64> {
+        Some(hash_structure(
+            vector_bits(self.obstacle.min())
+                .chain(vector_bits(self.obstacle.max()))
+                .chain([self.margin.to_bits()]),
+        ))
+    }
+}
+
+/// Cells per indexed axis the [`MultiCollisionConstraint`] grid may
+/// use at most; beyond this, denser scenes just share cells.
+const MULTI_GRID_MAX_RES: usize = 64;
+
+/// Keep the state outside *many* axis-aligned obstacles, behind one
+/// constraint.
+///
+/// A dense scene modelled as one [`CollisionConstraint`] per obstacle
+/// pays twice: the constraint list grows with the scene, and every
+/// Dykstra sweep projects against every obstacle. This owns the whole
+/// obstacle set instead. Containment queries go through a uniform grid
+/// over the first two dimensions (the same convention the candidate
+/// ring uses), built once in the constructor; exterior distance is a
+/// scan, which is off the per-sweep hot path.
+///
+/// Like the single-obstacle constraint this is nonconvex. A point
+/// inside an obstacle projects out of it through the nearest face;
+/// where obstacles overlap, the exit point is handed along the chain
+/// until no obstacle strictly contains it.
+#[derive(Debug, Clone)]
+pub struct MultiCollisionConstraint {
+    obstacles: Vec<Bounds>,
+    /// Union of the obstacle footprints — the grid's extent.
+    grid_bounds: Bounds,
+    /// Cells per indexed axis.
+    grid_res: usize,
+    /// Obstacle indices per cell, row-major over the indexed axes.
+    cells: Vec<Vec<usize>>,
+}
+
+/// Grid cell of coordinate `x` along `axis`, clamped into range.
+fn grid_cell(grid: &Bounds, res: usize, axis: usize, x: f64) -> usize {
+    let lo = grid.min().get(axis);
+    let extent = grid.max().get(axis) - lo;
+    if extent <= 0.0 {
+        return 0;
+    }
+    (((x - lo) / extent * res as f64) as usize).min(res - 1)
+}
+
+impl MultiCollisionConstraint {
+    /// Panics if `obstacles` is empty or the obstacles disagree on
+    /// dimension.
+    pub fn new(obstacles: Vec<Bounds>) -> Self {
+        assert!(
+            !obstacles.is_empty(),
+            "MultiCollisionConstraint requires obstacles"
+        );
+        let d = obstacles[0].dim();
+        assert!(
+            obstacles.iter().all(|o| o.dim() == d),
+            "MultiCollisionConstraint obstacles must share a dimension"
+        );
+        let mut grid_bounds = obstacles[0].clone();
+        for o in &obstacles[1..] {
+            grid_bounds = grid_bounds.union(o);
+        }
+        // √n cells per axis puts roughly one obstacle in each 2D cell.
+        let grid_res =
+            ((obstacles.len() as f64).sqrt().ceil() as usize).clamp(1, MULTI_GRID_MAX_RES);
+        let axes = d.min(2) as u32;
+        let mut cells = vec![Vec::new(); grid_res.pow(axes)];
+        for (i, o) in obstacles.iter().enumerate() {
+            let x0 = grid_cell(&grid_bounds, grid_res, 0, o.min().get(0));
+            let x1 = grid_cell(&grid_bounds, grid_res, 0, o.max().get(0));
+            if axes == 1 {
+                for cell in &mut cells[x0..=x1] {
+                    cell.push(i);
+                }
+            } else {
+                let y0 = grid_cell(&grid_bounds, grid_res, 1, o.min().get(1));
+                let y1 = grid_cell(&grid_bounds, grid_res, 1, o.max().get(1));
+                for cy in y0..=y1 {
+                    for cell in &mut cells[cy * grid_res + x0..=cy * grid_res + x1] {
+                        cell.push(i);
+                    }
+                }
+            }
+        }
+        MultiCollisionConstraint {
+            obstacles,
+            grid_bounds,
+            grid_res,
+            cells,
+        }
+    }
+
+    /// Validating constructor: an empty set, mismatched dimensions,
+    /// and non-finite corners are errors.
+    pub fn try_new(obstacles: Vec<Bounds>) -> Result<Self, crate::error::NewtonError> {
+        use crate::error::NewtonError;
+        let Some(first) = obstacles.first() else {
+            return Err(NewtonError::InvalidParameter(
+                "multi-collision requires at least one obstacle",
+            ));
+        };
+        let d = first.dim();
+        for o in &obstacles {
+            if o.dim() != d {
+                return Err(NewtonError::DimensionMismatch {
+                    expected: d,
+                    actual: o.dim(),
+                });
+            }
+            if !finite_bounds(o) {
+                return Err(NewtonError::InvalidParameter("obstacle bounds must be finite"));
+            }
+        }
+        Ok(MultiCollisionConstraint::new(obstacles))
+    }
+
+    pub fn obstacles(&self) -> &[Bounds] {
+        &self.obstacles
+    }
+
+    /// The grid cell index of `point` (which must lie inside the grid
+    /// bounds along the indexed axes).
+    fn cell_of(&self, point: &Vector) -> usize {
+        let cx = grid_cell(&self.grid_bounds, self.grid_res, 0, point.get(0));
+        if self.dim() < 2 {
+            cx
+        } else {
+            let cy = grid_cell(&self.grid_bounds, self.grid_res, 1, point.get(1));
+            cy * self.grid_res + cx
+        }
+    }
+
+    /// Index of an obstacle containing `point` (boundary inclusive,
+    /// matching [`CollisionConstraint`]), via the grid.
+    fn containing_obstacle(&self, point: &Vector) -> Option<usize> {
+        if !self.grid_bounds.contains(point) {
+            return None;
+        }
+        self.cells[self.cell_of(point)]
+            .iter()
+            .copied()
+            .find(|&i| self.obstacles[i].contains(point))
+    }
+
+    /// Index of an obstacle whose *interior* holds `point`; boundary
+    /// points do not count, so projection chains terminate.
+    fn strictly_containing(&self, point: &Vector) -> Option<usize> {
+        if !self.grid_bounds.contains(point) {
+            return None;
+        }
+        self.cells[self.cell_of(point)].iter().copied().find(|&i| {
+            let o = &self.obstacles[i];
+            (0..o.dim())
+                .all(|d| point.get(d) > o.min().get(d) && point.get(d) < o.max().get(d))
+        })
+    }
+
+    /// Exterior distance to the nearest obstacle (zero on a boundary).
+    fn exterior_distance(&self, point: &Vector) -> f64 {
+        self.obstacles
+            .iter()
+            .map(|o| point.distance(&o.clamp(point)))
+            .fold(f64::INFINITY, f64::min)
+    }
+}
+
+impl Constraint for MultiCollisionConstraint {
+    fn dim(&self) -> usize {
+        self.obstacles[0].dim()
+    }
+
+    fn contains(&self, point: &Vector) -> bool {
+        self.containing_obstacle(point).is_none()
+    }
+
+    fn project(&self, point: &Vector) -> Vector {
+        let Some(first) = self.containing_obstacle(point) else {
+            return point.clone();
+        };
+        let mut out = self.obstacles[first].closest_point_on_boundary(point);
+        // Overlapping obstacles can hand the exit point onward; follow
+        // the chain, bounded by the obstacle count.
+        for _ in 0..self.obstacles.len() {
+            let Some(next) = self.strictly_containing(&out) else {
+                break;
+            };
+            out = self.obstacles[next].closest_point_on_boundary(&out);
+        }
+        out
+    }
+
+    fn signed_distance(&self, point: &Vector) -> f64 {
+        if self.containing_obstacle(point).is_some() {
+            -point.distance(&self.project(point))
+        } else {
+            self.exterior_distance(point)
+        }
+    }
+
+    fn boundary_normal(&self, point: &Vector) -> Option<Vector> {
+        match self.containing_obstacle(point) {
+            None => {
+                // Feasible side: toward the nearest obstacle.
+                let mut best: Option<Vector> = None;
+                let mut best_d = f64::INFINITY;
+                for o in &self.obstacles {
+                    let c = o.clamp(point);
+                    let d = point.distance(&c);
+                    if d < best_d {
+                        best_d = d;
+                        best = Some(c);
+                    }
+                }
+                best?.sub(point).normalized()
+            }
+            Some(i) => self.obstacles[i]
+                .closest_point_on_boundary(point)
+                .sub(point)
+                .normalized()
+                .map(|n| n.scale(-1.0)),
+        }
+    }
+
+    fn interior_point(&self) -> Option<Vector> {
+        // One unit diagonally outside the union's minimum corner.
+        Some(self.grid_bounds.min().sub(&Vector::new(vec![1.0; self.dim()])))
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn structural_key(&self) -> Option<u64> {
+        Some(hash_structure(self.obstacles.iter().flat_map(|o| {
+            vector_bits(o.min()).chain(vector_bits(o.max())).collect::<Vec<_>>()
+        })))
+    }
+}
+
+/// Keep the state inside a union of allowed boxes — the rooms of a
+/// floor plan, say. Explicitly nonconvex: projection goes to the
+/// nearest room, and [`portals`](FreeSpaceConstraint::portals) exposes
+/// the shared faces between rooms as candidate seeds so the suggestion
+/// search can route through doorways. Far cheaper than modelling the
+/// complement as a fleet of collision constraints.
+#[derive(Debug, Clone)]
+pub struct FreeSpaceConstraint {
+    rooms: Vec<Bounds>,
+}
+
+impl FreeSpaceConstraint {
+    /// Panics if `rooms` is empty or the rooms disagree on dimension.
+    pub fn new(rooms: Vec<Bounds>) -> Self {
+        assert!(!rooms.is_empty(), "FreeSpaceConstraint requires rooms");
+        let d = rooms[0].dim();
+        assert!(
+            rooms.iter().all(|r| r.dim() == d),
+            "FreeSpaceConstraint rooms must share a dimension"
+        );
+        FreeSpaceConstraint { rooms }
+    }
+
+    /// Validating constructor: empty room lists, mismatched
+    /// dimensions, and non-finite corners are errors.
+    pub fn try_new(rooms: Vec<Bounds>) -> Result<Self, crate::error::NewtonError> {
+        use crate::error::NewtonError;
+        let Some(first) = rooms.first() else {
+            return Err(NewtonError::InvalidParameter(
+                "free space requires at least one room",
+            ));
+        };
+        let d = first.dim();
+        for room in &rooms {
+            if room.dim() != d {
+                return Err(NewtonError::DimensionMismatch {
+                    expected: d,
+                    actual: room.dim(),
+                });
+            }
+            if !finite_bounds(room) {
+                return Err(NewtonError::InvalidParameter("room bounds must be finite"));
+            }
+        }
+        Ok(FreeSpaceConstraint::new(rooms))
+    }
+
+    pub fn rooms(&self) -> &[Bounds] {
+        &self.rooms
+    }
+
+    /// Index of the room whose clamp of `point` is nearest.
+    pub fn nearest_room(&self, point: &Vector) -> usize {
+        let mut best = 0;
+        let mut best_d = f64::INFINITY;
+        for (i, room) in self.rooms.iter().enumerate() {
+            let d = point.distance(&room.clamp(point));
+            if d < best_d {
+                best_d = d;
+                best = i;
+            }
+        }
+        best
+    }
+
+    /// Centres of the shared faces between touching rooms (the
+    /// doorways). Useful as seeds for
+    /// [`suggest_with_seeds`](crate::suggest::suggest_with_seeds) when
+    /// a drag must cross rooms.
+    pub fn portals(&self) -> Vec<Vector> {
+        let mut out = Vec::new();
+        for i in 0..self.rooms.len() {
+            for j in (i + 1)..self.rooms.len() {
+                if let Some(shared) = self.rooms[i].intersection(&self.rooms[j]) {
+                    out.push(shared.center());
+                }
+            }
+        }
+        out
+    }
+
+    /// Interior slack of `point` within `room` (negative outside).
+    fn room_slack(room: &Bounds, point: &Vector) -> f64 {
+        if room.contains(point) {
+            (0..room.dim())
+                .map(|i| {
+                    let lo = point.get(i) - room.min().get(i);
+                    let hi = room.max().get(i) - point.get(i);
+                    lo.min(hi)
+                })
+                .fold(f64::INFINITY, f64::min)
+        } else {
+            -point.distance(&room.clamp(point))
+        }
+    }
+}
+
+impl Constraint for FreeSpaceConstraint {
+    fn dim(&self) -> usize {
+        self.rooms[0].dim()
+    }
+
+    fn contains(&self, point: &Vector) -> bool {
+        self.rooms.iter().any(|r| r.contains(point))
+    }
+
+    fn project(&self, point: &Vector) -> Vector {
+        self.rooms[self.nearest_room(point)].clamp(point)
+    }
+
+    fn signed_distance(&self, point: &Vector) -> f64 {
+        // The union's slack is the best any room offers.
+        self.rooms
+            .iter()
+            .map(|r| Self::room_slack(r, point))
+            .fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    fn interior_point(&self) -> Option<Vector> {
+        Some(self.rooms[0].center())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn structural_key(&self) -> Option<u64> {
+        Some(hash_structure(self.rooms.iter().flat_map(|r| {
+            vector_bits(r.min()).chain(vector_bits(r.max())).collect::<Vec<_>>()
+        })))
+    }
+}
+
+/// Restrict the state to a finite set of points (snap targets, grid
+/// cells, enumerated poses). Inherently nonconvex.
+#[derive(Debug, Clone)]
+pub struct DiscreteConstraint {
+    points: Vec<Vector>,
+    /// k-d tree over `points`, built once at construction; the root is
+    /// node 0.
+    nodes: Vec<KdNode>,
+}
+
+/// A k-d tree node; children are indices into the node vector, with
+/// [`KD_NONE`] for absent subtrees. The split axis cycles with depth.
+#[derive(Debug, Clone)]
+struct KdNode {
+    /// Index into the owning constraint's point set.
+    point: usize,
+    left: usize,
+    right: usize,
+}
+
+const KD_NONE: usize = usize::MAX;
+
+/// Builds a balanced k-d subtree over `indices` by median split,
+/// returning the subtree's root node index. Ties on the split axis
+/// break by point index, so construction is deterministic.
+fn build_kd(points: &[Vector], indices: &mut [usize], depth: usize, nodes: &mut Vec<KdNode>) -> usize {
+    if indices.is_empty() {
+        return KD_NONE;
+    }
+    let axis = depth % points[0].dim();
+    indices.sort_unstable_by(|&a, &b| {
+        points[a]
+            .get(axis)
+            .total_cmp(&points[b].get(axis))
+            .then(a.cmp(&b))
+    });
+    let mid = indices.len() / 2;
+    let node = nodes.len();
+    nodes.push(KdNode {
+        point: indices[mid],
+        left: KD_NONE,
+        right: KD_NONE,
+    });
+    let (lo, rest) = indices.split_at_mut(mid);
+    let left = build_kd(points, lo, depth + 1, nodes);
+    let right = build_kd(points, &mut rest[1..], depth + 1, nodes);
+    nodes[node].left = left;
+    nodes[node].right = right;
+    node
+}
+
+impl DiscreteConstraint {
+    /// Panics if `points` is empty or the points disagree on dimension.
+    pub fn new(points: Vec<Vector>) -> Self {
+        assert!(!points.is_empty(), "DiscreteConstraint requires at least one point");
+        let d = points[0].dim();
+        assert!(
+            points.iter().all(|p| p.dim() == d),
+            "DiscreteConstraint points must share a dimension"
+        );
+        let mut indices: Vec<usize> = (0..points.len()).collect();
+        let mut nodes = Vec::with_capacity(points.len());
+        build_kd(&points, &mut indices, 0, &mut nodes);
+        DiscreteConstraint { points, nodes }
+    }
+
+    /// Validating constructor: an empty set, mismatched dimensions,
+    /// and non-finite points are errors.
+    pub fn try_new(points: Vec<Vector>) -> Result<Self, crate::error::NewtonError> {
+        use crate::error::NewtonError;
+        let Some(first) = points.first() else {
+            return Err(NewtonError::InvalidParameter(
+                "discrete set requires at least one point",
+            ));
+        };
+        let d = first.dim();
+        for p in &points {
+            if p.dim() != d {
+                return Err(NewtonError::DimensionMismatch {
+                    expected: d,
+                    actual: p.dim(),
+                });
+            }
+            if !all_finite(p) {
+                return Err(NewtonError::InvalidParameter(
+                    "discrete points must be finite",
+                ));
+            }
+        }
+        Ok(DiscreteConstraint::new(points))
+    }
+
+    pub fn points(&self) -> &[Vector] {
+        &self.points
+    }
+
+    /// Nearest allowed point to `point`, in O(log n) via the k-d tree
+    /// (the balanced median split bounds the depth). Distance ties
+    /// resolve to the lowest point index, exactly as the linear scan
+    /// this replaced did.
+    pub fn nearest(&self, point: &Vector) -> &Vector {
+        let mut best = (f64::INFINITY, 0);
+        self.kd_nearest(0, 0, point, &mut best);
+        &self.points[best.1]
+    }
+
+    /// Recursive nearest-neighbour descent: visit the near side first,
+    /// then the far side only when the splitting plane is within the
+    /// best distance found so far (inclusive, so equidistant ties are
+    /// never pruned away).
+    fn kd_nearest(&self, node: usize, depth: usize, query: &Vector, best: &mut (f64, usize)) {
+        if node == KD_NONE {
+            return;
+        }
+        let n = &self.nodes[node];
+        let d = self.points[n.point].distance(query);
+        if d < best.0 || (d == best.0 && n.point < best.1) {
+            *best = (d, n.point);
+        }
+        let axis = depth % self.dim();
+        let diff = query.get(axis) - self.points[n.point].get(axis);
+        let (near, far) = if diff < 0.0 {
+            (n.left, n.right)
+        } else {
+            (n.right, n.left)
+        };
+        self.kd_nearest(near, depth + 1, query, best);
+        if diff.abs() <= best.0 {
+            self.kd_nearest(far, depth + 1, query, best);
+        }
+    }
+}
+
+impl Constraint for DiscreteConstraint {
+    fn dim(&self) -> usize {
+        self.points[0].dim()
+    }
+
+    fn contains(&self, point: &Vector) -> bool {
+        self.nearest(point).distance(point) < crate::EPSILON
+    }
+
+    fn project(&self, point: &Vector) -> Vector {
+        self.nearest(point).clone()
+    }
+
+    fn signed_distance(&self, point: &Vector) -> f64 {
+        -self.nearest(point).distance(point)
+    }
+
+    fn interior_point(&self) -> Option<Vector> {
+        // No interior to speak of; the first member is still a valid
+        // anchor.
+        Some(self.points[0].clone())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn structural_key(&self) -> Option<u64> {
+        Some(hash_structure(
+            self.points.iter().flat_map(vector_bits),
+        ))
+    }
+}
+
+/// Restrict each dimension to its own finite list of allowed values —
+/// font sizes × line heights, say — without materializing the
+/// Cartesian product as [`DiscreteConstraint`] points. The product
+/// structure makes projection separable: each coordinate snaps to its
+/// own nearest allowed value independently, which *is* the nearest
+/// product member. Nonconvex like any discrete set.
+#[derive(Debug, Clone)]
+pub struct ProductDiscreteConstraint {
+    /// Allowed values per dimension, each list sorted ascending.
+    values: Vec<Vec<f64>>,
+}
+
+impl ProductDiscreteConstraint {
+    /// One allowed-value list per dimension; each list is sorted
+    /// internally. Panics when there are no dimensions or any
+    /// dimension has no values.
+    pub fn new(mut values: Vec<Vec<f64>>) -> Self {
+        assert!(
+            !values.is_empty(),
+            "ProductDiscreteConstraint requires at least one dimension"
+        );
+        assert!(
+            values.iter().all(|axis| !axis.is_empty()),
+            "every dimension needs at least one allowed value"
+        );
+        for axis in &mut values {
+            axis.sort_unstable_by(f64::total_cmp);
+        }
+        ProductDiscreteConstraint { values }
+    }
+
+    /// Validating constructor: missing dimensions, empty value lists,
+    /// and non-finite values are errors.
+    pub fn try_new(values: Vec<Vec<f64>>) -> Result<Self, crate::error::NewtonError> {
+        use crate::error::NewtonError;
+        if values.is_empty() {
+            return Err(NewtonError::InvalidParameter(
+                "product set requires at least one dimension",
+            ));
+        }
+        for axis in &values {
+            if axis.is_empty() {
+                return Err(NewtonError::InvalidParameter(
+                    "every dimension needs at least one allowed value",
+                ));
+            }
+            if !axis.iter().all(|v| v.is_finite()) {
+                return Err(NewtonError::InvalidParameter(
+                    "allowed values must be finite",
+                ));
+            }
+        }
+        Ok(ProductDiscreteConstraint::new(values))
+    }
+
+    /// The allowed values per dimension, sorted ascending.
+    pub fn values(&self) -> &[Vec<f64>] {
+        &self.values
+    }
+
+    /// Nearest allowed value to `x` on one axis; equidistant ties go
+    /// to the smaller value.
+    fn nearest_on_axis(axis: &[f64], x: f64) -> f64 {
+        let i = axis.partition_point(|&v| v < x);
+        if i == 0 {
+            return axis[0];
+        }
+        if i == axis.len() {
+            return axis[i - 1];
+        }
+        let (lo, hi) = (axis[i - 1], axis[i]);
+        if x - lo <= hi - x {
+            lo
+        } else {
+            hi
+        }
+    }
+
+    /// Nearest product member, assembled axis by axis.
+    pub fn nearest(&self, point: &Vector) -> Vector {
+        Vector::new(
+            self.values
+                .iter()
+                .zip(point.as_slice())
+                .map(|(axis, &x)| Self::nearest_on_axis(axis, x))
+                .collect(),
+        )
+    }
+}
+
+impl Constraint for ProductDiscreteConstraint {
+    fn dim(&self) -> usize {
+        self.values.len()
+    }
+
+    fn contains(&self, point: &Vector) -> bool {
+        self.nearest(point).distance(point) < crate::EPSILON
+    }
+
+    fn project(&self, point: &Vector) -> Vector {
+        self.nearest(point)
+    }
+
+    fn signed_distance(&self, point: &Vector) -> f64 {
+        -self.nearest(point).distance(point)
+    }
+
+    fn interior_point(&self) -> Option<Vector> {
+        // No interior to speak of; the smallest member anchors.
+        Some(Vector::new(self.values.iter().map(|axis| axis[0]).collect()))
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn structural_key(&self) -> Option<u64> {
+        // Lengths delimit the per-axis lists so distinct shapes cannot
+        // collide by concatenation.
+        Some(hash_structure(self.values.iter().flat_map(|axis| {
+            std::iter::once(axis.len() as u64)
+                .chain(axis.iter().map(|v| v.to_bits()))
+                .collect::<Vec<_>>()
+        })))
+    }
+}
+
+/// Restrict every coordinate to integer multiples of a grid step —
+/// the output lattice of a renderer that only addresses quarter-pixel
+/// positions, say. Composed onto a system by
+/// [`suggest_quantized`](crate::suggest::suggest_quantized) so that
+/// "feasible" already means "representable on the output grid", rather
+/// than hosts rounding afterwards and breaking validity. Nonconvex in
+/// the same way [`DiscreteConstraint`] is, which the suggestion layer's
+/// candidate sampling handles.
+#[derive(Debug, Clone)]
+pub struct LatticeConstraint {
+    dim: usize,
+    step: f64,
+}
+
+impl LatticeConstraint {
+    /// Panics unless the step is strictly positive.
+    pub fn new(dim: usize, step: f64) -> Self {
+        assert!(step > 0.0, "lattice step must be positive");
+        LatticeConstraint { dim, step }
+    }
+
+    /// Validating constructor: the step must be finite and strictly
+    /// positive.
+    pub fn try_new(dim: usize, step: f64) -> Result<Self, crate::error::NewtonError> {
+        if !step.is_finite() || step <= 0.0 {
+            return Err(crate::error::NewtonError::InvalidParameter(
+                "lattice step must be finite and positive",
+            ));
+        }
+        Ok(LatticeConstraint::new(dim, step))
+    }
+
+    pub fn step(&self) -> f64 {
+        self.step
+    }
+}
+
+impl Constraint for LatticeConstraint {
+    fn dim(&self) -> usize {
+        self.dim
+    }
+
+    fn contains(&self, point: &Vector) -> bool {
+        point.distance(&self.project(point)) < crate::EPSILON
+    }
+
+    fn project(&self, point: &Vector) -> Vector {
+        Vector::new(
+            point
+                .as_slice()
+                .iter()
+                .map(|x| (x / self.step).round() * self.step)
+                .collect(),
+        )
+    }
+
+    fn signed_distance(&self, point: &Vector) -> f64 {
+        -point.distance(&self.project(point))
+    }
+
+    fn interior_point(&self) -> Option<Vector> {
+        Some(Vector::zeros(self.dim))
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn structural_key(&self) -> Option<u64> {
+        Some(hash_structure([self.dim as u64, self.step.to_bits()]))
+    }
+}
+
+/// Keeps the state within a motion budget of a reference state —
+/// typically the previous frame's position, so one suggestion can never
+/// teleport an object further than the device can animate.
+///
+/// The feasible set is the closed ball of radius `max_change` around
+/// `previous`; projection is the exact radial clamp. Sessions
+/// ([`crate::suggest::DragSession`]) construct one of these per frame
+/// rather than storing it in the document's system.
+#[derive(Debug, Clone)]
+pub struct RateLimitConstraint {
+    previous: Vector,
+    max_change: f64,
+}
+
+impl RateLimitConstraint {
+    /// Panics if the budget is negative.
+    pub fn new(previous: Vector, max_change: f64) -> Self {
+        assert!(max_change >= 0.0, "motion budget must be non-negative");
+        RateLimitConstraint { previous, max_change }
+    }
+
+    /// Validating constructor: the anchor must be finite and the
+    /// budget finite and non-negative.
+    pub fn try_new(previous: Vector, max_change: f64) -> Result<Self, crate::error::NewtonError> {
+        use crate::error::NewtonError;
+        if !all_finite(&previous) {
+            return Err(NewtonError::InvalidParameter(
+                "rate limit anchor must be finite",
+            ));
+        }
+        if !max_change.is_finite() || max_change < 0.0 {
+            return Err(NewtonError::InvalidParameter(
+                "motion budget must be finite and non-negative",
+            ));
+        }
+        Ok(RateLimitConstraint::new(previous, max_change))
+    }
+
+    pub fn previous(&self) -> &Vector {
+        &self.previous
+    }
+
+    pub fn max_change(&self) -> f64 {
+        self.max_change
+    }
+}
+
+impl Constraint for RateLimitConstraint {
+    fn dim(&self) -> usize {
+        self.previous.dim()
+    }
+
+    fn contains(&self, point: &Vector) -> bool {
+        point.distance(&self.previous) <= self.max_change + crate::EPSILON
+    }
+
+    fn project(&self, point: &Vector) -> Vector {
+        let offset = point.sub(&self.previous);
+        let d = offset.norm();
+        if d <= self.max_change || d < crate::EPSILON {
+            point.clone()
+        } else {
+            self.previous.add(&offset.scale(self.max_change / d))
+        }
+    }
+
+    fn signed_distance(&self, point: &Vector) -> f64 {
+        self.max_change - point.distance(&self.previous)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn structural_key(&self) -> Option<u64> {
+        Some(hash_structure(
+            vector_bits(&self.previous).chain([self.max_change.to_bits()]),
+        ))
+    }
+}
+
+/// Number of inward correction steps a robust projection may take.
+const ROBUST_PROJECT_STEPS: usize = 8;
+/// Step used for finite-difference distance gradients.
+const GRADIENT_STEP: f64 = 1e-5;
+
+/// Finite-difference gradient of a constraint's signed distance at
+/// `point` — the unit direction of steepest slack increase (toward the
+/// interior). `None` where the gradient is degenerate.
+pub(crate) fn slack_gradient(constraint: &dyn Constraint, point: &Vector) -> Option<Vector> {
+    let mut gradient = Vector::zeros(point.dim());
+    for i in 0..point.dim() {
+        let mut forward = point.clone();
+        forward.set(i, point.get(i) + GRADIENT_STEP);
+        let mut backward = point.clone();
+        backward.set(i, point.get(i) - GRADIENT_STEP);
+        gradient.set(
+            i,
+            (constraint.signed_distance(&forward) - constraint.signed_distance(&backward))
+                / (2.0 * GRADIENT_STEP),
+        );
+    }
+    gradient.normalized()
+}
+
+/// Shrinks another constraint's feasible set by a safety margin: only
+/// points at least `delta` *inside* the wrapped constraint count as
+/// feasible.
+///
+/// Suggestions landing exactly on a boundary get invalidated by the
+/// host app's own rounding; wrapping every constraint in one of these
+/// (see [`ConstraintSystem::shrunk`]) keeps answers strictly interior.
+/// Projection walks inward along the slack gradient, which is exact for
+/// smooth boundaries and a close approximation near corners.
+#[derive(Clone)]
+pub struct RobustConstraint {
+    inner: ConstraintRef,
+    delta: f64,
+}
+
+impl RobustConstraint {
+    /// Panics on a negative margin.
+    pub fn new(inner: ConstraintRef, delta: f64) -> Self {
+        assert!(delta >= 0.0, "robustness margin must be non-negative");
+        RobustConstraint { inner, delta }
+    }
+
+    /// Validating constructor: the margin must be finite and
+    /// non-negative.
+    pub fn try_new(inner: ConstraintRef, delta: f64) -> Result<Self, crate::error::NewtonError> {
+        if !delta.is_finite() || delta < 0.0 {
+            return Err(crate::error::NewtonError::InvalidParameter(
+                "robustness margin must be finite and non-negative",
+            ));
+        }
+        Ok(RobustConstraint::new(inner, delta))
+    }
+
+    pub fn delta(&self) -> f64 {
+        self.delta
+    }
+
+    pub fn inner(&self) -> &ConstraintRef {
+        &self.inner
+    }
+}
+
+impl Constraint for RobustConstraint {
+    fn dim(&self) -> usize {
+        self.inner.dim()
+    }
+
+    fn contains(&self, point: &Vector) -> bool {
+        self.inner.signed_distance(point) >= self.delta - crate::EPSILON
+    }
+
+    fn project(&self, point: &Vector) -> Vector {
+        if self.contains(point) {
+            return point.clone();
+        }
+        let mut x = self.inner.project(point);
+        for _ in 0..ROBUST_PROJECT_STEPS {
+            let slack = self.inner.signed_distance(&x);
+            if slack >= self.delta - crate::EPSILON {
+                break;
+            }
+            let Some(inward) = slack_gradient(self.inner.as_ref(), &x) else {
+                break;
+            };
+            x = x.add(&inward.scale(self.delta - slack));
+        }
+        x
+    }
+
+    fn signed_distance(&self, point: &Vector) -> f64 {
+        self.inner.signed_distance(point) - self.delta
+    }
+
+    fn boundary_normal(&self, point: &Vector) -> Option<Vector> {
+        // The shrunk boundary is the inner boundary offset inward;
+        // its normal direction is unchanged.
+        self.inner.boundary_normal(point)
+    }
+
+    fn interior_point(&self) -> Option<Vector> {
+        // The inner anchor is only valid if it clears the margin too.
+        self.inner.interior_point().filter(|p| self.contains(p))
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn structural_key(&self) -> Option<u64> {
+        let inner = self.inner.structural_key()?;
+        Some(hash_structure([inner, self.delta.to_bits()]))
+    }
+}
+
+/// First-class padding around an inner constraint: the feasible set
+/// tightened by `margin` on every side — which, for obstacle-style
+/// constraints whose feasible set is a complement, means the keep-out
+/// region grows. Convexity is preserved: a padded box is a box.
+///
+/// [`RobustConstraint`] reaches the same set by walking the slack
+/// gradient, which is approximate near corners. `WithMargin` instead
+/// resolves exact padded geometry at construction for the axis-aligned
+/// shapes — a [`BoxConstraint`] deflates its bounds, a
+/// [`CollisionConstraint`] folds the padding into its own margin — and
+/// falls back to the gradient walk for everything else. A margin large
+/// enough to collapse a box also falls back, yielding an everywhere-
+/// infeasible constraint rather than a panic.
+#[derive(Clone)]
+pub struct WithMargin {
+    inner: ConstraintRef,
+    margin: f64,
+    /// The padded geometry all queries delegate to, chosen once at
+    /// construction.
+    resolved: ConstraintRef,
+}
+
+impl WithMargin {
+    /// Pads `inner` by `margin`. Panics on a negative or non-finite
+    /// margin.
+    pub fn new(inner: ConstraintRef, margin: f64) -> Self {
+        assert!(
+            margin.is_finite() && margin >= 0.0,
+            "padding margin must be finite and non-negative"
+        );
+        let resolved: ConstraintRef =
+            if let Some(b) = inner.as_any().downcast_ref::<BoxConstraint>() {
+                let bounds = b.bounds();
+                let fits = (0..bounds.dim())
+                    .all(|d| bounds.max().get(d) - bounds.min().get(d) >= 2.0 * margin);
+                if fits {
+                    Arc::new(BoxConstraint::new(bounds.inflated(-margin)))
+                } else {
+                    Arc::new(RobustConstraint::new(inner.clone(), margin))
+                }
+            } else if let Some(c) = inner.as_any().downcast_ref::<CollisionConstraint>() {
+                Arc::new(CollisionConstraint::with_margin(
+                    c.obstacle().clone(),
+                    c.margin() + margin,
+                ))
+            } else {
+                Arc::new(RobustConstraint::new(inner.clone(), margin))
+            };
+        WithMargin {
+            inner,
+            margin,
+            resolved,
+        }
+    }
+
+    /// Validating constructor: the margin must be finite and
+    /// non-negative.
+    pub fn try_new(inner: ConstraintRef, margin: f64) -> Result<Self, crate::error::NewtonError> {
+        if !margin.is_finite() || margin < 0.0 {
+            return Err(crate::error::NewtonError::InvalidParameter(
+                "padding margin must be finite and non-negative",
+            ));
+        }
+        Ok(WithMargin::new(inner, margin))
+    }
+
+    pub fn inner(&self) -> &ConstraintRef {
+        &self.inner
+    }
+
+    pub fn margin(&self) -> f64 {
+        self.margin
+    }
+}
+
+impl Constraint for WithMargin {
+    fn dim(&self) -> usize {
+        self.inner.dim()
+    }
+
+    fn contains(&self, point: &Vector) -> bool {
+        self.resolved.contains(point)
+    }
+
+    fn project(&self, point: &Vector) -> Vector {
+        self.resolved.project(point)
+    }
+
+    fn signed_distance(&self, point: &Vector) -> f64 {
+        self.resolved.signed_distance(point)
+    }
+
+    fn boundary_normal(&self, point: &Vector) -> Option<Vector> {
+        self.resolved.boundary_normal(point)
+    }
+
+    fn interior_point(&self) -> Option<Vector> {
+        self.resolved.interior_point()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn structural_key(&self) -> Option<u64> {
+        let inner = self.inner.structural_key()?;
+        Some(hash_structure([inner, self.margin.to_bits()]))
+    }
+}
+
+/// Gates an inner constraint behind an activation region: outside the
+/// region the constraint imposes nothing.
+///
+/// Snap guides are the motivating case — an alignment guide should
+/// engage only within a few pixels of its line, not bend a drag from
+/// across the canvas. Wrapping the guide in an `ActivationConstraint`
+/// documents that intent in the system itself and lets
+/// [`ConstraintSystem::prune_inactive`] drop out-of-range constraints
+/// before a gesture's projections run.
+///
+/// A violated point inside the region projects onto the inner
+/// constraint rather than out of the region, so resolution follows the
+/// constraint's intent instead of escaping its range.
+#[derive(Clone)]
+pub struct ActivationConstraint {
+    inner: ConstraintRef,
+    region: Bounds,
+}
+
+impl ActivationConstraint {
+    /// Gates `inner` behind `region`. Panics when the region's
+    /// dimension disagrees with the inner constraint's.
+    pub fn new(inner: ConstraintRef, region: Bounds) -> Self {
+        assert_eq!(
+            region.dim(),
+            inner.dim(),
+            "activation region dimension does not match constraint"
+        );
+        ActivationConstraint { inner, region }
+    }
+
+    /// Validating constructor: the region must match the inner
+    /// constraint's dimension and have finite corners.
+    pub fn try_new(inner: ConstraintRef, region: Bounds) -> Result<Self, crate::error::NewtonError> {
+        if region.dim() != inner.dim() {
+            return Err(crate::error::NewtonError::DimensionMismatch {
+                expected: inner.dim(),
+                actual: region.dim(),
+            });
+        }
+        if !finite_bounds(&region) {
+            return Err(crate::error::NewtonError::InvalidParameter(
+                "activation region must be finite",
+            ));
+        }
+        Ok(ActivationConstraint::new(inner, region))
+    }
+
+    pub fn inner(&self) -> &ConstraintRef {
+        &self.inner
+    }
+
+    pub fn region(&self) -> &Bounds {
+        &self.region
+    }
+
+    /// Whether the constraint is active at `point`.
+    pub fn active_at(&self, point: &Vector) -> bool {
+        self.region.contains(point)
+    }
+}
+
+impl Constraint for ActivationConstraint {
+    fn dim(&self) -> usize {
+        self.inner.dim()
+    }
+
+    fn contains(&self, point: &Vector) -> bool {
+        !self.region.contains(point) || self.inner.contains(point)
+    }
+
+    fn project(&self, point: &Vector) -> Vector {
+        if self.contains(point) {
+            return point.clone();
+        }
+        self.inner.project(point)
+    }
+
+    fn signed_distance(&self, point: &Vector) -> f64 {
+        if !self.region.contains(point) {
+            // Satisfied by inactivity: slack is at least the walk to
+            // the region, and more if the inner constraint would be
+            // satisfied there anyway.
+            return self
+                .inner
+                .signed_distance(point)
+                .max(point.distance(&self.region.clamp(point)));
+        }
+        let inner = self.inner.signed_distance(point);
+        if inner >= 0.0 {
+            inner
+        } else {
+            // Violated, but stepping out of the region also restores
+            // feasibility — whichever exit is nearer bounds the depth.
+            let exit = point.distance(&self.region.closest_point_on_boundary(point));
+            -((-inner).min(exit))
+        }
+    }
+
+    fn boundary_normal(&self, point: &Vector) -> Option<Vector> {
+        if self.region.contains(point) {
+            return self.inner.boundary_normal(point);
+        }
+        // Feasible by inactivity: violation lies toward the region.
+        self.region.clamp(point).sub(point).normalized()
+    }
+
+    fn interior_point(&self) -> Option<Vector> {
+        self.inner.interior_point().filter(|p| self.contains(p))
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn structural_key(&self) -> Option<u64> {
+        let inner = self.inner.structural_key()?;
+        Some(hash_structure(
+            [inner]
+                .into_iter()
+                .chain(vector_bits(self.region.min()))
+                .chain(vector_bits(self.region.max())),
+        ))
+    }
+}
+
+/// Normalises an angle in radians to `[0, 2π)`.
+pub fn wrap_angle(theta: f64) -> f64 {
+    let tau = std::f64::consts::TAU;
+    let w = theta % tau;
+    if w < 0.0 {
+        w + tau
+    } else {
+        w
+    }
+}
+
+/// Shortest signed angular difference `a - b`, in `(-π, π]`.
+pub fn angle_difference(a: f64, b: f64) -> f64 {
+    let tau = std::f64::consts::TAU;
+    let mut d = (a - b) % tau;
+    if d > std::f64::consts::PI {
+        d -= tau;
+    } else if d <= -std::f64::consts::PI {
+        d += tau;
+    }
+    d
+}
+
+/// Restricts one dimension, interpreted as an angle in radians, to an
+/// arc — with wrap-around.
+///
+/// Box bounds on a heading behave wrongly at the 360°→0° seam: a
+/// heading of 359° clamped to `[0°, 10°]` jumps the long way round.
+/// `AngleConstraint` measures along the circle instead: membership and
+/// projection use the arc from `start` counter-clockwise to `end`, and
+/// distances are shortest-path angular distances.
+#[derive(Debug, Clone)]
+pub struct AngleConstraint {
+    dim: usize,
+    axis: usize,
+    start: f64,
+    /// Arc width in radians, in `[0, 2π]`.
+    width: f64,
+}
+
+impl AngleConstraint {
+    /// Allows the arc from `start` counter-clockwise through `width`
+    /// radians, on dimension `axis` of a `dim`-dimensional space.
+    /// Panics if the axis is out of range or the width is outside
+    /// `[0, 2π]`.
+    pub fn new(dim: usize, axis: usize, start: f64, width: f64) -> Self {
+        assert!(axis < dim, "axis out of range");
+        assert!(
+            (0.0..=std::f64::consts::TAU).contains(&width),
+            "arc width must lie in [0, 2π]"
+        );
+        AngleConstraint {
+            dim,
+            axis,
+            start: wrap_angle(start),
+            width,
+        }
+    }
+
+    /// Validating constructor for untrusted document data: rejects an
+    /// out-of-range axis, a non-finite start, or a width outside
+    /// `[0, 2π]` instead of panicking.
+    pub fn try_new(
+        dim: usize,
+        axis: usize,
+        start: f64,
+        width: f64,
+    ) -> Result<Self, crate::error::NewtonError> {
+        if axis >= dim {
+            return Err(crate::error::NewtonError::InvalidParameter(
+                "angle axis must be within the space's dimension",
+            ));
+        }
+        if !start.is_finite() {
+            return Err(crate::error::NewtonError::InvalidParameter(
+                "arc start must be finite",
+            ));
+        }
+        if !(0.0..=std::f64::consts::TAU).contains(&width) {
+            return Err(crate::error::NewtonError::InvalidParameter(
+                "arc width must lie in [0, 2π]",
+            ));
+        }
+        Ok(AngleConstraint::new(dim, axis, start, width))
+    }
+
+    /// Angular offset of `theta` past `start`, measured
+    /// counter-clockwise in `[0, 2π)`.
+    fn offset_into_arc(&self, theta: f64) -> f64 {
+        wrap_angle(theta - self.start)
+    }
+
+    /// The allowed angle nearest to `theta` by shortest-path distance.
+    pub fn clamp_angle(&self, theta: f64) -> f64 {
+        let off = self.offset_into_arc(theta);
+        if off <= self.width {
+            return theta;
+        }
+        // Outside the arc: snap to whichever endpoint is closer along
+        // the circle.
+        let to_start = angle_difference(theta, self.start).abs();
+        let end = self.start + self.width;
+        let to_end = angle_difference(theta, end).abs();
+        if to_start < to_end {
+            self.start
+        } else {
+            wrap_angle(end)
+        }
+    }
+}
+
+impl Constraint for AngleConstraint {
+    fn dim(&self) -> usize {
+        self.dim
+    }
+
+    fn contains(&self, point: &Vector) -> bool {
+        self.offset_into_arc(point.get(self.axis)) <= self.width + crate::EPSILON
+    }
+
+    fn project(&self, point: &Vector) -> Vector {
+        let mut out = point.clone();
+        out.set(self.axis, self.clamp_angle(point.get(self.axis)));
+        out
+    }
+
+    fn signed_distance(&self, point: &Vector) -> f64 {
+        // Angular units; callers mixing angles with lengths should
+        // precondition (see the suggestion layer's scaling hooks).
+        let theta = point.get(self.axis);
+        let off = self.offset_into_arc(theta);
+        if off <= self.width {
+            // Slack to the nearer end of the arc.
+            off.min(self.width - off)
+        } else {
+            let end = self.start + self.width;
+            -angle_difference(theta, self.start)
+                .abs()
+                .min(angle_difference(theta, end).abs())
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn structural_key(&self) -> Option<u64> {
+        Some(hash_structure([
+            self.dim as u64,
+            self.axis as u64,
+            self.start.to_bits(),
+            self.width.to_bits(),
+        ]))
+    }
+}
+
+/// One constraint's verdict on a point, from
+/// [`ConstraintSystem::diagnose`].
+#[derive(Debug, Clone)]
+pub struct ConstraintStatus {
+    /// Index of the constraint in the system.
+    pub index: usize,
+    /// Whether the point satisfies this constraint.
+    pub satisfied: bool,
+    /// Slack (positive) or violation depth (negative).
+    pub signed_distance: f64,
+    /// This constraint's own nearest feasible point (the point itself
+    /// when satisfied) — not the joint projection over the system.
+    pub nearest: Vector,
+}
+
+/// A collection of constraints over a common configuration space.
+///
+/// This is the document-level object: one system per manipulable
+/// object (or shared by a group), mutated as the document changes and
+/// queried every input event.
+pub struct ConstraintSystem {
+    dim: usize,
+    constraints: Vec<ConstraintRef>,
+    profiles: HashMap<String, RankingCriteria>,
+    search_policy: crate::suggest::SearchPolicy,
+    numeric_policy: NumericPolicy,
+    angular_dims: Vec<usize>,
+    revision: u64,
+}
+
+/// Runtime numeric thresholds, scaled to the problem's coordinate
+/// magnitude.
+///
+/// The crate-wide defaults ([`crate::EPSILON`], the 1e-7 projection
+/// tolerance) assume coordinates of order one to a few thousand — a
+/// pixel canvas. A CAD document in nanometres has legitimate features
+/// smaller than the default tolerance, and an astronomy-scale document
+/// has rounding noise far larger than it; both need the thresholds
+/// scaled, not replaced. Stored per system via
+/// [`ConstraintSystem::set_numeric_policy`]; system-level convergence
+/// and feasibility checks consult it, while each constraint's own
+/// `contains` slack remains [`crate::EPSILON`].
+#[derive(Debug, Clone)]
+pub struct NumericPolicy {
+    scale: f64,
+}
+
+impl Default for NumericPolicy {
+    fn default() -> Self {
+        NumericPolicy { scale: 1.0 }
+    }
+}
+
+impl NumericPolicy {
+    /// A policy for a problem whose coordinates are of order
+    /// `magnitude` (e.g. `1e12` for astronomical units, `1e-10` for
+    /// ångström-scale CAD). Panics unless strictly positive and
+    /// finite.
+    pub fn for_magnitude(magnitude: f64) -> Self {
+        assert!(
+            magnitude.is_finite() && magnitude > 0.0,
+            "coordinate magnitude must be positive and finite"
+        );
+        NumericPolicy { scale: magnitude }
+    }
+
+    /// The coordinate magnitude the thresholds are scaled for.
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Degeneracy/membership threshold: [`crate::EPSILON`] scaled to
+    /// the coordinate magnitude.
+    pub fn epsilon(&self) -> f64 {
+        crate::EPSILON * self.scale
+    }
+
+    /// Projection convergence threshold: the default
+    /// [`crate::project::ProjectionOptions`] tolerance scaled to the
+    /// coordinate magnitude.
+    pub fn tolerance(&self) -> f64 {
+        1e-7 * self.scale
+    }
+}
+
+impl ConstraintSystem {
+    /// Creates an empty system over a `dim`-dimensional space.
+    pub fn new(dim: usize) -> Self {
+        ConstraintSystem {
+            dim,
+            constraints: Vec::new(),
+            profiles: HashMap::new(),
+            search_policy: crate::suggest::SearchPolicy::default(),
+            numeric_policy: NumericPolicy::default(),
+            angular_dims: Vec::new(),
+            revision: 0,
+        }
+    }
+
+    /// Dimension of the configuration space.
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    /// Monotonic change counter, bumped by every mutation of the
+    /// constraint set. Caches ([`crate::cache::SuggestCache`]) compare
+    /// revisions instead of diffing constraints to decide whether
+    /// stored answers are still valid.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Adds a constraint by value. Panics on dimension mismatch.
+    pub fn add(&mut self, constraint: impl Constraint + 'static) {
+        self.add_ref(Arc::new(constraint));
+    }
+
+    /// Adds a shared constraint handle. Panics on dimension mismatch.
+    pub fn add_ref(&mut self, constraint: ConstraintRef) {
+        assert_eq!(
+            constraint.dim(),
+            self.dim,
+            "constraint dimension does not match system"
+        );
+        self.constraints.push(constraint);
+        self.revision += 1;
+    }
+
+    /// Removes the constraint at `index`, returning its handle. Panics
+    /// when the index is out of range, matching `Vec::remove`.
+    pub fn remove(&mut self, index: usize) -> ConstraintRef {
+        let removed = self.constraints.remove(index);
+        self.revision += 1;
+        removed
+    }
+
+    /// The constraints in insertion order.
+    pub fn constraints(&self) -> &[ConstraintRef] {
+        &self.constraints
+    }
+
+    /// Number of constraints.
+    pub fn len(&self) -> usize {
+        self.constraints.len()
+    }
+
+    /// True if the system has no constraints.
+    pub fn is_empty(&self) -> bool {
+        self.constraints.is_empty()
+    }
+
+    /// True if every constraint is satisfied at `point`.
+    pub fn is_feasible(&self, point: &Vector) -> bool {
+        self.constraints.iter().all(|c| c.contains(point))
+    }
+
+    /// Stores a named ranking profile (e.g. `"precision-mode"`,
+    /// `"fast-drag"`) with the document, replacing any previous profile
+    /// of that name. Behaviour tuning then lives with the constraint
+    /// system rather than scattered through application code.
+    pub fn set_profile(&mut self, name: impl Into<String>, criteria: RankingCriteria) {
+        self.profiles.insert(name.into(), criteria);
+    }
+
+    /// Looks up a stored ranking profile.
+    pub fn profile(&self, name: &str) -> Option<&RankingCriteria> {
+        self.profiles.get(name)
+    }
+
+    /// The search policy every suggest entry point uses against this
+    /// system.
+    pub fn search_policy(&self) -> &crate::suggest::SearchPolicy {
+        &self.search_policy
+    }
+
+    /// Replaces the search policy. Bumps the revision: a policy change
+    /// invalidates cached suggestions just as a constraint edit does.
+    pub fn set_search_policy(&mut self, policy: crate::suggest::SearchPolicy) {
+        self.search_policy = policy;
+        self.revision += 1;
+    }
+
+    /// The numeric thresholds used against this system.
+    pub fn numeric_policy(&self) -> &NumericPolicy {
+        &self.numeric_policy
+    }
+
+    /// Replaces the numeric policy. Bumps the revision: a threshold
+    /// change can flip cached answers between feasible and not.
+    pub fn set_numeric_policy(&mut self, policy: NumericPolicy) {
+        self.numeric_policy = policy;
+        self.revision += 1;
+    }
+
+    /// The dimensions declared angular (periodic with period `2π`),
+    /// in ascending order. Empty by default: every dimension is linear.
+    pub fn angular_dims(&self) -> &[usize] {
+        &self.angular_dims
+    }
+
+    /// Declares which dimensions are angular, so suggestion distances
+    /// along them wrap: a rotation from 359° to 1° reads as 2° of
+    /// motion, not 358°. Bumps the revision, since cached answers were
+    /// measured with the old metric. Panics on an out-of-range or
+    /// repeated dimension.
+    pub fn set_angular_dims(&mut self, mut dims: Vec<usize>) {
+        dims.sort_unstable();
+        for pair in dims.windows(2) {
+            assert!(pair[0] != pair[1], "angular dimension listed twice");
+        }
+        for &d in &dims {
+            assert!(d < self.dim, "angular dimension out of range");
+        }
+        self.angular_dims = dims;
+        self.revision += 1;
+    }
+
+    /// Removes a stored ranking profile, returning it if present.
+    pub fn remove_profile(&mut self, name: &str) -> Option<RankingCriteria> {
+        self.profiles.remove(name)
+    }
+
+    /// Names of all stored profiles, in arbitrary order.
+    pub fn profile_names(&self) -> impl Iterator<Item = &str> {
+        self.profiles.keys().map(String::as_str)
+    }
+
+    /// Removes structurally identical duplicate constraints, keeping
+    /// the first of each family, and returns how many were dropped.
+    /// Constraints without a [`structural_key`](
+    /// Constraint::structural_key) are always kept. Duplicates don't
+    /// change the feasible set, but each one costs a projection per
+    /// Dykstra sweep and drags on convergence.
+    pub fn dedup(&mut self) -> usize {
+        let mut seen: Vec<(std::any::TypeId, u64)> = Vec::new();
+        let before = self.constraints.len();
+        self.constraints.retain(|c| {
+            let Some(key) = c.structural_key() else {
+                return true;
+            };
+            let id = (c.as_any().type_id(), key);
+            if seen.contains(&id) {
+                false
+            } else {
+                seen.push(id);
+                true
+            }
+        });
+        let removed = before - self.constraints.len();
+        if removed > 0 {
+            self.revision += 1;
+        }
+        removed
+    }
+
+    /// A copy of this system with every feasible set shrunk by `delta`
+    /// (each constraint padded by a [`WithMargin`], so axis-aligned
+    /// shapes shrink exactly rather than by gradient walk). Ranking
+    /// profiles are not carried over. Panics on a negative margin.
+    pub fn shrunk(&self, delta: f64) -> ConstraintSystem {
+        let mut out = ConstraintSystem::new(self.dim);
+        out.search_policy = self.search_policy.clone();
+        out.numeric_policy = self.numeric_policy.clone();
+        out.angular_dims = self.angular_dims.clone();
+        for c in &self.constraints {
+            out.add(WithMargin::new(c.clone(), delta));
+        }
+        out
+    }
+
+    /// A copy of this system keeping only the constraints relevant at
+    /// `focus`: [`ActivationConstraint`]s whose region excludes the
+    /// focus are dropped, everything else is carried over (policies
+    /// included, profiles not — matching [`shrunk`](Self::shrunk)).
+    /// Hosts call this once per gesture so out-of-range snap guides
+    /// never reach the projection loop.
+    pub fn prune_inactive(&self, focus: &Vector) -> ConstraintSystem {
+        assert_eq!(focus.dim(), self.dim, "focus dimension mismatch");
+        let mut out = ConstraintSystem::new(self.dim);
+        out.search_policy = self.search_policy.clone();
+        out.numeric_policy = self.numeric_policy.clone();
+        out.angular_dims = self.angular_dims.clone();
+        for c in &self.constraints {
+            if let Some(gated) = c.as_any().downcast_ref::<ActivationConstraint>() {
+                if !gated.active_at(focus) {
+                    continue;
+                }
+            }
+            out.add_ref(c.clone());
+        }
+        out
+    }
+
+    /// Minimum signed distance over all constraints: the slack at
+    /// `point` if positive, the worst violation if negative. Infinite
+    /// for an empty system.
+    pub fn margin(&self, point: &Vector) -> f64 {
+        self.constraints
+            .iter()
+            .map(|c| c.signed_distance(point))
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// Deepest violation at `point` as `(constraint index, depth)`,
+    /// with depth positive, or `None` when every constraint is
+    /// satisfied. Ties go to the earliest constraint.
+    pub fn max_violation(&self, point: &Vector) -> Option<(usize, f64)> {
+        self.max_violation_weighted(point, &vec![1.0; self.constraints.len()])
+    }
+
+    /// Sum of all violation depths at `point` (zero when feasible).
+    pub fn total_violation(&self, point: &Vector) -> f64 {
+        self.total_violation_weighted(point, &vec![1.0; self.constraints.len()])
+    }
+
+    /// [`max_violation`](Self::max_violation) with a per-constraint
+    /// weight applied to each depth, for scoring layers that care more
+    /// about some constraints than others. Panics when `weights` has
+    /// the wrong length or a negative entry.
+    pub fn max_violation_weighted(
+        &self,
+        point: &Vector,
+        weights: &[f64],
+    ) -> Option<(usize, f64)> {
+        let mut worst: Option<(usize, f64)> = None;
+        for (i, (c, &w)) in self.constraints.iter().zip(self.check_weights(weights)).enumerate() {
+            let depth = w * (-c.signed_distance(point)).max(0.0);
+            if depth > 0.0 && worst.is_none_or(|(_, d)| depth > d) {
+                worst = Some((i, depth));
+            }
+        }
+        worst
+    }
+
+    /// [`total_violation`](Self::total_violation) with per-constraint
+    /// weights. Panics when `weights` has the wrong length or a
+    /// negative entry.
+    pub fn total_violation_weighted(&self, point: &Vector, weights: &[f64]) -> f64 {
+        self.constraints
+            .iter()
+            .zip(self.check_weights(weights))
+            .map(|(c, &w)| w * (-c.signed_distance(point)).max(0.0))
+            .sum()
+    }
+
+    fn check_weights<'a>(&self, weights: &'a [f64]) -> std::slice::Iter<'a, f64> {
+        assert_eq!(
+            weights.len(),
+            self.constraints.len(),
+            "one weight per constraint required"
+        );
+        assert!(
+            weights.iter().all(|&w| w >= 0.0),
+            "violation weights must be non-negative"
+        );
+        weights.iter()
+    }
+
+    /// A speculative copy of this system for "what if" evaluation —
+    /// delete the guide on the fork, run the suggestion, look at the
+    /// answer — without mutating the live document. Forking is cheap:
+    /// constraints are shared [`ConstraintRef`] handles, so the copy
+    /// is a vector of pointers, never constraint payloads. Ranking
+    /// profiles stay with the base — custom scorers are not cloneable
+    /// — and survive a commit untouched. Discarding is just dropping
+    /// the fork; committing is [`SystemFork::commit`].
+    pub fn fork(&self) -> SystemFork {
+        SystemFork {
+            base_revision: self.revision,
+            system: ConstraintSystem {
+                dim: self.dim,
+                constraints: self.constraints.clone(),
+                profiles: HashMap::new(),
+                search_policy: self.search_policy.clone(),
+                numeric_policy: self.numeric_policy.clone(),
+                angular_dims: self.angular_dims.clone(),
+                revision: self.revision,
+            },
+        }
+    }
+
+    /// Per-constraint verdicts at `point`, in constraint order: whether
+    /// each is satisfied, its signed distance, and its own nearest
+    /// feasible point. This is the inspector-panel view of the system —
+    /// aggregate queries like [`is_feasible`](Self::is_feasible) and
+    /// [`margin`](Self::margin) say *whether* a point is in trouble;
+    /// `diagnose` says *which* constraints object and by how much.
+    pub fn diagnose(&self, point: &Vector) -> Vec<ConstraintStatus> {
+        self.constraints
+            .iter()
+            .enumerate()
+            .map(|(index, c)| {
+                let satisfied = c.contains(point);
+                ConstraintStatus {
+                    index,
+                    satisfied,
+                    signed_distance: c.signed_distance(point),
+                    nearest: if satisfied {
+                        point.clone()
+                    } else {
+                        c.project(point)
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+/// A speculative copy of a [`ConstraintSystem`], taken with
+/// [`ConstraintSystem::fork`].
+///
+/// The host edits and queries the fork like any system, then either
+/// drops it — the discard path needs no code at all — or commits it
+/// back over the base. The fork remembers the revision it was taken
+/// at, and commit refuses a base that changed in between, so two
+/// overlapping speculations cannot silently clobber each other's
+/// edits.
+pub struct SystemFork {
+    base_revision: u64,
+    system: ConstraintSystem,
+}
+
+impl SystemFork {
+    /// The forked system, for queries and suggest calls.
+    pub fn system(&self) -> &ConstraintSystem {
+        &self.system
+    }
+
+    /// The forked system, for speculative edits.
+    pub fn system_mut(&mut self) -> &mut ConstraintSystem {
+        &mut self.system
+    }
+
+    /// Whether `base` is still at the revision this fork was taken
+    /// from — check before committing when another edit path may have
+    /// run meanwhile.
+    pub fn base_unchanged(&self, base: &ConstraintSystem) -> bool {
+        base.revision == self.base_revision
+    }
+
+    /// Replaces `base` with the fork's state, keeping the base's
+    /// ranking profiles in place. Bumps the base revision past both
+    /// sides, so caches keyed on either see a change. Panics when
+    /// `base` was mutated since the fork was taken.
+    pub fn commit(self, base: &mut ConstraintSystem) {
+        assert!(
+            self.base_unchanged(base),
+            "base system changed since the fork was taken"
+        );
+        let revision = base.revision.max(self.system.revision) + 1;
+        let profiles = std::mem::take(&mut base.profiles);
+        *base = self.system;
+        base.profiles = profiles;
+        base.revision = revision;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    #[test]
+    fn box_constraint_projects_and_measures_slack() {
+        let c = BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(10.0, 10.0)));
+        assert!(c.contains(&v(5.0, 5.0)));
+        assert_eq!(c.project(&v(12.0, 5.0)), v(10.0, 5.0));
+        assert_eq!(c.signed_distance(&v(5.0, 2.0)), 2.0);
+        assert_eq!(c.signed_distance(&v(13.0, 5.0)), -3.0);
+    }
+
+    #[test]
+    fn halfspace_projection() {
+        let c = HalfspaceConstraint::new(v(1.0, 0.0), 4.0);
+        assert!(c.contains(&v(4.0, 100.0)));
+        assert!(!c.contains(&v(5.0, 0.0)));
+        assert_eq!(c.project(&v(6.0, 3.0)), v(4.0, 3.0));
+        assert_eq!(c.signed_distance(&v(1.0, 0.0)), 3.0);
+    }
+
+    #[test]
+    fn collision_pushes_out_nearest_face() {
+        let c = CollisionConstraint::new(Bounds::new(v(0.0, 0.0), v(10.0, 10.0)));
+        assert!(c.contains(&v(-1.0, 5.0)));
+        assert!(!c.contains(&v(1.0, 5.0)));
+        // 1 unit from the left face, 5 from top/bottom: exits left.
+        assert_eq!(c.project(&v(1.0, 5.0)), v(0.0, 5.0));
+    }
+
+    #[test]
+    fn collision_margin_inflates_obstacle() {
+        let c = CollisionConstraint::with_margin(Bounds::new(v(0.0, 0.0), v(10.0, 10.0)), 2.0);
+        assert!(!c.contains(&v(-1.0, 5.0)));
+        assert!(c.contains(&v(-3.0, 5.0)));
+    }
+
+    #[test]
+    fn multi_collision_projects_out_of_the_containing_obstacle() {
+        let c = MultiCollisionConstraint::new(vec![
+            Bounds::new(v(0.0, 0.0), v(10.0, 10.0)),
+            Bounds::new(v(20.0, 0.0), v(30.0, 10.0)),
+        ]);
+        assert!(c.contains(&v(15.0, 5.0)));
+        assert_eq!(c.project(&v(15.0, 5.0)), v(15.0, 5.0));
+        // Inside the second obstacle: exits through its nearest face.
+        assert!(!c.contains(&v(21.0, 5.0)));
+        assert_eq!(c.project(&v(21.0, 5.0)), v(20.0, 5.0));
+        // Midway between the two, the slack is to the nearer one.
+        assert_eq!(c.signed_distance(&v(14.0, 5.0)), 4.0);
+    }
+
+    #[test]
+    fn multi_collision_chains_through_overlapping_obstacles() {
+        let c = MultiCollisionConstraint::new(vec![
+            Bounds::new(v(0.0, 0.0), v(10.0, 10.0)),
+            Bounds::new(v(5.0, 0.0), v(15.0, 10.0)),
+        ]);
+        // (6, 5) sits inside both; the exit point must clear the
+        // overlap, not land in the neighbouring obstacle's interior.
+        let out = c.project(&v(6.0, 5.0));
+        for o in c.obstacles() {
+            assert!(
+                !(0..2).all(|d| out.get(d) > o.min().get(d) && out.get(d) < o.max().get(d)),
+                "projection landed strictly inside an obstacle"
+            );
+        }
+        assert!(c.signed_distance(&v(6.0, 5.0)) < 0.0);
+    }
+
+    #[test]
+    fn multi_collision_grid_agrees_with_a_naive_scan() {
+        // A 6×6 field of obstacles, dense enough to exercise shared
+        // and empty grid cells.
+        let mut obstacles = Vec::new();
+        for gy in 0..6 {
+            for gx in 0..6 {
+                let min = v(gx as f64 * 4.0, gy as f64 * 4.0);
+                obstacles.push(Bounds::new(min.clone(), min.add(&v(2.0, 2.0))));
+            }
+        }
+        let c = MultiCollisionConstraint::new(obstacles.clone());
+        for ix in -2..50 {
+            for iy in -2..50 {
+                let p = v(ix as f64 * 0.5, iy as f64 * 0.5);
+                let naive = !obstacles.iter().any(|o| o.contains(&p));
+                assert_eq!(c.contains(&p), naive, "disagreement at {p:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn multi_collision_try_new_validates_the_obstacle_set() {
+        let err = MultiCollisionConstraint::try_new(Vec::new()).unwrap_err();
+        assert_eq!(
+            err,
+            crate::error::NewtonError::InvalidParameter(
+                "multi-collision requires at least one obstacle"
+            )
+        );
+        let err = MultiCollisionConstraint::try_new(vec![
+            Bounds::new(v(0.0, 0.0), v(1.0, 1.0)),
+            Bounds::new(Vector::zeros(3), Vector::new(vec![1.0, 1.0, 1.0])),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            err,
+            crate::error::NewtonError::DimensionMismatch {
+                expected: 2,
+                actual: 3
+            }
+        );
+    }
+
+    #[test]
+    fn discrete_snaps_to_nearest() {
+        let c = DiscreteConstraint::new(vec![v(0.0, 0.0), v(10.0, 0.0)]);
+        assert_eq!(c.project(&v(7.0, 1.0)), v(10.0, 0.0));
+        assert!(c.contains(&v(10.0, 0.0)));
+        assert!(!c.contains(&v(5.0, 0.0)));
+    }
+
+    #[test]
+    fn product_discrete_projects_dimension_wise() {
+        // Font sizes × line heights, never materialized as pairs.
+        let c = ProductDiscreteConstraint::new(vec![
+            vec![8.0, 9.0, 10.0, 12.0, 14.0],
+            vec![1.0, 1.25, 1.5],
+        ]);
+        assert_eq!(c.project(&v(11.2, 1.3)), v(12.0, 1.25));
+        assert!(c.contains(&v(9.0, 1.5)));
+        assert!(!c.contains(&v(9.0, 1.4)));
+        // Equidistant ties snap to the smaller value.
+        assert_eq!(c.project(&v(9.5, 1.0)), v(9.0, 1.0));
+        assert_eq!(c.signed_distance(&v(8.0, 1.25)), 0.0);
+    }
+
+    #[test]
+    fn product_discrete_try_new_validates_the_value_lists() {
+        let err = ProductDiscreteConstraint::try_new(vec![vec![1.0], Vec::new()]).unwrap_err();
+        assert_eq!(
+            err,
+            crate::error::NewtonError::InvalidParameter(
+                "every dimension needs at least one allowed value"
+            )
+        );
+        let err = ProductDiscreteConstraint::try_new(vec![vec![1.0, f64::NAN]]).unwrap_err();
+        assert_eq!(
+            err,
+            crate::error::NewtonError::InvalidParameter("allowed values must be finite")
+        );
+    }
+
+    #[test]
+    fn discrete_nearest_matches_a_linear_scan() {
+        // Deterministic pseudo-random cloud, dense enough to exercise
+        // both sides of many splitting planes.
+        let mut seed = 0x2545_F491_4F6C_DD1D_u64;
+        let mut next = move || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as f64 / (1u64 << 31) as f64 * 100.0
+        };
+        let points: Vec<Vector> = (0..500).map(|_| v(next(), next())).collect();
+        let c = DiscreteConstraint::new(points.clone());
+        for _ in 0..100 {
+            let q = v(next(), next());
+            let naive = points
+                .iter()
+                .min_by(|a, b| a.distance(&q).total_cmp(&b.distance(&q)))
+                .unwrap();
+            assert_eq!(c.nearest(&q), naive);
+        }
+    }
+
+    #[test]
+    fn robust_constraint_shrinks_the_feasible_set() {
+        let sys = {
+            let mut sys = ConstraintSystem::new(2);
+            sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(100.0, 100.0))));
+            sys.shrunk(5.0)
+        };
+        assert!(sys.is_feasible(&v(50.0, 50.0)));
+        assert!(!sys.is_feasible(&v(50.0, 2.0)));
+        let c = &sys.constraints()[0];
+        assert!(c.project(&v(50.0, -10.0)).distance(&v(50.0, 5.0)) < 1e-4);
+        assert_eq!(c.signed_distance(&v(50.0, 50.0)), 45.0);
+    }
+
+    #[test]
+    fn angle_constraint_handles_the_seam() {
+        // Allowed headings: -20°..20° expressed as an arc from 340°
+        // crossing the seam.
+        let arc = AngleConstraint::new(1, 0, 340.0_f64.to_radians(), 40.0_f64.to_radians());
+        let heading = |deg: f64| Vector::new(vec![deg.to_radians()]);
+        assert!(arc.contains(&heading(350.0)));
+        assert!(arc.contains(&heading(10.0)));
+        assert!(!arc.contains(&heading(180.0)));
+        // 25° snaps back to 20°, not the long way round to 340°.
+        let p = arc.project(&heading(25.0));
+        assert!((p.get(0) - 20.0_f64.to_radians()).abs() < 1e-9);
+        // 330° snaps forward to 340°.
+        let p = arc.project(&heading(330.0));
+        assert!((p.get(0) - 340.0_f64.to_radians()).abs() < 1e-9);
+        // Interior slack is measured to the nearer arc end.
+        let s = arc.signed_distance(&heading(0.0));
+        assert!((s - 20.0_f64.to_radians()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn angle_wrap_helpers() {
+        use std::f64::consts::{PI, TAU};
+        assert!((wrap_angle(-0.5) - (TAU - 0.5)).abs() < 1e-12);
+        assert!((wrap_angle(TAU + 1.0) - 1.0).abs() < 1e-12);
+        assert!((angle_difference(0.1, TAU - 0.1) - 0.2).abs() < 1e-12);
+        assert!((angle_difference(TAU - 0.1, 0.1) + 0.2).abs() < 1e-12);
+        assert!((angle_difference(PI, 0.0) - PI).abs() < 1e-12);
+    }
+
+    #[test]
+    fn free_space_projects_to_nearest_room() {
+        // Two rooms joined by a shared wall segment at x = 10.
+        let space = FreeSpaceConstraint::new(vec![
+            Bounds::new(v(0.0, 0.0), v(10.0, 10.0)),
+            Bounds::new(v(10.0, 4.0), v(20.0, 6.0)),
+        ]);
+        assert!(space.contains(&v(5.0, 5.0)));
+        assert!(space.contains(&v(15.0, 5.0)));
+        assert!(!space.contains(&v(15.0, 9.0)));
+        // Outside both rooms: lands on the nearer one.
+        assert_eq!(space.project(&v(15.0, 9.0)), v(15.0, 6.0));
+        assert_eq!(space.nearest_room(&v(15.0, 9.0)), 1);
+        // Inside slack comes from the containing room.
+        assert_eq!(space.signed_distance(&v(5.0, 5.0)), 5.0);
+        assert!(space.signed_distance(&v(15.0, 9.0)) < 0.0);
+    }
+
+    #[test]
+    fn free_space_portals_sit_on_shared_faces() {
+        let space = FreeSpaceConstraint::new(vec![
+            Bounds::new(v(0.0, 0.0), v(10.0, 10.0)),
+            Bounds::new(v(10.0, 4.0), v(20.0, 6.0)),
+            Bounds::new(v(100.0, 100.0), v(110.0, 110.0)),
+        ]);
+        let portals = space.portals();
+        assert_eq!(portals.len(), 1);
+        assert_eq!(portals[0], v(10.0, 5.0));
+    }
+
+    #[test]
+    fn dedup_drops_structural_duplicates() {
+        let mut sys = ConstraintSystem::new(2);
+        let bounds = Bounds::new(v(0.0, 0.0), v(10.0, 10.0));
+        sys.add(BoxConstraint::new(bounds.clone()));
+        sys.add(BoxConstraint::new(bounds.clone()));
+        sys.add(BoxConstraint::new(bounds.clone()));
+        // Same bounds but a different type: not a duplicate.
+        sys.add(CollisionConstraint::new(bounds));
+        sys.add(HalfspaceConstraint::new(v(1.0, 0.0), 5.0));
+        sys.add(HalfspaceConstraint::new(v(1.0, 0.0), 5.0));
+        let before = sys.revision();
+        assert_eq!(sys.dedup(), 3);
+        assert_eq!(sys.len(), 3);
+        assert!(sys.revision() > before);
+        // A second pass finds nothing.
+        assert_eq!(sys.dedup(), 0);
+    }
+
+    #[test]
+    fn system_checks_all_constraints() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(10.0, 10.0))));
+        sys.add(HalfspaceConstraint::new(v(0.0, 1.0), 5.0));
+        assert!(sys.is_feasible(&v(5.0, 5.0)));
+        assert!(!sys.is_feasible(&v(5.0, 6.0)));
+        assert_eq!(sys.len(), 2);
+    }
+
+    #[test]
+    fn boundary_normals_point_out_of_the_feasible_set() {
+        let b = BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(10.0, 10.0)));
+        // Interior point nearest the left face.
+        assert_eq!(b.boundary_normal(&v(2.0, 5.0)), Some(v(-1.0, 0.0)));
+        // Exterior corner region: diagonal back toward the corner.
+        let n = b.boundary_normal(&v(13.0, 14.0)).unwrap();
+        assert!(n.distance(&v(3.0, 4.0).scale(0.2)) < 1e-9);
+
+        let h = HalfspaceConstraint::new(v(0.0, 2.0), 10.0);
+        assert_eq!(h.boundary_normal(&v(7.0, 7.0)), Some(v(0.0, 1.0)));
+
+        // Collision: leaving feasibility means moving toward the
+        // obstacle; from inside, away from the nearest face restores
+        // it.
+        let c = CollisionConstraint::new(Bounds::new(v(0.0, 0.0), v(10.0, 10.0)));
+        assert_eq!(c.boundary_normal(&v(-3.0, 5.0)), Some(v(1.0, 0.0)));
+        assert_eq!(c.boundary_normal(&v(1.0, 5.0)), Some(v(1.0, 0.0)));
+    }
+
+    #[test]
+    fn constraint_refs_downcast_to_concrete_types() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(HalfspaceConstraint::new(v(1.0, 0.0), 4.0));
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(10.0, 10.0))));
+        // Host-side pattern: find the box among the refs and read its
+        // parameters back out.
+        let bounds = sys
+            .constraints()
+            .iter()
+            .find_map(|c| c.as_any().downcast_ref::<BoxConstraint>())
+            .map(|b| b.bounds().clone())
+            .unwrap();
+        assert_eq!(bounds.max(), &v(10.0, 10.0));
+    }
+
+    #[test]
+    fn interior_points_are_feasible() {
+        let b = BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(10.0, 10.0)));
+        assert_eq!(b.interior_point(), Some(v(5.0, 5.0)));
+
+        let h = HalfspaceConstraint::new(v(0.0, 2.0), 10.0);
+        let p = h.interior_point().unwrap();
+        assert!(h.contains(&p));
+        assert!(h.signed_distance(&p) > 0.5);
+
+        let c = CollisionConstraint::new(Bounds::new(v(0.0, 0.0), v(10.0, 10.0)));
+        assert!(c.contains(&c.interior_point().unwrap()));
+
+        // A margin the inner anchor cannot clear yields no anchor.
+        let tight = RobustConstraint::new(
+            Arc::new(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(10.0, 10.0)))),
+            6.0,
+        );
+        assert_eq!(tight.interior_point(), None);
+    }
+
+    #[test]
+    fn collision_projection_is_exact_near_corners() {
+        let c = CollisionConstraint::new(Bounds::new(v(0.0, 0.0), v(10.0, 10.0)));
+        // Interior point near a corner: the nearest exterior point is
+        // still through the nearest face — no point on any edge or at
+        // any corner beats the perpendicular push.
+        let p = v(1.0, 2.0);
+        let out = c.project(&p);
+        assert_eq!(out, v(0.0, 2.0));
+        let d = p.distance(&out);
+        // Sweep the whole obstacle boundary to confirm nothing there
+        // is nearer than the returned projection.
+        let perimeter = |t: f64| -> Vector {
+            match t {
+                t if t < 10.0 => v(t, 0.0),
+                t if t < 20.0 => v(10.0, t - 10.0),
+                t if t < 30.0 => v(30.0 - t, 10.0),
+                t => v(0.0, 40.0 - t),
+            }
+        };
+        for k in 0..400 {
+            let q = perimeter(k as f64 / 10.0);
+            assert!(p.distance(&q) >= d - 1e-9);
+        }
+        // Exterior slack near a corner is measured to the corner
+        // itself, not to a face plane.
+        let slack = c.signed_distance(&v(12.0, 12.0));
+        assert!((slack - 8.0_f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn with_margin_pads_boxes_exactly_at_corners() {
+        let padded = WithMargin::new(
+            Arc::new(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(10.0, 10.0)))),
+            1.0,
+        );
+        // The padded box is a box: the corner projection is exact, not
+        // a gradient-walk approximation.
+        assert_eq!(padded.project(&v(20.0, 20.0)), v(9.0, 9.0));
+        assert!(padded.contains(&v(1.0, 1.0)));
+        assert!(!padded.contains(&v(0.5, 5.0)));
+    }
+
+    #[test]
+    fn with_margin_expands_obstacles() {
+        let padded = WithMargin::new(
+            Arc::new(CollisionConstraint::new(Bounds::new(
+                v(0.0, 0.0),
+                v(10.0, 10.0),
+            ))),
+            2.0,
+        );
+        assert!(!padded.contains(&v(11.0, 5.0)));
+        assert_eq!(padded.project(&v(11.0, 5.0)), v(12.0, 5.0));
+        assert!(padded.contains(&v(12.5, 5.0)));
+    }
+
+    #[test]
+    fn with_margin_collapse_is_infeasible_not_a_panic() {
+        let collapsed = WithMargin::new(
+            Arc::new(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(1.0, 1.0)))),
+            10.0,
+        );
+        assert!(!collapsed.contains(&v(0.5, 0.5)));
+        assert_eq!(collapsed.interior_point(), None);
+    }
+
+    #[test]
+    fn violation_aggregates_attribute_and_weight() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(10.0, 10.0))));
+        sys.add(HalfspaceConstraint::new(v(0.0, 1.0), 5.0));
+        // Feasible: nothing to report.
+        assert_eq!(sys.max_violation(&v(5.0, 3.0)), None);
+        assert_eq!(sys.total_violation(&v(5.0, 3.0)), 0.0);
+        // Outside the box by 2 and the halfspace by 7.
+        let p = v(5.0, 12.0);
+        assert_eq!(sys.max_violation(&p), Some((1, 7.0)));
+        assert_eq!(sys.total_violation(&p), 9.0);
+        // Weighting the box up flips the attribution.
+        assert_eq!(sys.max_violation_weighted(&p, &[10.0, 1.0]), Some((0, 20.0)));
+        assert_eq!(sys.total_violation_weighted(&p, &[10.0, 1.0]), 27.0);
+        // Zero weight mutes a constraint entirely.
+        assert_eq!(sys.max_violation_weighted(&p, &[1.0, 0.0]), Some((0, 2.0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "one weight per constraint")]
+    fn violation_weights_must_match_length() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(10.0, 10.0))));
+        sys.total_violation_weighted(&v(0.0, 0.0), &[1.0, 1.0]);
+    }
+
+    #[test]
+    fn diagnose_reports_each_constraint() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(10.0, 10.0))));
+        sys.add(HalfspaceConstraint::new(v(0.0, 1.0), 5.0));
+        let statuses = sys.diagnose(&v(5.0, 7.0));
+        assert_eq!(statuses.len(), 2);
+        // The box is happy and leaves the point alone.
+        assert!(statuses[0].satisfied);
+        assert_eq!(statuses[0].index, 0);
+        assert_eq!(statuses[0].signed_distance, 3.0);
+        assert_eq!(statuses[0].nearest, v(5.0, 7.0));
+        // The halfspace objects by 2 and offers its own projection.
+        assert!(!statuses[1].satisfied);
+        assert_eq!(statuses[1].signed_distance, -2.0);
+        assert_eq!(statuses[1].nearest, v(5.0, 5.0));
+    }
+
+    #[test]
+    fn try_new_rejects_untrusted_garbage() {
+        use crate::error::NewtonError;
+        // Non-finite numbers never reach the panicking constructors.
+        assert_eq!(
+            BoxConstraint::try_new(Bounds::new(v(0.0, 0.0), v(1.0, f64::INFINITY))).err(),
+            Some(NewtonError::InvalidParameter("box bounds must be finite"))
+        );
+        assert_eq!(
+            HalfspaceConstraint::try_new(v(0.0, 0.0), 1.0).err(),
+            Some(NewtonError::InvalidParameter(
+                "halfspace normal must be nonzero"
+            ))
+        );
+        assert_eq!(
+            DiscreteConstraint::try_new(vec![]).err(),
+            Some(NewtonError::InvalidParameter(
+                "discrete set requires at least one point"
+            ))
+        );
+        assert_eq!(
+            DiscreteConstraint::try_new(vec![v(0.0, 0.0), Vector::zeros(3)]).err(),
+            Some(NewtonError::DimensionMismatch {
+                expected: 2,
+                actual: 3
+            })
+        );
+        assert_eq!(
+            LatticeConstraint::try_new(2, 0.0).err(),
+            Some(NewtonError::InvalidParameter(
+                "lattice step must be finite and positive"
+            ))
+        );
+        assert_eq!(
+            AngleConstraint::try_new(2, 2, 0.0, 1.0).err(),
+            Some(NewtonError::InvalidParameter(
+                "angle axis must be within the space's dimension"
+            ))
+        );
+        assert_eq!(
+            AngleConstraint::try_new(2, 0, 0.0, 7.0).err(),
+            Some(NewtonError::InvalidParameter(
+                "arc width must lie in [0, 2π]"
+            ))
+        );
+    }
+
+    #[test]
+    fn activation_gates_a_snap_guide() {
+        // A guide keeping x at or left of 50, engaging only within 8
+        // units of its line.
+        let gated = ActivationConstraint::new(
+            Arc::new(HalfspaceConstraint::new(v(1.0, 0.0), 50.0)),
+            Bounds::new(v(42.0, -1000.0), v(58.0, 1000.0)),
+        );
+        // Far from the guide: no opinion, full slack to the region.
+        assert!(gated.contains(&v(80.0, 0.0)));
+        assert_eq!(gated.project(&v(80.0, 0.0)), v(80.0, 0.0));
+        assert_eq!(gated.signed_distance(&v(80.0, 0.0)), 22.0);
+        // In range and violating: projects onto the inner constraint.
+        assert!(!gated.contains(&v(53.0, 0.0)));
+        assert_eq!(gated.project(&v(53.0, 0.0)), v(50.0, 0.0));
+        // Depth is bounded by the nearer exit (inner at 3, region at 5).
+        assert_eq!(gated.signed_distance(&v(53.0, 0.0)), -3.0);
+        assert_eq!(gated.signed_distance(&v(57.0, 0.0)), -1.0);
+        // In range and satisfied.
+        assert!(gated.contains(&v(45.0, 0.0)));
+    }
+
+    #[test]
+    fn prune_inactive_drops_out_of_range_guides() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(100.0, 100.0))));
+        sys.add(ActivationConstraint::new(
+            Arc::new(HalfspaceConstraint::new(v(1.0, 0.0), 50.0)),
+            Bounds::new(v(42.0, 0.0), v(58.0, 100.0)),
+        ));
+        // Focus far from the guide: only the canvas box survives.
+        let pruned = sys.prune_inactive(&v(90.0, 50.0));
+        assert_eq!(pruned.len(), 1);
+        // Focus in range: the guide stays.
+        assert_eq!(sys.prune_inactive(&v(45.0, 50.0)).len(), 2);
+        // Non-gated constraints are never pruned.
+        assert_eq!(sys.prune_inactive(&v(200.0, 200.0)).len(), 1);
+    }
+
+    #[test]
+    fn numeric_policy_scales_with_coordinate_magnitude() {
+        let policy = NumericPolicy::default();
+        assert_eq!(policy.epsilon(), crate::EPSILON);
+        let astro = NumericPolicy::for_magnitude(1e12);
+        assert_eq!(astro.epsilon(), crate::EPSILON * 1e12);
+        assert_eq!(astro.tolerance(), 1e-7 * 1e12);
+        // Shrinks too: ångström-scale features stay resolvable.
+        assert!(NumericPolicy::for_magnitude(1e-10).tolerance() < 1e-16);
+
+        let mut sys = ConstraintSystem::new(2);
+        let before = sys.revision();
+        sys.set_numeric_policy(astro);
+        assert!(sys.revision() > before, "threshold changes must invalidate caches");
+        assert_eq!(sys.numeric_policy().scale(), 1e12);
+        // Derived systems inherit the policy.
+        assert_eq!(sys.shrunk(0.0).numeric_policy().scale(), 1e12);
+    }
+
+    #[test]
+    #[should_panic(expected = "coordinate magnitude must be positive and finite")]
+    fn numeric_policy_rejects_bad_magnitude() {
+        NumericPolicy::for_magnitude(0.0);
+    }
+
+    #[test]
+    fn try_new_accepts_valid_parameters() {
+        let c = BoxConstraint::try_new(Bounds::new(v(0.0, 0.0), v(10.0, 10.0))).unwrap();
+        assert!(c.contains(&v(5.0, 5.0)));
+        let h = HalfspaceConstraint::try_new(v(0.0, 1.0), 5.0).unwrap();
+        assert!(h.contains(&v(0.0, 4.0)));
+        assert!(AngleConstraint::try_new(2, 0, 0.0, 1.0).is_ok());
+    }
+
+    #[test]
+    fn forks_speculate_without_touching_the_base() {
+        let mut base = ConstraintSystem::new(2);
+        base.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(100.0, 100.0))));
+        base.add(HalfspaceConstraint::new(v(1.0, 0.0), 50.0));
+
+        let mut fork = base.fork();
+        // The copy shares constraint payloads, not clones of them.
+        assert!(Arc::ptr_eq(&base.constraints()[0], &fork.system().constraints()[0]));
+        // "What if I delete this guide?"
+        fork.system_mut().remove(1);
+        assert!(fork.system().is_feasible(&v(80.0, 10.0)));
+        // The live document never saw the deletion; dropping the fork
+        // is the whole discard path.
+        assert!(!base.is_feasible(&v(80.0, 10.0)));
+        assert_eq!(base.len(), 2);
+    }
+
+    #[test]
+    fn committing_a_fork_applies_it_and_bumps_the_revision() {
+        let mut base = ConstraintSystem::new(2);
+        base.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(100.0, 100.0))));
+        base.add(HalfspaceConstraint::new(v(1.0, 0.0), 50.0));
+        let before = base.revision();
+
+        let mut fork = base.fork();
+        fork.system_mut().remove(1);
+        assert!(fork.base_unchanged(&base));
+        fork.commit(&mut base);
+        assert_eq!(base.len(), 1);
+        assert!(base.is_feasible(&v(80.0, 10.0)));
+        // Caches keyed on the base revision must see the commit.
+        assert!(base.revision() > before);
+    }
+
+    #[test]
+    #[should_panic(expected = "base system changed since the fork was taken")]
+    fn committing_over_a_changed_base_panics() {
+        let mut base = ConstraintSystem::new(2);
+        base.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(100.0, 100.0))));
+        let fork = base.fork();
+        base.add(HalfspaceConstraint::new(v(1.0, 0.0), 50.0));
+        assert!(!fork.base_unchanged(&base));
+        fork.commit(&mut base);
+    }
+}
@@ -0,0 +1,171 @@
+//! Input deltas.
+//!
+//! A [`Delta`] is one increment of movement in configuration space,
+//! tagged with where it came from and when. Input pipelines accumulate,
+//! split and rescale these constantly; the utilities here keep the
+//! metadata intact while they do, instead of every caller hand-rolling
+//! the arithmetic and dropping the source or timestamp on the floor.
+
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use crate::linalg::Vector;
+
+/// Where a delta originated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeltaSource {
+    /// Direct touch or mouse drag.
+    Pointer,
+    /// Keyboard nudge (arrow keys etc.).
+    Keyboard,
+    /// Generated by code (animation, undo, remote sync).
+    Programmatic,
+    /// Composition of deltas with differing sources.
+    Mixed,
+}
+
+/// One increment of movement with its provenance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Delta {
+    /// The movement itself.
+    pub change: Vector,
+    /// Where the movement came from.
+    pub source: DeltaSource,
+    /// Monotonic timestamp in microseconds (see [`monotonic_now_us`]).
+    pub timestamp_us: u64,
+}
+
+/// Microseconds elapsed on a process-wide monotonic clock. The epoch is
+/// the first call in this process; only differences are meaningful.
+pub fn monotonic_now_us() -> u64 {
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+    EPOCH.get_or_init(Instant::now).elapsed().as_micros() as u64
+}
+
+impl Delta {
+    /// Creates a delta stamped with the current monotonic time.
+    pub fn new(change: Vector, source: DeltaSource) -> Self {
+        Delta {
+            change,
+            source,
+            timestamp_us: monotonic_now_us(),
+        }
+    }
+
+    /// Creates a delta with an explicit timestamp, for replay and
+    /// testing.
+    pub fn with_timestamp(change: Vector, source: DeltaSource, timestamp_us: u64) -> Self {
+        Delta {
+            change,
+            source,
+            timestamp_us,
+        }
+    }
+
+    /// Sums a sequence of deltas into one. The result carries the
+    /// latest timestamp; the source is preserved when all inputs agree
+    /// and becomes [`DeltaSource::Mixed`] otherwise. Returns `None` for
+    /// an empty slice (there is no dimension to give a zero delta).
+    pub fn compose(deltas: &[Delta]) -> Option<Delta> {
+        let first = deltas.first()?;
+        let mut change = first.change.clone();
+        let mut source = first.source;
+        let mut timestamp_us = first.timestamp_us;
+        for d in &deltas[1..] {
+            change = change.add(&d.change);
+            if d.source != source {
+                source = DeltaSource::Mixed;
+            }
+            timestamp_us = timestamp_us.max(d.timestamp_us);
+        }
+        Some(Delta {
+            change,
+            source,
+            timestamp_us,
+        })
+    }
+
+    /// Splits into per-dimension component deltas (zero components
+    /// included), each preserving source and timestamp. Composing the
+    /// result reproduces `self`.
+    pub fn split_components(&self) -> Vec<Delta> {
+        (0..self.change.dim())
+            .map(|i| {
+                let mut c = Vector::zeros(self.change.dim());
+                c.set(i, self.change.get(i));
+                Delta {
+                    change: c,
+                    source: self.source,
+                    timestamp_us: self.timestamp_us,
+                }
+            })
+            .collect()
+    }
+
+    /// Scales the movement, preserving source and timestamp.
+    pub fn scaled(&self, k: f64) -> Delta {
+        Delta {
+            change: self.change.scale(k),
+            source: self.source,
+            timestamp_us: self.timestamp_us,
+        }
+    }
+
+    /// The position reached by applying this delta to `position`.
+    pub fn apply(&self, position: &Vector) -> Vector {
+        position.add(&self.change)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    #[test]
+    fn compose_sums_and_keeps_latest_timestamp() {
+        let a = Delta::with_timestamp(v(1.0, 0.0), DeltaSource::Pointer, 10);
+        let b = Delta::with_timestamp(v(0.0, 2.0), DeltaSource::Pointer, 30);
+        let c = Delta::compose(&[a, b]).unwrap();
+        assert_eq!(c.change, v(1.0, 2.0));
+        assert_eq!(c.source, DeltaSource::Pointer);
+        assert_eq!(c.timestamp_us, 30);
+    }
+
+    #[test]
+    fn compose_mixes_disagreeing_sources() {
+        let a = Delta::with_timestamp(v(1.0, 0.0), DeltaSource::Pointer, 10);
+        let b = Delta::with_timestamp(v(1.0, 0.0), DeltaSource::Keyboard, 20);
+        assert_eq!(Delta::compose(&[a, b]).unwrap().source, DeltaSource::Mixed);
+        assert!(Delta::compose(&[]).is_none());
+    }
+
+    #[test]
+    fn split_round_trips_through_compose() {
+        let d = Delta::with_timestamp(v(3.0, -4.0), DeltaSource::Keyboard, 7);
+        let parts = d.split_components();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].change, v(3.0, 0.0));
+        assert_eq!(parts[1].change, v(0.0, -4.0));
+        assert_eq!(Delta::compose(&parts).unwrap(), d);
+    }
+
+    #[test]
+    fn scaling_preserves_metadata() {
+        let d = Delta::with_timestamp(v(2.0, 2.0), DeltaSource::Programmatic, 99);
+        let s = d.scaled(0.5);
+        assert_eq!(s.change, v(1.0, 1.0));
+        assert_eq!(s.source, DeltaSource::Programmatic);
+        assert_eq!(s.timestamp_us, 99);
+    }
+
+    #[test]
+    fn new_uses_monotonic_clock() {
+        let a = Delta::new(v(0.0, 0.0), DeltaSource::Pointer);
+        let b = Delta::new(v(0.0, 0.0), DeltaSource::Pointer);
+        assert!(b.timestamp_us >= a.timestamp_us);
+    }
+}
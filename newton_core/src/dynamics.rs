@@ -0,0 +1,195 @@
+//! Optional momentum layer on top of the suggestion engine.
+//!
+//! Plain per-frame suggestion is memoryless: dragging into a wall dead
+//! stops, and releasing a fling goes nowhere. [`DynamicsSession`] gives
+//! an object velocity, treats the intent as a spring force pulling the
+//! object toward it, integrates one fixed timestep, and projects the
+//! resulting motion through [`suggest`]. Velocity is then re-derived
+//! from the *actual* (constrained) displacement, so the component into
+//! a boundary dies while the tangential component survives — smooth
+//! sliding instead of dead stops.
+//!
+//! The timestep is fixed at construction and every step integrates
+//! exactly once, so identical input sequences produce identical motion
+//! on every device regardless of frame pacing.
+
+use crate::constraint::ConstraintSystem;
+use crate::linalg::Vector;
+use crate::rank::RankingCriteria;
+use crate::suggest::{suggest, SuggestResponse};
+
+/// Position and velocity of a simulated object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DynamicState {
+    pub position: Vector,
+    pub velocity: Vector,
+}
+
+/// Integration parameters. All are per-session constants to keep the
+/// simulation deterministic.
+#[derive(Debug, Clone)]
+pub struct DynamicsParams {
+    /// Fixed timestep in seconds.
+    pub dt: f64,
+    /// Spring stiffness pulling the object toward the intent.
+    pub stiffness: f64,
+    /// Velocity damping per second (0 = frictionless).
+    pub damping: f64,
+}
+
+impl Default for DynamicsParams {
+    fn default() -> Self {
+        DynamicsParams {
+            dt: 1.0 / 120.0,
+            stiffness: 60.0,
+            damping: 8.0,
+        }
+    }
+}
+
+/// A per-gesture dynamics simulation (unit mass).
+pub struct DynamicsSession {
+    state: DynamicState,
+    params: DynamicsParams,
+}
+
+impl DynamicsSession {
+    /// Starts at rest at `position`. Panics on a non-positive timestep
+    /// or negative stiffness/damping.
+    pub fn begin(position: Vector, params: DynamicsParams) -> Self {
+        assert!(params.dt > 0.0, "timestep must be positive");
+        assert!(params.stiffness >= 0.0, "stiffness must be non-negative");
+        assert!(params.damping >= 0.0, "damping must be non-negative");
+        let dim = position.dim();
+        DynamicsSession {
+            state: DynamicState {
+                position,
+                velocity: Vector::zeros(dim),
+            },
+            params,
+        }
+    }
+
+    pub fn state(&self) -> &DynamicState {
+        &self.state
+    }
+
+    /// Injects velocity directly, e.g. from a fling gesture's release
+    /// velocity.
+    pub fn impulse(&mut self, velocity: &Vector) {
+        self.state.velocity = self.state.velocity.add(velocity);
+    }
+
+    /// Integrates one fixed timestep toward `intent` (pass the current
+    /// position as intent to coast on momentum alone) and projects the
+    /// motion through the constraint system.
+    pub fn step(
+        &mut self,
+        system: &ConstraintSystem,
+        intent: &Vector,
+        criteria: &RankingCriteria,
+    ) -> SuggestResponse {
+        let DynamicsParams { dt, stiffness, damping } = self.params;
+        // Semi-implicit Euler: update velocity first, then position.
+        let spring = intent.sub(&self.state.position).scale(stiffness);
+        let drag = self.state.velocity.scale(-damping);
+        let accel = spring.add(&drag);
+        let velocity = self.state.velocity.add(&accel.scale(dt));
+        let tentative = self.state.position.add(&velocity.scale(dt));
+
+        let response = suggest(system, &self.state.position, &tentative, criteria);
+
+        // Re-derive velocity from the constrained displacement: the
+        // blocked component vanishes, the tangential one survives.
+        self.state.velocity = response.position.sub(&self.state.position).scale(1.0 / dt);
+        self.state.position = response.position.clone();
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bounds::Bounds;
+    use crate::constraint::BoxConstraint;
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    fn open_box() -> ConstraintSystem {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(Bounds::new(
+            v(-1000.0, -1000.0),
+            v(1000.0, 10.0),
+        )));
+        sys
+    }
+
+    #[test]
+    fn free_motion_accelerates_toward_intent() {
+        let sys = open_box();
+        let mut session = DynamicsSession::begin(v(0.0, 0.0), DynamicsParams::default());
+        let intent = v(100.0, 0.0);
+        let criteria = RankingCriteria::default();
+        let mut last_x = 0.0;
+        for _ in 0..30 {
+            let r = session.step(&sys, &intent, &criteria);
+            assert!(r.position.get(0) >= last_x);
+            last_x = r.position.get(0);
+        }
+        assert!(last_x > 1.0);
+    }
+
+    #[test]
+    fn hitting_a_wall_slides_along_it() {
+        let sys = open_box();
+        // Start just below the y = 10 ceiling, pushing up and to the
+        // right.
+        let mut session = DynamicsSession::begin(v(0.0, 9.0), DynamicsParams::default());
+        let intent = v(200.0, 200.0);
+        let criteria = RankingCriteria::default();
+        let mut final_pos = v(0.0, 9.0);
+        for _ in 0..60 {
+            final_pos = session.step(&sys, &intent, &criteria).position;
+        }
+        // Pinned to the ceiling, but still travelling in x.
+        assert!(final_pos.get(1) <= 10.0 + 1e-9);
+        assert!(final_pos.get(0) > 1.0, "tangential motion died: {final_pos:?}");
+    }
+
+    #[test]
+    fn fixed_timestep_is_deterministic() {
+        let run = || {
+            let sys = open_box();
+            let mut session = DynamicsSession::begin(v(0.0, 0.0), DynamicsParams::default());
+            let criteria = RankingCriteria::default();
+            let mut trace = Vec::new();
+            for i in 0..20 {
+                let intent = v(i as f64 * 5.0, 5.0);
+                trace.push(session.step(&sys, &intent, &criteria).position);
+            }
+            trace
+        };
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn impulse_coasts_and_decays() {
+        let sys = open_box();
+        let mut session = DynamicsSession::begin(v(0.0, 0.0), DynamicsParams::default());
+        session.impulse(&v(120.0, 0.0));
+        let criteria = RankingCriteria::default();
+        // Coast: intent equals current position each frame.
+        let mut positions = Vec::new();
+        for _ in 0..40 {
+            let here = session.state().position.clone();
+            positions.push(session.step(&sys, &here, &criteria).position.clone());
+        }
+        // It moved, and damping eventually slows it down.
+        assert!(positions.last().unwrap().get(0) > 0.5);
+        let early_step = positions[1].get(0) - positions[0].get(0);
+        let late_step = positions[39].get(0) - positions[38].get(0);
+        assert!(late_step < early_step);
+    }
+}
@@ -0,0 +1,191 @@
+//! Ephemeral constraints with a managed lifetime.
+//!
+//! Momentary constraints — an alignment guide that appears while two
+//! edges line up, a temporary keep-out around a drop animation — are
+//! routinely added to the permanent [`ConstraintSystem`] and then
+//! leaked: the code path that should have removed them never runs, and
+//! weeks later a document is full of stale snap constraints nobody can
+//! explain. [`EphemeralSet`] keeps them out of the permanent system
+//! entirely. The host registers each one with a time-to-live in frames
+//! or wall-clock time, calls [`EphemeralSet::begin_frame`] once per
+//! frame, and overlays the survivors onto the base system per gesture;
+//! expiry is the default, not a cleanup path someone has to remember.
+
+use std::time::Duration;
+
+use crate::constraint::{Constraint, ConstraintRef, ConstraintSystem};
+use crate::delta::monotonic_now_us;
+
+/// How long an ephemeral constraint lives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ttl {
+    /// Survives this many [`EphemeralSet::begin_frame`] calls. Frame
+    /// TTLs replay deterministically, which wall-clock ones cannot.
+    Frames(u64),
+    /// Expires once this much wall-clock time has passed (measured on
+    /// the [`monotonic_now_us`] clock).
+    Duration(Duration),
+}
+
+/// When an entry stops being included.
+#[derive(Debug, Clone, Copy)]
+enum Expiry {
+    AtFrame(u64),
+    AtTimeUs(u64),
+}
+
+struct Entry {
+    constraint: ConstraintRef,
+    expiry: Expiry,
+}
+
+/// A session-managed set of expiring constraints, overlaid on a
+/// permanent system per gesture.
+pub struct EphemeralSet {
+    dim: usize,
+    frame: u64,
+    entries: Vec<Entry>,
+}
+
+impl EphemeralSet {
+    /// An empty set over a `dim`-dimensional space.
+    pub fn new(dim: usize) -> Self {
+        EphemeralSet {
+            dim,
+            frame: 0,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Registers a constraint by value. Panics on dimension mismatch
+    /// or a zero-frame TTL (which could never be observed).
+    pub fn add(&mut self, constraint: impl Constraint + 'static, ttl: Ttl) {
+        self.add_ref(std::sync::Arc::new(constraint), ttl);
+    }
+
+    /// Registers a shared constraint handle with a time-to-live.
+    pub fn add_ref(&mut self, constraint: ConstraintRef, ttl: Ttl) {
+        assert_eq!(
+            constraint.dim(),
+            self.dim,
+            "constraint dimension does not match set"
+        );
+        let expiry = match ttl {
+            Ttl::Frames(n) => {
+                assert!(n > 0, "frame TTL must be positive");
+                Expiry::AtFrame(self.frame + n)
+            }
+            Ttl::Duration(d) => {
+                Expiry::AtTimeUs(monotonic_now_us().saturating_add(d.as_micros() as u64))
+            }
+        };
+        self.entries.push(Entry { constraint, expiry });
+    }
+
+    /// Advances the frame counter and drops expired entries. Call once
+    /// per rendered frame, before building overlays.
+    pub fn begin_frame(&mut self) {
+        self.frame += 1;
+        let frame = self.frame;
+        let now = monotonic_now_us();
+        self.entries.retain(|e| match e.expiry {
+            Expiry::AtFrame(at) => frame < at,
+            Expiry::AtTimeUs(at) => now < at,
+        });
+    }
+
+    /// Live constraint count.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Drops every entry immediately, e.g. on gesture end.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// A copy of `base` with every live ephemeral constraint appended.
+    /// Policies are carried over; the base system itself is untouched,
+    /// so nothing ephemeral can leak into the document. Panics when
+    /// the base dimension disagrees with the set's.
+    pub fn overlay(&self, base: &ConstraintSystem) -> ConstraintSystem {
+        assert_eq!(base.dim(), self.dim, "system dimension does not match set");
+        let mut out = ConstraintSystem::new(self.dim);
+        out.set_search_policy(base.search_policy().clone());
+        out.set_numeric_policy(base.numeric_policy().clone());
+        out.set_angular_dims(base.angular_dims().to_vec());
+        for c in base.constraints() {
+            out.add_ref(c.clone());
+        }
+        for e in &self.entries {
+            out.add_ref(e.constraint.clone());
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bounds::Bounds;
+    use crate::constraint::{BoxConstraint, HalfspaceConstraint};
+    use crate::linalg::Vector;
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    #[test]
+    fn frame_ttls_expire_deterministically() {
+        let mut set = EphemeralSet::new(2);
+        set.add(HalfspaceConstraint::new(v(1.0, 0.0), 50.0), Ttl::Frames(2));
+        assert_eq!(set.len(), 1);
+        set.begin_frame();
+        assert_eq!(set.len(), 1);
+        set.begin_frame();
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn elapsed_durations_expire() {
+        let mut set = EphemeralSet::new(2);
+        set.add(
+            HalfspaceConstraint::new(v(1.0, 0.0), 50.0),
+            Ttl::Duration(Duration::ZERO),
+        );
+        set.add(
+            HalfspaceConstraint::new(v(0.0, 1.0), 50.0),
+            Ttl::Duration(Duration::from_secs(3600)),
+        );
+        set.begin_frame();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn overlay_constrains_without_touching_the_base() {
+        let mut base = ConstraintSystem::new(2);
+        base.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(100.0, 100.0))));
+        let mut set = EphemeralSet::new(2);
+        set.add(HalfspaceConstraint::new(v(1.0, 0.0), 50.0), Ttl::Frames(1));
+        let combined = set.overlay(&base);
+        assert_eq!(combined.len(), 2);
+        assert!(!combined.is_feasible(&v(80.0, 10.0)));
+        // The permanent system never saw the guide.
+        assert_eq!(base.len(), 1);
+        assert!(base.is_feasible(&v(80.0, 10.0)));
+        // Next frame the guide is gone from fresh overlays too.
+        set.begin_frame();
+        assert_eq!(set.overlay(&base).len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "frame TTL must be positive")]
+    fn zero_frame_ttl_is_rejected() {
+        let mut set = EphemeralSet::new(2);
+        set.add(HalfspaceConstraint::new(v(1.0, 0.0), 50.0), Ttl::Frames(0));
+    }
+}
@@ -0,0 +1,70 @@
+//! Crate-wide error type for the fallible (`try_*`) entry points.
+//!
+//! The engine's house rule is panics for programmer errors and honest
+//! best-effort answers for hard numerical cases. That is right for
+//! trusted call sites inside a frame loop and wrong for untrusted
+//! document data or host-language bindings that cannot afford to
+//! unwind. The `try_*` APIs surface the same conditions as values of
+//! one structured type, so hosts branch on a variant instead of
+//! parsing panic messages.
+//!
+//! [`crate::linear::LinearError`] predates this type and stays
+//! module-local: the simplex solver's failure modes are meaningful
+//! only to its own callers.
+
+/// What went wrong in a fallible engine call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NewtonError {
+    /// Operands disagree on dimension.
+    DimensionMismatch { expected: usize, actual: usize },
+    /// A constraint or policy was built with invalid parameters; the
+    /// message names the offending one.
+    InvalidParameter(&'static str),
+    /// No feasible point could be found — the constraints may admit no
+    /// solution at all.
+    Infeasible,
+    /// Iterative projection hit its iteration cap while still moving.
+    NonConvergence { iterations: usize },
+    /// A shared frame/work budget was already exhausted.
+    BudgetExceeded,
+    /// An incoming state or delta contains NaN or ±∞ and the active
+    /// [`crate::sanitize`] policy rejects rather than repairs.
+    NonFiniteInput,
+}
+
+impl std::fmt::Display for NewtonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NewtonError::DimensionMismatch { expected, actual } => {
+                write!(f, "dimension mismatch: expected {expected}, got {actual}")
+            }
+            NewtonError::InvalidParameter(what) => write!(f, "invalid parameter: {what}"),
+            NewtonError::Infeasible => write!(f, "no feasible point found"),
+            NewtonError::NonConvergence { iterations } => {
+                write!(f, "projection did not converge within {iterations} iterations")
+            }
+            NewtonError::BudgetExceeded => write!(f, "work budget exhausted"),
+            NewtonError::NonFiniteInput => write!(f, "input contains NaN or infinity"),
+        }
+    }
+}
+
+impl std::error::Error for NewtonError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_name_the_condition() {
+        let e = NewtonError::DimensionMismatch {
+            expected: 2,
+            actual: 3,
+        };
+        assert_eq!(e.to_string(), "dimension mismatch: expected 2, got 3");
+        assert_eq!(
+            NewtonError::InvalidParameter("lattice step").to_string(),
+            "invalid parameter: lattice step"
+        );
+    }
+}
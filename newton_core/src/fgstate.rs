@@ -0,0 +1,287 @@
+//! Constraint engagement state for UI feedback.
+//!
+//! The f/g ratio compares how hard the user is pushing against the
+//! constraints (`f`, the raw intent's distance to the feasible
+//! intersection) with how much room they have (`g`, `f` plus the
+//! remaining slack). Near zero the gesture is unconstrained; near one
+//! the object is pinned to a boundary. Haptic and visual layers key
+//! off the discretised [`FGState`].
+
+/// Ratio below which a gesture is considered unconstrained.
+pub const ENGAGED_THRESHOLD: f64 = 0.15;
+/// Ratio at or above which the object is treated as pinned.
+pub const EXACT_THRESHOLD: f64 = 0.85;
+
+/// Discretised engagement level of the current gesture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FGState {
+    /// Moving freely; constraints are not (meaningfully) resisting.
+    Slack,
+    /// Constraints are bending the gesture but not dominating it.
+    Engaged,
+    /// The object is effectively on a constraint boundary.
+    Exact,
+}
+
+impl FGState {
+    /// Classifies an f/g ratio. Values are clamped to `[0, 1]`.
+    pub fn from_ratio(ratio: f64) -> FGState {
+        let r = ratio.clamp(0.0, 1.0);
+        if r < ENGAGED_THRESHOLD {
+            FGState::Slack
+        } else if r < EXACT_THRESHOLD {
+            FGState::Engaged
+        } else {
+            FGState::Exact
+        }
+    }
+
+    /// Computes the ratio from resistance `f` and slack `g` and
+    /// classifies it. `g <= 0` counts as fully engaged.
+    pub fn classify(f: f64, g: f64) -> FGState {
+        if g <= 0.0 {
+            return FGState::Exact;
+        }
+        FGState::from_ratio(f / (f + g))
+    }
+
+    /// Haptic amplitude in `[0, 1]` for this state.
+    pub fn haptic_amplitude(&self) -> f64 {
+        match self {
+            FGState::Slack => 0.0,
+            FGState::Engaged => 0.4,
+            FGState::Exact => 1.0,
+        }
+    }
+
+    /// Indicator color as linear RGB, for guideline/overlay tinting.
+    pub fn color(&self) -> (f32, f32, f32) {
+        match self {
+            FGState::Slack => (0.55, 0.55, 0.58),
+            FGState::Engaged => (0.20, 0.55, 0.95),
+            FGState::Exact => (0.95, 0.45, 0.15),
+        }
+    }
+}
+
+/// Tunable thresholds and feedback mapping for [`FGState`].
+///
+/// The inherent methods on [`FGState`] use the crate defaults; hosts
+/// that want different engagement bands, haptic levels or brand colors
+/// build an `FGConfig` and classify through it instead.
+#[derive(Debug, Clone)]
+pub struct FGConfig {
+    /// Ratio below which a gesture counts as `Slack`.
+    pub engaged_threshold: f64,
+    /// Ratio at or above which a gesture counts as `Exact`.
+    pub exact_threshold: f64,
+    /// Haptic amplitudes for `[Slack, Engaged, Exact]`, each in `[0, 1]`.
+    pub amplitudes: [f64; 3],
+    /// Linear RGB indicator colors for `[Slack, Engaged, Exact]`.
+    pub colors: [(f32, f32, f32); 3],
+}
+
+impl Default for FGConfig {
+    fn default() -> Self {
+        FGConfig {
+            engaged_threshold: ENGAGED_THRESHOLD,
+            exact_threshold: EXACT_THRESHOLD,
+            amplitudes: [
+                FGState::Slack.haptic_amplitude(),
+                FGState::Engaged.haptic_amplitude(),
+                FGState::Exact.haptic_amplitude(),
+            ],
+            colors: [
+                FGState::Slack.color(),
+                FGState::Engaged.color(),
+                FGState::Exact.color(),
+            ],
+        }
+    }
+}
+
+impl FGConfig {
+    /// Creates a config with custom thresholds and default feedback
+    /// mapping. Panics unless `0 <= engaged < exact <= 1`.
+    pub fn with_thresholds(engaged_threshold: f64, exact_threshold: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&engaged_threshold)
+                && (0.0..=1.0).contains(&exact_threshold)
+                && engaged_threshold < exact_threshold,
+            "thresholds must satisfy 0 <= engaged < exact <= 1"
+        );
+        FGConfig {
+            engaged_threshold,
+            exact_threshold,
+            ..FGConfig::default()
+        }
+    }
+
+    /// Classifies an f/g ratio against these thresholds. Values are
+    /// clamped to `[0, 1]`.
+    pub fn classify_ratio(&self, ratio: f64) -> FGState {
+        let r = ratio.clamp(0.0, 1.0);
+        if r < self.engaged_threshold {
+            FGState::Slack
+        } else if r < self.exact_threshold {
+            FGState::Engaged
+        } else {
+            FGState::Exact
+        }
+    }
+
+    /// Haptic amplitude configured for `state`.
+    pub fn amplitude(&self, state: FGState) -> f64 {
+        self.amplitudes[state as usize]
+    }
+
+    /// Indicator color configured for `state`.
+    pub fn color(&self, state: FGState) -> (f32, f32, f32) {
+        self.colors[state as usize]
+    }
+}
+
+/// Exponential smoothing of the f/g ratio across frames.
+///
+/// Raw per-frame ratios flip between `Slack` and `Exact` when a gesture
+/// rides a constraint boundary, which the haptic layer renders as
+/// buzzing. The filter low-passes the ratio with a first-order
+/// exponential (time constant in seconds) before classification, so
+/// single-frame spikes do not reach the actuator.
+#[derive(Debug, Clone)]
+pub struct FGStateFilter {
+    time_constant: f64,
+    smoothed: Option<f64>,
+}
+
+impl FGStateFilter {
+    /// Creates a filter with the given time constant in seconds. A time
+    /// constant of zero disables smoothing (raw ratios pass through).
+    /// Panics on negative values.
+    pub fn new(time_constant: f64) -> Self {
+        assert!(time_constant >= 0.0, "time constant must be non-negative");
+        FGStateFilter {
+            time_constant,
+            smoothed: None,
+        }
+    }
+
+    /// Feeds one frame's raw ratio with the elapsed time `dt` in
+    /// seconds and returns the state of the smoothed ratio. The first
+    /// sample initialises the filter rather than decaying from zero.
+    pub fn update(&mut self, ratio: f64, dt: f64) -> FGState {
+        let raw = ratio.clamp(0.0, 1.0);
+        let next = match self.smoothed {
+            None => raw,
+            Some(prev) => {
+                let alpha = if self.time_constant <= 0.0 {
+                    1.0
+                } else {
+                    1.0 - (-dt / self.time_constant).exp()
+                };
+                prev + alpha * (raw - prev)
+            }
+        };
+        self.smoothed = Some(next);
+        FGState::from_ratio(next)
+    }
+
+    /// Current smoothed ratio, if any sample has been fed.
+    pub fn smoothed_ratio(&self) -> Option<f64> {
+        self.smoothed
+    }
+
+    /// State of the current smoothed ratio, if any sample has been fed.
+    pub fn state(&self) -> Option<FGState> {
+        self.smoothed.map(FGState::from_ratio)
+    }
+
+    /// Forgets all history, e.g. when a new gesture begins.
+    pub fn reset(&mut self) {
+        self.smoothed = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ratio_bands() {
+        assert_eq!(FGState::from_ratio(0.0), FGState::Slack);
+        assert_eq!(FGState::from_ratio(0.5), FGState::Engaged);
+        assert_eq!(FGState::from_ratio(1.0), FGState::Exact);
+        // Out-of-range input clamps rather than panicking.
+        assert_eq!(FGState::from_ratio(-3.0), FGState::Slack);
+        assert_eq!(FGState::from_ratio(7.0), FGState::Exact);
+    }
+
+    #[test]
+    fn zero_slack_is_exact() {
+        assert_eq!(FGState::classify(1.0, 0.0), FGState::Exact);
+        assert_eq!(FGState::classify(0.0, 5.0), FGState::Slack);
+    }
+
+    #[test]
+    fn config_defaults_match_inherent_behaviour() {
+        let cfg = FGConfig::default();
+        for r in [0.0, 0.1, 0.2, 0.5, 0.84, 0.9, 1.0] {
+            assert_eq!(cfg.classify_ratio(r), FGState::from_ratio(r));
+        }
+        assert_eq!(cfg.amplitude(FGState::Exact), FGState::Exact.haptic_amplitude());
+        assert_eq!(cfg.color(FGState::Slack), FGState::Slack.color());
+    }
+
+    #[test]
+    fn custom_thresholds_move_the_bands() {
+        let cfg = FGConfig::with_thresholds(0.5, 0.6);
+        assert_eq!(cfg.classify_ratio(0.4), FGState::Slack);
+        assert_eq!(cfg.classify_ratio(0.55), FGState::Engaged);
+        assert_eq!(cfg.classify_ratio(0.7), FGState::Exact);
+    }
+
+    #[test]
+    #[should_panic(expected = "thresholds")]
+    fn inverted_thresholds_are_rejected() {
+        FGConfig::with_thresholds(0.9, 0.1);
+    }
+
+    #[test]
+    fn filter_suppresses_single_frame_spikes() {
+        let mut filter = FGStateFilter::new(0.1);
+        for _ in 0..10 {
+            assert_eq!(filter.update(0.0, 1.0 / 120.0), FGState::Slack);
+        }
+        // One frame at the boundary must not reach Exact through a
+        // 100 ms filter.
+        assert_ne!(filter.update(1.0, 1.0 / 120.0), FGState::Exact);
+        // But a sustained boundary contact eventually does.
+        for _ in 0..100 {
+            filter.update(1.0, 1.0 / 120.0);
+        }
+        assert_eq!(filter.state(), Some(FGState::Exact));
+    }
+
+    #[test]
+    fn zero_time_constant_is_passthrough() {
+        let mut filter = FGStateFilter::new(0.0);
+        assert_eq!(filter.update(0.0, 1.0 / 60.0), FGState::Slack);
+        assert_eq!(filter.update(1.0, 1.0 / 60.0), FGState::Exact);
+    }
+
+    #[test]
+    fn first_sample_initialises() {
+        let mut filter = FGStateFilter::new(1.0);
+        // No decay from an implicit zero: the first sample is taken
+        // as-is.
+        assert_eq!(filter.update(0.9, 1.0 / 60.0), FGState::Exact);
+        filter.reset();
+        assert_eq!(filter.state(), None);
+    }
+
+    #[test]
+    fn amplitude_monotone_in_engagement() {
+        assert!(FGState::Slack.haptic_amplitude() < FGState::Engaged.haptic_amplitude());
+        assert!(FGState::Engaged.haptic_amplitude() < FGState::Exact.haptic_amplitude());
+    }
+}
@@ -0,0 +1,344 @@
+//! Feasibility field sampling over a 2D window.
+//!
+//! Editors render "where can this object go?" as a heat map. Doing
+//! that host-side with one suggest call per pixel is far too slow —
+//! and unnecessary, since an overlay only needs the signed margin at
+//! each cell, not a ranked suggestion. [`sample_feasibility`] evaluates
+//! [`ConstraintSystem::margin`] over a regular grid in one pass and
+//! hands back the raw margins plus an [`FGState`] classification per
+//! sample, so the host maps cells straight to colors via
+//! [`FGState::color`].
+//!
+//! Samples lie on grid *nodes* — evenly spaced, window corners
+//! included — so the same grid feeds boundary extraction, which needs
+//! signed distances at cell corners.
+
+use crate::bounds::Bounds;
+use crate::constraint::ConstraintSystem;
+use crate::fgstate::FGState;
+use crate::linalg::Vector;
+
+/// Margins of a constraint system sampled over a regular 2D grid.
+#[derive(Debug, Clone)]
+pub struct FeasibilityGrid {
+    window: Bounds,
+    samples_per_axis: usize,
+    /// The engagement falloff scale captured at sampling time.
+    radius: f64,
+    /// Row-major: index `iy * samples_per_axis + ix`.
+    margins: Vec<f64>,
+}
+
+/// Samples the system's margin on a `samples_per_axis` ×
+/// `samples_per_axis` grid of nodes spanning `window`, corners
+/// included. Panics unless the system and window are 2-dimensional and
+/// at least two samples per axis are requested.
+pub fn sample_feasibility(
+    system: &ConstraintSystem,
+    window: &Bounds,
+    samples_per_axis: usize,
+) -> FeasibilityGrid {
+    assert_eq!(system.dim(), 2, "feasibility sampling is 2D");
+    assert_eq!(window.dim(), 2, "window dimension mismatch");
+    assert!(
+        samples_per_axis >= 2,
+        "need at least two samples per axis"
+    );
+    let mut margins = Vec::with_capacity(samples_per_axis * samples_per_axis);
+    for iy in 0..samples_per_axis {
+        for ix in 0..samples_per_axis {
+            margins.push(system.margin(&node(window, samples_per_axis, ix, iy)));
+        }
+    }
+    FeasibilityGrid {
+        window: window.clone(),
+        samples_per_axis,
+        radius: system.search_policy().search_radius(),
+        margins,
+    }
+}
+
+/// Position of grid node `(ix, iy)` within `window`.
+fn node(window: &Bounds, samples_per_axis: usize, ix: usize, iy: usize) -> Vector {
+    let t = |i: usize| i as f64 / (samples_per_axis - 1) as f64;
+    Vector::new(vec![
+        window.min().get(0) + t(ix) * (window.max().get(0) - window.min().get(0)),
+        window.min().get(1) + t(iy) * (window.max().get(1) - window.min().get(1)),
+    ])
+}
+
+impl FeasibilityGrid {
+    /// The sampled window.
+    pub fn window(&self) -> &Bounds {
+        &self.window
+    }
+
+    /// Nodes per axis.
+    pub fn samples_per_axis(&self) -> usize {
+        self.samples_per_axis
+    }
+
+    /// Position of node `(ix, iy)`. Panics out of range.
+    pub fn point(&self, ix: usize, iy: usize) -> Vector {
+        assert!(
+            ix < self.samples_per_axis && iy < self.samples_per_axis,
+            "grid index out of range"
+        );
+        node(&self.window, self.samples_per_axis, ix, iy)
+    }
+
+    /// Signed margin at node `(ix, iy)`: slack if positive, violation
+    /// depth if negative. Panics out of range.
+    pub fn margin(&self, ix: usize, iy: usize) -> f64 {
+        assert!(
+            ix < self.samples_per_axis && iy < self.samples_per_axis,
+            "grid index out of range"
+        );
+        self.margins[iy * self.samples_per_axis + ix]
+    }
+
+    /// Whether node `(ix, iy)` is feasible.
+    pub fn feasible(&self, ix: usize, iy: usize) -> bool {
+        self.margin(ix, iy) >= 0.0
+    }
+
+    /// Engagement classification at node `(ix, iy)`: `Exact` on or
+    /// past the boundary, fading to `Slack` at the search radius — the
+    /// same falloff [`crate::sensitivity::pressure`] reports.
+    pub fn state(&self, ix: usize, iy: usize) -> FGState {
+        FGState::from_ratio((self.radius - self.margin(ix, iy)) / self.radius)
+    }
+
+    /// All margins, row-major (`iy * samples_per_axis + ix`), for
+    /// hosts uploading the field as a texture.
+    pub fn margins(&self) -> &[f64] {
+        &self.margins
+    }
+
+    /// The feasible region's boundary as polylines, extracted by
+    /// marching squares over the sampled margins.
+    ///
+    /// Each polyline is a sequence of points where the interpolated
+    /// margin crosses zero; loops closed inside the window repeat
+    /// their first point at the end, while boundaries that leave the
+    /// window stay open. A uniformly feasible or infeasible window
+    /// yields no polylines. Accuracy is limited by the grid: features
+    /// smaller than one cell are missed, and crossings are placed by
+    /// linear interpolation along cell edges.
+    pub fn boundary(&self) -> Vec<Vec<Vector>> {
+        let n = self.samples_per_axis;
+        let mut segments: Vec<(Vector, Vector)> = Vec::new();
+        for iy in 0..n - 1 {
+            for ix in 0..n - 1 {
+                self.cell_segments(ix, iy, &mut segments);
+            }
+        }
+        stitch(segments, self.stitch_tolerance())
+    }
+
+    /// Matching tolerance for shared cell-edge crossings; scaled to
+    /// the window so astronomical and subatomic canvases both stitch.
+    fn stitch_tolerance(&self) -> f64 {
+        1e-9 * (1.0 + self.window.min().distance(self.window.max()))
+    }
+
+    /// Zero crossing between nodes `a` and `b`, whose margins `va` and
+    /// `vb` straddle zero.
+    fn crossing(&self, a: (usize, usize), b: (usize, usize), va: f64, vb: f64) -> Vector {
+        let t = va / (va - vb);
+        self.point(a.0, a.1).lerp(&self.point(b.0, b.1), t)
+    }
+
+    /// Emits this cell's boundary segments (marching squares, with the
+    /// two ambiguous saddle cases resolved by the cell's mean margin).
+    fn cell_segments(&self, ix: usize, iy: usize, out: &mut Vec<(Vector, Vector)>) {
+        // Corners counter-clockwise from the cell's min corner.
+        let corners = [(ix, iy), (ix + 1, iy), (ix + 1, iy + 1), (ix, iy + 1)];
+        let m: Vec<f64> = corners.iter().map(|&(x, y)| self.margin(x, y)).collect();
+        let mut case = 0usize;
+        for (bit, &v) in m.iter().enumerate() {
+            if v >= 0.0 {
+                case |= 1 << bit;
+            }
+        }
+        // Crossing on the edge between corners `i` and `(i + 1) % 4`.
+        let edge = |i: usize| {
+            let j = (i + 1) % 4;
+            self.crossing(corners[i], corners[j], m[i], m[j])
+        };
+        let edges: &[(usize, usize)] = match case {
+            0b0000 | 0b1111 => &[],
+            0b0001 | 0b1110 => &[(3, 0)],
+            0b0010 | 0b1101 => &[(0, 1)],
+            0b0100 | 0b1011 => &[(1, 2)],
+            0b1000 | 0b0111 => &[(2, 3)],
+            0b0011 | 0b1100 => &[(3, 1)],
+            0b0110 | 0b1001 => &[(0, 2)],
+            // Saddles: two opposite corners feasible. The mean margin
+            // decides whether the feasible corners connect through the
+            // cell center or stay separated.
+            0b0101 => {
+                if m.iter().sum::<f64>() >= 0.0 {
+                    &[(0, 1), (2, 3)]
+                } else {
+                    &[(3, 0), (1, 2)]
+                }
+            }
+            0b1010 => {
+                if m.iter().sum::<f64>() >= 0.0 {
+                    &[(3, 0), (1, 2)]
+                } else {
+                    &[(0, 1), (2, 3)]
+                }
+            }
+            _ => unreachable!("4-bit case"),
+        };
+        let tolerance = self.stitch_tolerance();
+        for &(a, b) in edges {
+            let (pa, pb) = (edge(a), edge(b));
+            // A crossing pinned exactly to a corner can degenerate.
+            if pa.distance(&pb) > tolerance {
+                out.push((pa, pb));
+            }
+        }
+    }
+}
+
+/// Chains shared-endpoint segments into polylines, deterministically:
+/// segments are consumed in emission order and extended greedily at
+/// both ends.
+fn stitch(mut segments: Vec<(Vector, Vector)>, tolerance: f64) -> Vec<Vec<Vector>> {
+    let mut polylines = Vec::new();
+    while let Some((a, b)) = segments.pop() {
+        let mut line = std::collections::VecDeque::from([a, b]);
+        loop {
+            let head = line.front().unwrap().clone();
+            let tail = line.back().unwrap().clone();
+            let Some(index) = segments.iter().position(|(p, q)| {
+                p.distance(&tail) < tolerance
+                    || q.distance(&tail) < tolerance
+                    || p.distance(&head) < tolerance
+                    || q.distance(&head) < tolerance
+            }) else {
+                break;
+            };
+            let (p, q) = segments.remove(index);
+            if p.distance(&tail) < tolerance {
+                line.push_back(q);
+            } else if q.distance(&tail) < tolerance {
+                line.push_back(p);
+            } else if p.distance(&head) < tolerance {
+                line.push_front(q);
+            } else {
+                line.push_front(p);
+            }
+        }
+        polylines.push(line.into());
+    }
+    polylines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraint::{BoxConstraint, CollisionConstraint};
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    fn boxed_system() -> ConstraintSystem {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(100.0, 100.0))));
+        sys.add(CollisionConstraint::new(Bounds::new(
+            v(40.0, 40.0),
+            v(60.0, 60.0),
+        )));
+        sys
+    }
+
+    #[test]
+    fn nodes_span_the_window_corners() {
+        let sys = boxed_system();
+        let grid = sample_feasibility(&sys, &Bounds::new(v(0.0, 0.0), v(100.0, 100.0)), 5);
+        assert_eq!(grid.point(0, 0), v(0.0, 0.0));
+        assert_eq!(grid.point(4, 4), v(100.0, 100.0));
+        assert_eq!(grid.point(2, 1), v(50.0, 25.0));
+        assert_eq!(grid.margins().len(), 25);
+    }
+
+    #[test]
+    fn margins_match_the_system() {
+        let sys = boxed_system();
+        let grid = sample_feasibility(&sys, &Bounds::new(v(0.0, 0.0), v(100.0, 100.0)), 5);
+        // Center of the obstacle: violated.
+        assert!(!grid.feasible(2, 2));
+        assert_eq!(grid.margin(2, 2), sys.margin(&v(50.0, 50.0)));
+        // A corner of the canvas: feasible but on the boundary.
+        assert!(grid.feasible(0, 0));
+        assert_eq!(grid.margin(0, 0), 0.0);
+    }
+
+    #[test]
+    fn states_band_by_distance_to_the_boundary() {
+        let sys = boxed_system();
+        let grid = sample_feasibility(&sys, &Bounds::new(v(0.0, 0.0), v(100.0, 100.0)), 5);
+        // On and inside the obstacle both read Exact.
+        assert_eq!(grid.state(2, 2), FGState::Exact);
+        assert_eq!(grid.state(0, 0), FGState::Exact);
+        // (25, 25) has 15 units of slack against a 48-unit radius.
+        assert_eq!(grid.state(1, 1), FGState::Engaged);
+    }
+
+    #[test]
+    fn boundary_traces_a_closed_loop_around_a_box() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(Bounds::new(v(20.0, 20.0), v(80.0, 80.0))));
+        let grid = sample_feasibility(&sys, &Bounds::new(v(0.0, 0.0), v(100.0, 100.0)), 51);
+        let lines = grid.boundary();
+        assert_eq!(lines.len(), 1);
+        let line = &lines[0];
+        // Closed: the loop repeats its first point.
+        assert!(line.first().unwrap().distance(line.last().unwrap()) < 1e-9);
+        // Every crossing sits on the box perimeter, up to grid error.
+        for p in line {
+            assert!(sys.margin(p).abs() < 2.0, "off-boundary point {p}");
+        }
+    }
+
+    #[test]
+    fn open_boundaries_stay_open() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(crate::constraint::HalfspaceConstraint::new(
+            v(1.0, 0.0),
+            50.0,
+        ));
+        let grid = sample_feasibility(&sys, &Bounds::new(v(0.0, 0.0), v(100.0, 100.0)), 26);
+        let lines = grid.boundary();
+        assert_eq!(lines.len(), 1);
+        let line = &lines[0];
+        assert!(line.first().unwrap().distance(line.last().unwrap()) > 1.0);
+        for p in line {
+            assert!((p.get(0) - 50.0).abs() < 1e-6, "crossing off x = 50: {p}");
+        }
+    }
+
+    #[test]
+    fn uniform_windows_have_no_boundary() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(1000.0, 1000.0))));
+        // Fully feasible window, well inside the box.
+        let grid = sample_feasibility(&sys, &Bounds::new(v(100.0, 100.0), v(200.0, 200.0)), 9);
+        assert!(grid.boundary().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "at least two samples")]
+    fn degenerate_resolution_is_rejected() {
+        sample_feasibility(
+            &boxed_system(),
+            &Bounds::new(v(0.0, 0.0), v(10.0, 10.0)),
+            1,
+        );
+    }
+}
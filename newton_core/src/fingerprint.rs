@@ -0,0 +1,154 @@
+//! Stable 64-bit fingerprints of engine state.
+//!
+//! Cache keys, replay-log integrity checks, and change detection
+//! between processes all need "same bits, same number". The standard
+//! library's hasher only promises determinism within one build, so
+//! fingerprints use an explicit FNV-1a over exact `f64` bit patterns:
+//! bit-equal inputs fingerprint identically on every platform and
+//! toolchain, and `-0.0` versus `0.0` (or differing NaN payloads) are
+//! deliberately distinct — a fingerprint certifies bits, not numeric
+//! equivalence.
+
+use crate::constraint::ConstraintSystem;
+use crate::linalg::Vector;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Incremental FNV-1a 64-bit hasher over `u64` words.
+///
+/// Kept byte-oriented internally so the result matches the reference
+/// FNV-1a stream of the words' little-endian bytes.
+#[derive(Debug, Clone)]
+pub struct Fingerprint64 {
+    state: u64,
+}
+
+impl Fingerprint64 {
+    pub fn new() -> Self {
+        Fingerprint64 { state: FNV_OFFSET }
+    }
+
+    /// Folds one word into the fingerprint.
+    pub fn write_u64(&mut self, word: u64) {
+        for byte in word.to_le_bytes() {
+            self.state ^= byte as u64;
+            self.state = self.state.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    /// Folds one float in by its exact bit pattern.
+    pub fn write_f64(&mut self, value: f64) {
+        self.write_u64(value.to_bits());
+    }
+
+    /// The fingerprint of everything written so far.
+    pub fn finish(&self) -> u64 {
+        self.state
+    }
+}
+
+impl Default for Fingerprint64 {
+    fn default() -> Self {
+        Fingerprint64::new()
+    }
+}
+
+/// Stable fingerprint of a state vector: its dimension and the exact
+/// bit pattern of every component.
+pub fn fingerprint_vector(v: &Vector) -> u64 {
+    let mut fp = Fingerprint64::new();
+    fp.write_u64(v.dim() as u64);
+    for x in v.as_slice() {
+        fp.write_f64(*x);
+    }
+    fp.finish()
+}
+
+/// Stable fingerprint of a constraint system's composition: dimension,
+/// constraint count, and each constraint's
+/// [`structural_key`](crate::constraint::Constraint::structural_key)
+/// in order. Returns `None` when any constraint opts out of keying —
+/// an unkeyed system must never collide with a keyed one.
+pub fn fingerprint_system(system: &ConstraintSystem) -> Option<u64> {
+    let mut fp = Fingerprint64::new();
+    fp.write_u64(system.dim() as u64);
+    fp.write_u64(system.constraints().len() as u64);
+    for c in system.constraints() {
+        fp.write_u64(c.structural_key()?);
+    }
+    Some(fp.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bounds::Bounds;
+    use crate::constraint::{BoxConstraint, HalfspaceConstraint};
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    #[test]
+    fn vector_fingerprints_are_bit_exact() {
+        assert_eq!(fingerprint_vector(&v(1.0, 2.0)), fingerprint_vector(&v(1.0, 2.0)));
+        assert_ne!(fingerprint_vector(&v(1.0, 2.0)), fingerprint_vector(&v(2.0, 1.0)));
+        // Numerically equal but bit-distinct: a fingerprint certifies
+        // bits.
+        assert_ne!(fingerprint_vector(&v(0.0, 0.0)), fingerprint_vector(&v(-0.0, 0.0)));
+        // Dimension participates, so a zero-padded vector is distinct.
+        assert_ne!(
+            fingerprint_vector(&Vector::zeros(2)),
+            fingerprint_vector(&Vector::zeros(3))
+        );
+    }
+
+    #[test]
+    fn system_fingerprints_track_composition() {
+        let bounds = Bounds::new(v(0.0, 0.0), v(100.0, 100.0));
+        let mut a = ConstraintSystem::new(2);
+        a.add(BoxConstraint::new(bounds.clone()));
+        a.add(HalfspaceConstraint::new(v(1.0, 0.0), 50.0));
+        let mut b = ConstraintSystem::new(2);
+        b.add(BoxConstraint::new(bounds.clone()));
+        b.add(HalfspaceConstraint::new(v(1.0, 0.0), 50.0));
+        assert_eq!(fingerprint_system(&a), fingerprint_system(&b));
+
+        // Order is part of the composition (projection sweeps see it).
+        let mut reversed = ConstraintSystem::new(2);
+        reversed.add(HalfspaceConstraint::new(v(1.0, 0.0), 50.0));
+        reversed.add(BoxConstraint::new(bounds));
+        assert_ne!(fingerprint_system(&a), fingerprint_system(&reversed));
+    }
+
+    #[test]
+    fn unkeyed_constraints_disable_the_fingerprint() {
+        struct Opaque;
+        impl crate::constraint::Constraint for Opaque {
+            fn dim(&self) -> usize {
+                2
+            }
+            fn contains(&self, _point: &Vector) -> bool {
+                true
+            }
+            fn project(&self, point: &Vector) -> Vector {
+                point.clone()
+            }
+            fn as_any(&self) -> &dyn std::any::Any {
+                self
+            }
+        }
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(Opaque);
+        assert_eq!(fingerprint_system(&sys), None);
+    }
+
+    #[test]
+    fn known_answer_is_pinned() {
+        // Guards cross-process stability: this value must never change
+        // for existing inputs, or persisted cache keys and replay logs
+        // break.
+        assert_eq!(fingerprint_vector(&Vector::zeros(1)), 0x3922_09f1_4dea_4c24);
+    }
+}
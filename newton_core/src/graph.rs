@@ -0,0 +1,218 @@
+//! Dependency tracking between constraints and objects.
+//!
+//! In a document, constraints bind objects together: an alignment
+//! constraint reads one object's edge and positions another's. Editing
+//! an object therefore only invalidates the objects it is connected to
+//! through some chain of constraints — its *connected component* — and
+//! within that component updates must flow in dependency order, like a
+//! spreadsheet recalculation. [`DependencyGraph`] records which objects
+//! each constraint reads (inputs) and writes (outputs), answers "what
+//! must re-solve after this edit", and produces a deterministic
+//! propagation order, refusing with [`CycleError`] when the bindings
+//! feed back into themselves.
+//!
+//! All traversals iterate objects in ascending id order, so the same
+//! document yields the same order on every run.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::object::ObjectId;
+
+/// One constraint's reads and writes, by object id. The `constraint`
+/// field is the caller's identifier for it — typically its index in a
+/// [`ConstraintSystem`](crate::constraint::ConstraintSystem).
+#[derive(Debug, Clone)]
+pub struct Binding {
+    pub constraint: usize,
+    pub inputs: Vec<ObjectId>,
+    pub outputs: Vec<ObjectId>,
+}
+
+/// The bindings form a directed cycle: propagation from any object on
+/// it would never terminate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError {
+    /// Objects left unordered by the cycle, ascending.
+    pub objects: Vec<ObjectId>,
+}
+
+/// Directed bipartite graph between constraints and the objects they
+/// read and write.
+#[derive(Debug, Clone, Default)]
+pub struct DependencyGraph {
+    bindings: Vec<Binding>,
+}
+
+impl DependencyGraph {
+    pub fn new() -> Self {
+        DependencyGraph::default()
+    }
+
+    /// Records that `constraint` reads `inputs` and writes `outputs`.
+    pub fn bind(&mut self, constraint: usize, inputs: Vec<ObjectId>, outputs: Vec<ObjectId>) {
+        self.bindings.push(Binding {
+            constraint,
+            inputs,
+            outputs,
+        });
+    }
+
+    pub fn bindings(&self) -> &[Binding] {
+        &self.bindings
+    }
+
+    /// Every object connected to `object` through any chain of
+    /// bindings, direction ignored, including `object` itself.
+    /// Ascending id order.
+    pub fn component(&self, object: ObjectId) -> Vec<ObjectId> {
+        let mut seen = BTreeSet::new();
+        seen.insert(object);
+        let mut frontier = vec![object];
+        while let Some(o) = frontier.pop() {
+            for b in &self.bindings {
+                if b.inputs.contains(&o) || b.outputs.contains(&o) {
+                    for n in b.inputs.iter().chain(&b.outputs) {
+                        if seen.insert(*n) {
+                            frontier.push(*n);
+                        }
+                    }
+                }
+            }
+        }
+        seen.into_iter().collect()
+    }
+
+    /// Identifiers of every constraint touching `object`'s component,
+    /// ascending and deduplicated — the set to re-solve after editing
+    /// `object`.
+    pub fn constraints_in_component(&self, object: ObjectId) -> Vec<usize> {
+        let component: BTreeSet<ObjectId> = self.component(object).into_iter().collect();
+        let mut out: BTreeSet<usize> = BTreeSet::new();
+        for b in &self.bindings {
+            if b.inputs.iter().chain(&b.outputs).any(|o| component.contains(o)) {
+                out.insert(b.constraint);
+            }
+        }
+        out.into_iter().collect()
+    }
+
+    /// The order in which objects downstream of an edit to `edited`
+    /// must re-solve: a topological order of the objects reachable
+    /// through input→output edges, starting with `edited` itself.
+    /// Deterministic (ties break by ascending id); [`CycleError`] when
+    /// the reachable bindings contain a directed cycle.
+    pub fn propagation_order(&self, edited: ObjectId) -> Result<Vec<ObjectId>, CycleError> {
+        // Directed edges over the reachable subgraph.
+        let mut successors: BTreeMap<ObjectId, BTreeSet<ObjectId>> = BTreeMap::new();
+        let mut reachable = BTreeSet::new();
+        reachable.insert(edited);
+        let mut frontier = vec![edited];
+        while let Some(o) = frontier.pop() {
+            for b in &self.bindings {
+                if b.inputs.contains(&o) {
+                    for out in &b.outputs {
+                        successors.entry(o).or_default().insert(*out);
+                        if reachable.insert(*out) {
+                            frontier.push(*out);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Kahn's algorithm, always taking the smallest ready id.
+        let mut indegree: BTreeMap<ObjectId, usize> =
+            reachable.iter().map(|o| (*o, 0)).collect();
+        for outs in successors.values() {
+            for o in outs {
+                *indegree.get_mut(o).unwrap() += 1;
+            }
+        }
+        // The edited object starts the propagation even if some binding
+        // also writes it from elsewhere in the reachable set.
+        let mut order = Vec::with_capacity(reachable.len());
+        let mut ready: BTreeSet<ObjectId> = indegree
+            .iter()
+            .filter(|(o, d)| **d == 0 || **o == edited)
+            .map(|(o, _)| *o)
+            .collect();
+        while let Some(&o) = ready.iter().next() {
+            ready.remove(&o);
+            if !order.contains(&o) {
+                order.push(o);
+            }
+            for out in successors.get(&o).into_iter().flatten() {
+                let d = indegree.get_mut(out).unwrap();
+                *d = d.saturating_sub(1);
+                if *d == 0 && !order.contains(out) {
+                    ready.insert(*out);
+                }
+            }
+        }
+        if order.len() < reachable.len() {
+            let ordered: BTreeSet<ObjectId> = order.iter().copied().collect();
+            return Err(CycleError {
+                objects: reachable.difference(&ordered).copied().collect(),
+            });
+        }
+        Ok(order)
+    }
+
+    /// True if any directed cycle exists anywhere in the graph.
+    pub fn has_cycle(&self) -> bool {
+        let objects: BTreeSet<ObjectId> = self
+            .bindings
+            .iter()
+            .flat_map(|b| b.inputs.iter().chain(&b.outputs).copied())
+            .collect();
+        objects.iter().any(|o| self.propagation_order(*o).is_err())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn component_ignores_unconnected_objects() {
+        let mut g = DependencyGraph::new();
+        g.bind(0, vec![1], vec![2]);
+        g.bind(1, vec![2], vec![3]);
+        g.bind(2, vec![10], vec![11]);
+        assert_eq!(g.component(1), vec![1, 2, 3]);
+        assert_eq!(g.component(11), vec![10, 11]);
+        assert_eq!(g.constraints_in_component(3), vec![0, 1]);
+    }
+
+    #[test]
+    fn propagation_order_is_topological_and_deterministic() {
+        let mut g = DependencyGraph::new();
+        // 1 feeds 2 and 3; both feed 4.
+        g.bind(0, vec![1], vec![2, 3]);
+        g.bind(1, vec![2], vec![4]);
+        g.bind(2, vec![3], vec![4]);
+        let order = g.propagation_order(1).unwrap();
+        assert_eq!(order, vec![1, 2, 3, 4]);
+        // Editing a leaf touches only itself.
+        assert_eq!(g.propagation_order(4).unwrap(), vec![4]);
+    }
+
+    #[test]
+    fn cycles_are_detected() {
+        let mut g = DependencyGraph::new();
+        g.bind(0, vec![1], vec![2]);
+        g.bind(1, vec![2], vec![3]);
+        g.bind(2, vec![3], vec![2]);
+        let err = g.propagation_order(1).unwrap_err();
+        assert_eq!(err.objects, vec![2, 3]);
+        assert!(g.has_cycle());
+    }
+
+    #[test]
+    fn acyclic_graph_reports_no_cycle() {
+        let mut g = DependencyGraph::new();
+        g.bind(0, vec![1], vec![2]);
+        g.bind(1, vec![1], vec![3]);
+        assert!(!g.has_cycle());
+    }
+}
@@ -0,0 +1,338 @@
+//! Group moves suggested as one transform.
+//!
+//! Running [`suggest`](crate::suggest::suggest) per member tears a
+//! group apart: each object projects independently, relative spacing
+//! collapses against whichever boundary each one meets first. A group
+//! move should be a single transform — the same translation (and
+//! optionally uniform scale) applied to every member — so the thing to
+//! project onto feasibility is the *transform*, not the members.
+//!
+//! Translations get the exact treatment: a translation `t` is valid
+//! exactly when every member's constraint set, re-expressed around
+//! that member, admits `t`. Those re-expressed constraints form an
+//! ordinary [`ConstraintSystem`] over translation space, so the full
+//! suggestion search runs there unchanged and the group glides and
+//! slides as one object. Scaling is nonlinear in the members'
+//! positions; combined transforms instead blend deterministically
+//! toward the identity until every member fits (the identity is
+//! feasible whenever the current positions are).
+
+use crate::constraint::{Constraint, ConstraintRef, ConstraintSystem};
+use crate::linalg::Vector;
+use crate::rank::RankingCriteria;
+use crate::suggest::{suggest, SuggestionQuality};
+
+/// Blend bisection steps for combined translate/scale transforms;
+/// resolves the feasible blend to one part in 2³².
+const BLEND_STEPS: usize = 32;
+
+/// A rigid group move: translate, and uniformly scale about a pivot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupTransform {
+    /// Common displacement applied to every member.
+    pub translation: Vector,
+    /// Uniform scale factor about the pivot; `1` leaves spacing alone.
+    pub scale: f64,
+}
+
+impl GroupTransform {
+    /// The do-nothing transform.
+    pub fn identity(dim: usize) -> Self {
+        GroupTransform {
+            translation: Vector::zeros(dim),
+            scale: 1.0,
+        }
+    }
+
+    /// A pure translation.
+    pub fn translate(translation: Vector) -> Self {
+        GroupTransform {
+            translation,
+            scale: 1.0,
+        }
+    }
+
+    /// Where this transform sends `point`, scaling about `pivot`.
+    pub fn apply(&self, pivot: &Vector, point: &Vector) -> Vector {
+        pivot
+            .add(&point.sub(pivot).scale(self.scale))
+            .add(&self.translation)
+    }
+
+    /// The transform `fraction` of the way from the identity to
+    /// `self`.
+    fn toward(&self, fraction: f64) -> GroupTransform {
+        GroupTransform {
+            translation: self.translation.scale(fraction),
+            scale: 1.0 + fraction * (self.scale - 1.0),
+        }
+    }
+}
+
+/// A suggested group move.
+#[derive(Debug, Clone)]
+pub struct GroupResponse {
+    /// The nearest valid transform found.
+    pub transform: GroupTransform,
+    /// Each member's position under that transform, in input order.
+    pub positions: Vec<Vector>,
+    /// Faithfulness of the transform to the requested one.
+    pub quality: SuggestionQuality,
+}
+
+/// A member's constraint re-expressed over translation space: `t`
+/// satisfies it exactly when `base + t` satisfies the inner
+/// constraint.
+struct OffsetConstraint {
+    inner: ConstraintRef,
+    base: Vector,
+}
+
+impl Constraint for OffsetConstraint {
+    fn dim(&self) -> usize {
+        self.inner.dim()
+    }
+
+    fn contains(&self, t: &Vector) -> bool {
+        self.inner.contains(&self.base.add(t))
+    }
+
+    fn project(&self, t: &Vector) -> Vector {
+        self.inner.project(&self.base.add(t)).sub(&self.base)
+    }
+
+    fn signed_distance(&self, t: &Vector) -> f64 {
+        self.inner.signed_distance(&self.base.add(t))
+    }
+
+    fn boundary_normal(&self, t: &Vector) -> Option<Vector> {
+        self.inner.boundary_normal(&self.base.add(t))
+    }
+
+    fn interior_point(&self) -> Option<Vector> {
+        self.inner.interior_point().map(|p| p.sub(&self.base))
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// The system every member must satisfy, re-expressed over translation
+/// space.
+fn translation_space(system: &ConstraintSystem, positions: &[Vector]) -> ConstraintSystem {
+    let mut out = ConstraintSystem::new(system.dim());
+    out.set_search_policy(system.search_policy().clone());
+    out.set_numeric_policy(system.numeric_policy().clone());
+    for p in positions {
+        for c in system.constraints() {
+            out.add(OffsetConstraint {
+                inner: c.clone(),
+                base: p.clone(),
+            });
+        }
+    }
+    out
+}
+
+/// Suggests the valid group translation nearest to `delta`: the full
+/// candidate search runs in translation space, so a blocked group
+/// slides along boundaries exactly as a single dragged object would.
+/// Panics on dimension mismatches or an empty group.
+pub fn suggest_group_translation(
+    system: &ConstraintSystem,
+    positions: &[Vector],
+    delta: &Vector,
+    criteria: &RankingCriteria,
+) -> GroupResponse {
+    assert!(!positions.is_empty(), "group must have at least one member");
+    assert_eq!(delta.dim(), system.dim(), "delta dimension mismatch");
+    let space = translation_space(system, positions);
+    let response = suggest(&space, &Vector::zeros(system.dim()), delta, criteria);
+    let transform = GroupTransform::translate(response.position.clone());
+    GroupResponse {
+        positions: positions.iter().map(|p| p.add(&response.position)).collect(),
+        transform,
+        quality: response.quality,
+    }
+}
+
+/// Suggests the valid group transform nearest to `intended`, scaling
+/// about `pivot`.
+///
+/// Pure translations delegate to [`suggest_group_translation`]. With a
+/// scale component the transform blends toward the identity by
+/// bisection until every member is feasible; when even the identity is
+/// infeasible (the current positions already violate), the identity is
+/// returned as best effort rather than inventing per-member motion.
+pub fn suggest_group_transform(
+    system: &ConstraintSystem,
+    positions: &[Vector],
+    pivot: &Vector,
+    intended: &GroupTransform,
+    criteria: &RankingCriteria,
+) -> GroupResponse {
+    assert!(!positions.is_empty(), "group must have at least one member");
+    assert_eq!(pivot.dim(), system.dim(), "pivot dimension mismatch");
+    if intended.scale == 1.0 {
+        return suggest_group_translation(system, positions, &intended.translation, criteria);
+    }
+    let feasible_at = |fraction: f64| {
+        let t = intended.toward(fraction);
+        positions.iter().all(|p| system.is_feasible(&t.apply(pivot, p)))
+    };
+    if feasible_at(1.0) {
+        return respond(positions, pivot, intended.clone(), SuggestionQuality::Exact);
+    }
+    if !feasible_at(0.0) {
+        return respond(
+            positions,
+            pivot,
+            GroupTransform::identity(system.dim()),
+            SuggestionQuality::BestEffort,
+        );
+    }
+    // Largest feasible blend by bisection. Feasibility need not be
+    // monotone in the blend for nonconvex systems, so this finds *a*
+    // feasible frontier deterministically, not a global optimum.
+    let (mut lo, mut hi) = (0.0_f64, 1.0_f64);
+    for _ in 0..BLEND_STEPS {
+        let mid = (lo + hi) / 2.0;
+        if feasible_at(mid) {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    respond(
+        positions,
+        pivot,
+        intended.toward(lo),
+        SuggestionQuality::Projected,
+    )
+}
+
+fn respond(
+    positions: &[Vector],
+    pivot: &Vector,
+    transform: GroupTransform,
+    quality: SuggestionQuality,
+) -> GroupResponse {
+    GroupResponse {
+        positions: positions.iter().map(|p| transform.apply(pivot, p)).collect(),
+        transform,
+        quality,
+    }
+}
+
+/// Convenience: the members' centroid, the usual scaling pivot.
+pub fn centroid(positions: &[Vector]) -> Vector {
+    assert!(!positions.is_empty(), "centroid of an empty group");
+    let mut sum = Vector::zeros(positions[0].dim());
+    for p in positions {
+        sum = sum.add(p);
+    }
+    sum.scale(1.0 / positions.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bounds::Bounds;
+    use crate::constraint::{BoxConstraint, CollisionConstraint};
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    fn canvas() -> ConstraintSystem {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(100.0, 100.0))));
+        sys
+    }
+
+    #[test]
+    fn blocked_translation_preserves_spacing() {
+        let sys = canvas();
+        let positions = [v(60.0, 50.0), v(90.0, 50.0)];
+        // 30 to the right would push the trailing member to x = 120;
+        // only 10 fits.
+        let r = suggest_group_translation(
+            &sys,
+            &positions,
+            &v(30.0, 0.0),
+            &RankingCriteria::default(),
+        );
+        assert_eq!(r.quality, SuggestionQuality::Projected);
+        assert!(r.transform.translation.distance(&v(10.0, 0.0)) < 1e-6);
+        assert!((r.positions[1].get(0) - r.positions[0].get(0) - 30.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn group_slides_along_boundaries_as_one() {
+        let sys = canvas();
+        let positions = [v(60.0, 40.0), v(90.0, 40.0)];
+        // Diagonal drag into the right wall: the x component clamps,
+        // the y component survives.
+        let r = suggest_group_translation(
+            &sys,
+            &positions,
+            &v(30.0, 20.0),
+            &RankingCriteria::default(),
+        );
+        assert!((r.positions[1].get(0) - 100.0).abs() < 1e-6);
+        assert!(r.transform.translation.get(1) > 5.0);
+    }
+
+    #[test]
+    fn feasible_moves_come_back_exact() {
+        let sys = canvas();
+        let r = suggest_group_translation(
+            &sys,
+            &[v(10.0, 10.0), v(20.0, 20.0)],
+            &v(5.0, 5.0),
+            &RankingCriteria::default(),
+        );
+        assert_eq!(r.quality, SuggestionQuality::Exact);
+        assert_eq!(r.positions[0], v(15.0, 15.0));
+    }
+
+    #[test]
+    fn scale_blends_until_the_group_fits() {
+        let sys = canvas();
+        let positions = [v(40.0, 50.0), v(60.0, 50.0)];
+        let pivot = centroid(&positions);
+        // Scaling 10x about (50, 50) would put members at x = -50 and
+        // x = 150; the canvas admits at most 5x (x = 0 and x = 100).
+        let intended = GroupTransform {
+            translation: Vector::zeros(2),
+            scale: 10.0,
+        };
+        let r = suggest_group_transform(&sys, &positions, &pivot, &intended, &RankingCriteria::default());
+        assert_eq!(r.quality, SuggestionQuality::Projected);
+        assert!((r.transform.scale - 5.0).abs() < 1e-6);
+        assert!((r.positions[0].get(0) - 0.0).abs() < 1e-6);
+        assert!((r.positions[1].get(0) - 100.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn infeasible_start_returns_identity_best_effort() {
+        let mut sys = canvas();
+        sys.add(CollisionConstraint::new(Bounds::new(v(0.0, 0.0), v(30.0, 30.0))));
+        let positions = [v(10.0, 10.0)];
+        let intended = GroupTransform {
+            translation: v(1.0, 0.0),
+            scale: 2.0,
+        };
+        let r = suggest_group_transform(
+            &sys,
+            &positions,
+            &centroid(&positions),
+            &intended,
+            &RankingCriteria::default(),
+        );
+        assert_eq!(r.quality, SuggestionQuality::BestEffort);
+        assert_eq!(r.transform, GroupTransform::identity(2));
+        assert_eq!(r.positions[0], positions[0]);
+    }
+}
@@ -0,0 +1,205 @@
+//! Smart alignment guides.
+//!
+//! Design tools snap a dragged object to relationships with its
+//! neighbours: edges flush, centers aligned, spacing continued. This
+//! module derives those relationships from a [`Scene`] on the fly and
+//! emits them as candidate positions for the dragged object, which
+//! callers feed into the suggestion search as extra seeds
+//! ([`crate::suggest::suggest_with_seeds`]). Guides are ephemeral: they
+//! are recomputed per gesture, never stored in the constraint system.
+
+use crate::linalg::Vector;
+use crate::object::{NTObject, ObjectId, Scene};
+
+/// The relationship a guide encodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuideKind {
+    /// An edge of the moving object flush with an edge of a neighbour.
+    EdgeAlign,
+    /// Centers aligned on one axis.
+    CenterAlign,
+    /// Spacing between two neighbours continued to the moving object.
+    EqualSpacing,
+}
+
+/// One snap opportunity for the moving object.
+#[derive(Debug, Clone)]
+pub struct Guide {
+    pub kind: GuideKind,
+    /// Axis the relationship holds on.
+    pub axis: usize,
+    /// Position (at the moving object's anchor) that realises the
+    /// relationship, with the remaining axes taken from the intent.
+    pub position: Vector,
+}
+
+/// Tuning for guide generation.
+#[derive(Debug, Clone)]
+pub struct GuideOptions {
+    /// Guides whose position is further than this from the intent are
+    /// dropped.
+    pub snap_distance: f64,
+    pub edges: bool,
+    pub centers: bool,
+    pub spacing: bool,
+}
+
+impl Default for GuideOptions {
+    fn default() -> Self {
+        GuideOptions {
+            snap_distance: 8.0,
+            edges: true,
+            centers: true,
+            spacing: true,
+        }
+    }
+}
+
+/// Generates alignment guides for moving `moving` to `intent` within
+/// `scene`. Returns an empty vec if the object is unknown.
+pub fn generate_guides(
+    scene: &Scene,
+    moving: ObjectId,
+    intent: &Vector,
+    options: &GuideOptions,
+) -> Vec<Guide> {
+    let Some(subject) = scene.get(moving) else {
+        return Vec::new();
+    };
+    let dim = intent.dim();
+    let mut guides = Vec::new();
+    let others: Vec<&NTObject> = scene
+        .objects()
+        .iter()
+        .filter(|o| o.id() != moving)
+        .collect();
+
+    // Where the subject's rectangle would sit if the drag landed
+    // exactly on the intent.
+    let min_at = |position: &Vector| subject.anchor.to_min_corner(position, &subject.size);
+    let intent_min = min_at(intent);
+
+    let mut push = |kind: GuideKind, axis: usize, value_min: f64| {
+        // Candidate: intent with `axis` adjusted so the subject's min
+        // corner lands on `value_min`.
+        let mut min = intent_min.clone();
+        min.set(axis, value_min);
+        let position = subject.anchor.from_min_corner(&min, &subject.size);
+        if position.distance(intent) <= options.snap_distance {
+            guides.push(Guide { kind, axis, position });
+        }
+    };
+
+    for axis in 0..dim {
+        for other in &others {
+            let ob = other.bounds();
+            let (o_min, o_max) = (ob.min().get(axis), ob.max().get(axis));
+            let o_center = (o_min + o_max) / 2.0;
+            let s_size = subject.size.get(axis);
+            if options.edges {
+                // Flush min-to-min, min-to-max, max-to-min, max-to-max.
+                push(GuideKind::EdgeAlign, axis, o_min);
+                push(GuideKind::EdgeAlign, axis, o_max);
+                push(GuideKind::EdgeAlign, axis, o_min - s_size);
+                push(GuideKind::EdgeAlign, axis, o_max - s_size);
+            }
+            if options.centers {
+                push(GuideKind::CenterAlign, axis, o_center - s_size / 2.0);
+            }
+        }
+        if options.spacing {
+            // Continue the rhythm of every neighbour pair: place the
+            // subject so its center extends their center spacing.
+            for a in &others {
+                for b in &others {
+                    if a.id() == b.id() {
+                        continue;
+                    }
+                    let ac = a.bounds().center().get(axis);
+                    let bc = b.bounds().center().get(axis);
+                    let continued = bc + (bc - ac);
+                    let s_size = subject.size.get(axis);
+                    push(GuideKind::EqualSpacing, axis, continued - s_size / 2.0);
+                }
+            }
+        }
+    }
+    guides
+}
+
+/// Candidate positions of the generated guides, ready to seed the
+/// suggestion search.
+pub fn guide_seeds(guides: &[Guide]) -> Vec<Vector> {
+    guides.iter().map(|g| g.position.clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    fn scene_with_neighbour() -> (Scene, ObjectId) {
+        let mut scene = Scene::new(2);
+        // Neighbour: 10x10 rectangle centered at (50, 50).
+        let n = scene.add("neighbour", v(50.0, 50.0));
+        scene.get_mut(n).unwrap().size = v(10.0, 10.0);
+        // Subject: 10x10 rectangle being dragged.
+        let s = scene.add("subject", v(0.0, 0.0));
+        scene.get_mut(s).unwrap().size = v(10.0, 10.0);
+        (scene, s)
+    }
+
+    #[test]
+    fn center_alignment_guide_appears_within_snap_distance() {
+        let (scene, subject) = scene_with_neighbour();
+        // Dragging near x = 50: center-to-center on axis 0 should fire.
+        let intent = v(48.0, 80.0);
+        let guides = generate_guides(&scene, subject, &intent, &GuideOptions::default());
+        assert!(guides
+            .iter()
+            .any(|g| g.kind == GuideKind::CenterAlign
+                && g.axis == 0
+                && g.position == v(50.0, 80.0)));
+    }
+
+    #[test]
+    fn edge_alignment_guide_lands_flush() {
+        let (scene, subject) = scene_with_neighbour();
+        // Neighbour's left edge is at 45; dragging our left edge nearby.
+        let intent = v(51.0, 80.0); // our min corner at 46
+        let guides = generate_guides(&scene, subject, &intent, &GuideOptions::default());
+        // min-to-min flush: our min corner at 45, i.e. center at 50.
+        assert!(guides
+            .iter()
+            .any(|g| g.kind == GuideKind::EdgeAlign && g.axis == 0 && g.position == v(50.0, 80.0)));
+    }
+
+    #[test]
+    fn far_intents_produce_no_guides() {
+        let (scene, subject) = scene_with_neighbour();
+        let guides = generate_guides(
+            &scene,
+            subject,
+            &v(200.0, 200.0),
+            &GuideOptions::default(),
+        );
+        assert!(guides.is_empty());
+    }
+
+    #[test]
+    fn spacing_guide_continues_the_rhythm() {
+        let (mut scene, subject) = scene_with_neighbour();
+        // Second neighbour centered at (70, 50): spacing 20 on axis 0.
+        let n2 = scene.add("neighbour2", v(70.0, 50.0));
+        scene.get_mut(n2).unwrap().size = v(10.0, 10.0);
+        // Continuing the rhythm puts our center at x = 90.
+        let intent = v(88.0, 50.0);
+        let guides = generate_guides(&scene, subject, &intent, &GuideOptions::default());
+        assert!(guides
+            .iter()
+            .any(|g| g.kind == GuideKind::EqualSpacing && g.position == v(90.0, 50.0)));
+    }
+}
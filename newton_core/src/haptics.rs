@@ -0,0 +1,232 @@
+//! Haptic waveform synthesis.
+//!
+//! [`FGState::haptic_amplitude`] gives a per-frame level; actuators
+//! want more than that: a continuous amplitude envelope plus sharp
+//! transients when the object locks onto a boundary. This module does
+//! that mapping once so host apps feed frames in and actuator commands
+//! out, instead of each reinventing it against `haptic_amplitude()`.
+
+use crate::constraint::ConstraintSystem;
+use crate::fgstate::{FGConfig, FGState};
+use crate::linalg::Vector;
+
+/// A transient "click" event layered on top of the continuous envelope,
+/// in the intensity/sharpness parameter space used by mobile haptic
+/// engines.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transient {
+    /// Strength of the click in `[0, 1]`.
+    pub intensity: f64,
+    /// Crispness of the click in `[0, 1]`; higher is more percussive.
+    pub sharpness: f64,
+}
+
+/// Device-ready parameters for one frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HapticFrame {
+    /// Continuous envelope level in `[0, 1]`.
+    pub amplitude: f64,
+    /// A click to fire this frame, if any.
+    pub transient: Option<Transient>,
+}
+
+/// Stateful per-gesture synthesiser: feed one [`FGState`] per frame,
+/// get actuator parameters back.
+///
+/// Transients fire on transitions *into* `Exact` (the moment the
+/// object locks onto a boundary) and, more gently, on release back out
+/// of `Exact`. Steady states produce only the envelope.
+#[derive(Debug, Clone)]
+pub struct WaveformSynth {
+    config: FGConfig,
+    previous: Option<FGState>,
+}
+
+/// Click fired when the object locks onto a boundary.
+const LOCK_TRANSIENT: Transient = Transient {
+    intensity: 1.0,
+    sharpness: 0.8,
+};
+/// Softer click fired when the object releases from a boundary.
+const RELEASE_TRANSIENT: Transient = Transient {
+    intensity: 0.4,
+    sharpness: 0.3,
+};
+
+impl WaveformSynth {
+    pub fn new(config: FGConfig) -> Self {
+        WaveformSynth {
+            config,
+            previous: None,
+        }
+    }
+
+    /// Synthesises one frame from the current engagement state.
+    pub fn feed(&mut self, state: FGState) -> HapticFrame {
+        let transient = match (self.previous, state) {
+            (Some(FGState::Exact), FGState::Exact) => None,
+            (_, FGState::Exact) => Some(LOCK_TRANSIENT),
+            (Some(FGState::Exact), _) => Some(RELEASE_TRANSIENT),
+            _ => None,
+        };
+        self.previous = Some(state);
+        HapticFrame {
+            amplitude: self.config.amplitude(state),
+            transient,
+        }
+    }
+
+    /// Forgets gesture history, e.g. on touch-up.
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+}
+
+/// Checks the docs' monotonicity claim: along the straight segment
+/// from `from` to `to`, the haptic amplitude of the engagement state
+/// must never decrease. Returns the index of the first offending
+/// sample, or `None` when the claim holds over all `samples` points.
+///
+/// Engagement at a point is classified from the system's margin
+/// against the search radius, the same state the overlay layers use
+/// ([`crate::field`]). The claim is a theorem when approaching a
+/// convex system from deep slack; for nonconvex systems — or a start
+/// point already pressed against a different boundary — a path can
+/// legitimately relax before it violates, which is exactly what this
+/// routine exists to detect. Panics on dimension mismatches or fewer
+/// than two samples.
+pub fn verify_amplitude_monotone(
+    system: &ConstraintSystem,
+    from: &Vector,
+    to: &Vector,
+    samples: usize,
+    config: &FGConfig,
+) -> Option<usize> {
+    assert_eq!(from.dim(), system.dim(), "path start dimension mismatch");
+    assert_eq!(to.dim(), system.dim(), "path end dimension mismatch");
+    assert!(samples >= 2, "need at least two samples along the path");
+    let radius = system.search_policy().search_radius();
+    let mut previous = f64::NEG_INFINITY;
+    for i in 0..samples {
+        let t = i as f64 / (samples - 1) as f64;
+        let p = from.lerp(to, t);
+        let ratio = (radius - system.margin(&p)) / radius;
+        let amplitude = config.amplitude(config.classify_ratio(ratio));
+        if amplitude < previous {
+            return Some(i);
+        }
+        previous = amplitude;
+    }
+    None
+}
+
+/// Batch helper: synthesises a whole recorded state sequence.
+pub fn synthesize(states: &[FGState], config: &FGConfig) -> Vec<HapticFrame> {
+    let mut synth = WaveformSynth::new(config.clone());
+    states.iter().map(|&s| synth.feed(s)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bounds::Bounds;
+    use crate::constraint::{BoxConstraint, CollisionConstraint};
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    #[test]
+    fn amplitude_is_monotone_into_a_convex_wall() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(100.0, 100.0))));
+        let hit = verify_amplitude_monotone(
+            &sys,
+            &v(50.0, 50.0),
+            &v(130.0, 50.0),
+            64,
+            &FGConfig::default(),
+        );
+        assert_eq!(hit, None);
+    }
+
+    #[test]
+    fn rays_from_deep_slack_into_violation_are_monotone() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(100.0, 100.0))));
+        // Deterministic sweep of directions out of the deepest slack.
+        let mut seed = 0xDEAD_BEEF_u64;
+        let mut next = move || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as f64 / (1u64 << 31) as f64
+        };
+        for _ in 0..50 {
+            let angle = next() * std::f64::consts::TAU;
+            let to = v(50.0 + 120.0 * angle.cos(), 50.0 + 120.0 * angle.sin());
+            let hit =
+                verify_amplitude_monotone(&sys, &v(50.0, 50.0), &to, 64, &FGConfig::default());
+            assert_eq!(hit, None, "amplitude dipped on the ray toward {to:?}");
+        }
+    }
+
+    #[test]
+    fn grazing_an_obstacle_is_caught() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(CollisionConstraint::new(Bounds::new(
+            v(40.0, 30.0),
+            v(60.0, 50.0),
+        )));
+        // The path closes on the obstacle and then pulls away: the
+        // amplitude legitimately relaxes mid-path, and the checker
+        // must say so rather than bless the claim.
+        let hit = verify_amplitude_monotone(
+            &sys,
+            &v(0.0, 20.0),
+            &v(100.0, 20.0),
+            64,
+            &FGConfig::default(),
+        );
+        assert!(hit.is_some());
+    }
+
+    #[test]
+    fn lock_fires_one_transient() {
+        let frames = synthesize(
+            &[FGState::Slack, FGState::Engaged, FGState::Exact, FGState::Exact],
+            &FGConfig::default(),
+        );
+        assert!(frames[0].transient.is_none());
+        assert!(frames[1].transient.is_none());
+        assert_eq!(frames[2].transient, Some(LOCK_TRANSIENT));
+        // Holding the boundary does not retrigger the click.
+        assert!(frames[3].transient.is_none());
+    }
+
+    #[test]
+    fn release_fires_soft_transient() {
+        let frames = synthesize(&[FGState::Exact, FGState::Slack], &FGConfig::default());
+        // First frame is already Exact: a fresh gesture starting on a
+        // boundary still announces the lock.
+        assert_eq!(frames[0].transient, Some(LOCK_TRANSIENT));
+        assert_eq!(frames[1].transient, Some(RELEASE_TRANSIENT));
+    }
+
+    #[test]
+    fn envelope_follows_config_amplitudes() {
+        let cfg = FGConfig::default();
+        let frames = synthesize(&[FGState::Slack, FGState::Engaged], &cfg);
+        assert_eq!(frames[0].amplitude, cfg.amplitude(FGState::Slack));
+        assert_eq!(frames[1].amplitude, cfg.amplitude(FGState::Engaged));
+    }
+
+    #[test]
+    fn reset_rearms_the_lock_transient() {
+        let mut synth = WaveformSynth::new(FGConfig::default());
+        synth.feed(FGState::Exact);
+        synth.reset();
+        let frame = synth.feed(FGState::Exact);
+        assert_eq!(frame.transient, Some(LOCK_TRANSIENT));
+    }
+}
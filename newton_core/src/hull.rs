@@ -0,0 +1,376 @@
+//! Convex hull constraints.
+//!
+//! "Stay inside the region the user sketched" deserves better than the
+//! sketch's bounding box. [`ConvexHullConstraint`] takes the sketch's
+//! points and enforces their convex hull exactly: facets are
+//! enumerated by testing every hyperplane spanned by `dim` of the
+//! points and keeping the ones with all points on one side — O(n^dim),
+//! which is fine for the small point sets sketches produce — and
+//! projection runs Dykstra over the facet halfspaces, which is exact
+//! for a convex target. Input that does not affinely span the space
+//! (collinear 2D points, say) relaxes to the slab around its affine
+//! hull; authors wanting a segment should use a
+//! [`PathConstraint`](crate::path::PathConstraint) instead.
+
+use crate::constraint::Constraint;
+use crate::linalg::Vector;
+
+/// Dykstra sweeps used to project onto the facet intersection.
+const PROJECTION_SWEEPS: usize = 100;
+
+/// Keep the state inside the convex hull of a point set.
+#[derive(Debug, Clone)]
+pub struct ConvexHullConstraint {
+    dim: usize,
+    /// Facets as `(unit normal, offset)` halfspaces `n·x <= offset`.
+    facets: Vec<(Vector, f64)>,
+}
+
+impl ConvexHullConstraint {
+    /// Builds the hull of `points`. Panics when fewer than `dim + 1`
+    /// points are supplied or the points disagree on dimension.
+    pub fn new(points: Vec<Vector>) -> Self {
+        assert!(!points.is_empty(), "ConvexHullConstraint requires points");
+        let dim = points[0].dim();
+        assert!(
+            points.iter().all(|p| p.dim() == dim),
+            "ConvexHullConstraint points must share a dimension"
+        );
+        assert!(
+            points.len() > dim,
+            "a {dim}-dimensional hull needs at least {} points",
+            dim + 1
+        );
+
+        let mut facets: Vec<(Vector, f64)> = Vec::new();
+        let mut subset = vec![0usize; dim];
+        enumerate_subsets(points.len(), dim, 0, &mut subset, &mut |chosen| {
+            let Some(normal) = hyperplane_normal(&points, chosen) else {
+                return;
+            };
+            let offset = normal.dot(&points[chosen[0]]);
+            let mut keep_pos = true;
+            let mut keep_neg = true;
+            for p in &points {
+                let side = normal.dot(p) - offset;
+                if side > crate::EPSILON {
+                    keep_pos = false;
+                }
+                if side < -crate::EPSILON {
+                    keep_neg = false;
+                }
+            }
+            let candidate = if keep_pos {
+                Some((normal.clone(), offset))
+            } else if keep_neg {
+                Some((normal.scale(-1.0), -offset))
+            } else {
+                None
+            };
+            if let Some((n, o)) = candidate {
+                let duplicate = facets
+                    .iter()
+                    .any(|(fn_, fo)| fn_.distance(&n) < 1e-9 && (fo - o).abs() < 1e-9);
+                if !duplicate {
+                    facets.push((n, o));
+                }
+            }
+        });
+        ConvexHullConstraint { dim, facets }
+    }
+
+    /// Validating constructor for untrusted document data: rejects
+    /// empty or dimension-mismatched point sets, non-finite
+    /// coordinates, and point sets too small to span a hull.
+    pub fn try_new(points: Vec<Vector>) -> Result<Self, crate::error::NewtonError> {
+        let Some(first) = points.first() else {
+            return Err(crate::error::NewtonError::InvalidParameter(
+                "a hull needs at least one point",
+            ));
+        };
+        let dim = first.dim();
+        for p in &points {
+            if p.dim() != dim {
+                return Err(crate::error::NewtonError::DimensionMismatch {
+                    expected: dim,
+                    actual: p.dim(),
+                });
+            }
+            if !crate::constraint::all_finite(p) {
+                return Err(crate::error::NewtonError::InvalidParameter(
+                    "hull points must be finite",
+                ));
+            }
+        }
+        if points.len() <= dim {
+            return Err(crate::error::NewtonError::InvalidParameter(
+                "a hull needs more points than its dimension",
+            ));
+        }
+        Ok(ConvexHullConstraint::new(points))
+    }
+
+    /// The hull's facets as `(unit normal, offset)` pairs.
+    pub fn facets(&self) -> &[(Vector, f64)] {
+        &self.facets
+    }
+}
+
+/// Calls `visit` with every size-`k` index subset of `0..n`.
+fn enumerate_subsets(
+    n: usize,
+    k: usize,
+    start: usize,
+    subset: &mut Vec<usize>,
+    visit: &mut impl FnMut(&[usize]),
+) {
+    let depth = subset.len() - k;
+    if k == 0 {
+        visit(subset);
+        return;
+    }
+    for i in start..=(n - k) {
+        subset[depth] = i;
+        enumerate_subsets(n, k - 1, i + 1, subset, visit);
+    }
+}
+
+/// Unit normal of the hyperplane through the chosen points: a unit
+/// vector orthogonal to all their pairwise differences, found as a
+/// nullspace vector by Gaussian elimination. `None` when the points
+/// are affinely degenerate.
+fn hyperplane_normal(points: &[Vector], chosen: &[usize]) -> Option<Vector> {
+    let dim = points[chosen[0]].dim();
+    let mut rows: Vec<Vec<f64>> = chosen[1..]
+        .iter()
+        .map(|&i| {
+            points[i]
+                .sub(&points[chosen[0]])
+                .as_slice()
+                .to_vec()
+        })
+        .collect();
+
+    // Forward elimination with partial pivoting; track pivot columns.
+    let mut pivot_cols = Vec::new();
+    let mut r = 0;
+    for col in 0..dim {
+        let Some(best) = (r..rows.len())
+            .filter(|&i| rows[i][col].abs() > 1e-12)
+            .max_by(|&a, &b| rows[a][col].abs().total_cmp(&rows[b][col].abs()))
+        else {
+            continue;
+        };
+        rows.swap(r, best);
+        let pivot_row = rows[r].clone();
+        for row in rows.iter_mut().skip(r + 1) {
+            let factor = row[col] / pivot_row[col];
+            for (c, pv) in pivot_row.iter().enumerate().skip(col) {
+                row[c] -= factor * pv;
+            }
+        }
+        pivot_cols.push(col);
+        r += 1;
+        if r == rows.len() {
+            break;
+        }
+    }
+    if pivot_cols.len() < rows.len() {
+        return None; // Degenerate: differences do not span a hyperplane.
+    }
+    let free = (0..dim).find(|c| !pivot_cols.contains(c))?;
+
+    // Back-substitute with the free variable set to 1.
+    let mut normal = vec![0.0; dim];
+    normal[free] = 1.0;
+    for (row_i, &col) in pivot_cols.iter().enumerate().rev() {
+        let mut value = 0.0;
+        for c in 0..dim {
+            if c != col {
+                value -= rows[row_i][c] * normal[c];
+            }
+        }
+        normal[col] = value / rows[row_i][col];
+    }
+    Vector::new(normal).normalized()
+}
+
+impl Constraint for ConvexHullConstraint {
+    fn dim(&self) -> usize {
+        self.dim
+    }
+
+    fn contains(&self, point: &Vector) -> bool {
+        self.facets
+            .iter()
+            .all(|(n, o)| n.dot(point) <= o + crate::EPSILON)
+    }
+
+    fn project(&self, point: &Vector) -> Vector {
+        if self.contains(point) {
+            return point.clone();
+        }
+        // Dykstra over the facet halfspaces: exact for this convex
+        // intersection.
+        let mut x = point.clone();
+        let mut corrections = vec![Vector::zeros(self.dim); self.facets.len()];
+        for _ in 0..PROJECTION_SWEEPS {
+            let mut moved = 0.0;
+            for (i, (n, o)) in self.facets.iter().enumerate() {
+                let y = x.add(&corrections[i]);
+                let excess = n.dot(&y) - o;
+                let projected = if excess > 0.0 {
+                    y.sub(&n.scale(excess))
+                } else {
+                    y.clone()
+                };
+                corrections[i] = y.sub(&projected);
+                moved += x.distance(&projected);
+                x = projected;
+            }
+            if moved < crate::EPSILON {
+                break;
+            }
+        }
+        x
+    }
+
+    fn signed_distance(&self, point: &Vector) -> f64 {
+        // Inside: slack to the nearest facet plane. Outside: the facet
+        // bound is exact for one violated plane and a lower bound in
+        // corner regions, where the projection distance refines it.
+        let worst = self
+            .facets
+            .iter()
+            .map(|(n, o)| o - n.dot(point))
+            .fold(f64::INFINITY, f64::min);
+        if worst >= 0.0 {
+            worst
+        } else {
+            -point.distance(&self.project(point))
+        }
+    }
+
+    fn boundary_normal(&self, point: &Vector) -> Option<Vector> {
+        if self.contains(point) {
+            // The facet with the least slack.
+            self.facets
+                .iter()
+                .min_by(|(n1, o1), (n2, o2)| (o1 - n1.dot(point)).total_cmp(&(o2 - n2.dot(point))))
+                .map(|(n, _)| n.clone())
+        } else {
+            point.sub(&self.project(point)).normalized()
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    fn triangle() -> ConvexHullConstraint {
+        ConvexHullConstraint::new(vec![v(0.0, 0.0), v(10.0, 0.0), v(0.0, 10.0)])
+    }
+
+    #[test]
+    fn triangle_membership() {
+        let hull = triangle();
+        assert_eq!(hull.facets().len(), 3);
+        assert!(hull.contains(&v(2.0, 2.0)));
+        assert!(hull.contains(&v(0.0, 0.0)));
+        assert!(!hull.contains(&v(6.0, 6.0)));
+        assert!(!hull.contains(&v(-1.0, 2.0)));
+    }
+
+    #[test]
+    fn interior_points_of_the_input_do_not_add_facets() {
+        let hull = ConvexHullConstraint::new(vec![
+            v(0.0, 0.0),
+            v(10.0, 0.0),
+            v(0.0, 10.0),
+            v(2.0, 2.0),
+        ]);
+        assert_eq!(hull.facets().len(), 3);
+    }
+
+    #[test]
+    fn projection_lands_on_the_hull() {
+        let hull = triangle();
+        // Straight out from the hypotenuse x + y = 10.
+        let p = hull.project(&v(10.0, 10.0));
+        assert!(p.distance(&v(5.0, 5.0)) < 1e-6);
+        // Corner region: nearest point is the vertex.
+        let p = hull.project(&v(-3.0, -4.0));
+        assert!(p.distance(&v(0.0, 0.0)) < 1e-6);
+        // Feasible points are untouched.
+        assert_eq!(hull.project(&v(1.0, 1.0)), v(1.0, 1.0));
+    }
+
+    #[test]
+    fn three_dimensional_hull_from_a_simplex() {
+        let p = |x: f64, y: f64, z: f64| Vector::new(vec![x, y, z]);
+        let hull = ConvexHullConstraint::new(vec![
+            p(0.0, 0.0, 0.0),
+            p(10.0, 0.0, 0.0),
+            p(0.0, 10.0, 0.0),
+            p(0.0, 0.0, 10.0),
+        ]);
+        assert_eq!(hull.facets().len(), 4);
+        assert!(hull.contains(&p(1.0, 1.0, 1.0)));
+        assert!(!hull.contains(&p(5.0, 5.0, 5.0)));
+        assert!(!hull.contains(&p(-1.0, 1.0, 1.0)));
+    }
+
+    #[test]
+    fn boundary_normal_picks_the_nearest_facet() {
+        let hull = triangle();
+        // Interior point nearest the bottom edge y = 0.
+        let n = hull.boundary_normal(&v(4.0, 1.0)).unwrap();
+        assert!(n.distance(&v(0.0, -1.0)) < 1e-9);
+        // Outside the hypotenuse: straight out along x + y.
+        let n = hull.boundary_normal(&v(10.0, 10.0)).unwrap();
+        assert!(n.distance(&v(1.0, 1.0).scale(1.0 / 2.0_f64.sqrt())) < 1e-6);
+    }
+
+    #[test]
+    fn signed_distance_signs_match_membership() {
+        let hull = triangle();
+        assert!(hull.signed_distance(&v(2.0, 2.0)) > 0.0);
+        assert!(hull.signed_distance(&v(10.0, 10.0)) < 0.0);
+    }
+
+    #[test]
+    fn try_new_validates_the_point_set() {
+        use crate::error::NewtonError;
+        assert_eq!(
+            ConvexHullConstraint::try_new(vec![]).err(),
+            Some(NewtonError::InvalidParameter(
+                "a hull needs at least one point"
+            ))
+        );
+        assert_eq!(
+            ConvexHullConstraint::try_new(vec![v(0.0, 0.0), Vector::zeros(3)]).err(),
+            Some(NewtonError::DimensionMismatch {
+                expected: 2,
+                actual: 3
+            })
+        );
+        assert_eq!(
+            ConvexHullConstraint::try_new(vec![v(0.0, 0.0), v(1.0, 0.0)]).err(),
+            Some(NewtonError::InvalidParameter(
+                "a hull needs more points than its dimension"
+            ))
+        );
+        let hull =
+            ConvexHullConstraint::try_new(vec![v(0.0, 0.0), v(10.0, 0.0), v(0.0, 10.0)]).unwrap();
+        assert_eq!(hull.facets().len(), 3);
+    }
+}
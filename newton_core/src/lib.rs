@@ -0,0 +1,67 @@
+//! # newton_core
+//!
+//! The native constraint engine of the Newton suite: given where a
+//! gesture *wants* an object and a set of geometric constraints, it
+//! computes where the object *may* go, how good that answer is, and the
+//! engagement state that drives haptic/visual feedback.
+//!
+//! The pipeline, per input event:
+//!
+//! 1. [`constraint`] — declare feasible sets over configuration space.
+//! 2. [`project`] — Dykstra/alternating projection onto their
+//!    intersection.
+//! 3. [`suggest`] — candidate search around the intent for nonconvex
+//!    cases.
+//! 4. [`rank`] — weighted scoring to pick what the user sees.
+//! 5. [`fgstate`] — discretised engagement level for feedback layers.
+
+pub mod analyze;
+pub mod bounds;
+pub mod cache;
+pub mod constraint;
+pub mod delta;
+pub mod dynamics;
+pub mod ephemeral;
+pub mod error;
+pub mod fgstate;
+pub mod field;
+pub mod fingerprint;
+pub mod graph;
+pub mod group;
+pub mod guides;
+pub mod haptics;
+pub mod hull;
+pub mod linalg;
+pub mod linear;
+pub mod multi;
+pub mod object;
+pub mod path;
+pub mod precondition;
+pub mod project;
+pub mod rank;
+pub mod resize;
+pub mod rng;
+pub mod sanitize;
+pub mod scenarios;
+pub mod scheduler;
+pub mod sensitivity;
+pub mod service;
+pub mod simplify;
+pub mod snap;
+pub mod state;
+pub mod suggest;
+pub mod sweep;
+pub mod verify;
+
+pub use bounds::Bounds;
+pub use constraint::{Constraint, ConstraintRef, ConstraintSystem};
+pub use error::NewtonError;
+pub use fgstate::FGState;
+pub use linalg::Vector;
+pub use object::{NTObject, ObjectId, Scene};
+pub use rank::{rank_candidates, RankingCriteria, Scorer};
+pub use suggest::{suggest, DowngradeReason, SuggestResponse, SuggestionQuality};
+
+/// Geometric tolerance used for membership and degeneracy checks
+/// throughout the crate.
+pub const EPSILON: f64 = 1e-9;
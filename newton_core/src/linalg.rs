@@ -0,0 +1,634 @@
+//! Small dense vector type used throughout the engine.
+//!
+//! Object state is a point in an n-dimensional configuration space
+//! (position, extent, rotation, ...), so everything downstream of the
+//! constraint layer works in terms of [`Vector`] rather than fixed 2D
+//! points.
+
+/// Dimensions stored inline, without a heap allocation. Covers the
+/// pervasive 2D/3D object states plus a rotation or extent component.
+const INLINE_DIM: usize = 4;
+
+/// Backing store: a fixed inline array for small dimensions, a `Vec`
+/// beyond. The variant is determined by dimension alone, so equal
+/// vectors always share a representation.
+#[derive(Debug, Clone)]
+enum Storage {
+    Inline { len: usize, data: [f64; INLINE_DIM] },
+    Heap(Vec<f64>),
+}
+
+/// A dense vector of `f64` components. Up to four components live
+/// inline; larger vectors spill to the heap.
+///
+/// Dimensions are checked at the API boundary: binary operations panic
+/// if the operands disagree on dimension, which always indicates a
+/// caller bug rather than a recoverable condition.
+#[derive(Debug, Clone)]
+pub struct Vector {
+    storage: Storage,
+}
+
+impl Vector {
+    /// Creates a vector from its components.
+    pub fn new(data: Vec<f64>) -> Self {
+        if data.len() <= INLINE_DIM {
+            let mut inline = [0.0; INLINE_DIM];
+            inline[..data.len()].copy_from_slice(&data);
+            Vector {
+                storage: Storage::Inline {
+                    len: data.len(),
+                    data: inline,
+                },
+            }
+        } else {
+            Vector {
+                storage: Storage::Heap(data),
+            }
+        }
+    }
+
+    /// Creates the zero vector of the given dimension.
+    pub fn zeros(dim: usize) -> Self {
+        Vector::build(dim, |_| 0.0)
+    }
+
+    /// Creates a vector by evaluating `f` at each index, inline when
+    /// the dimension permits.
+    fn build(dim: usize, mut f: impl FnMut(usize) -> f64) -> Vector {
+        if dim <= INLINE_DIM {
+            let mut inline = [0.0; INLINE_DIM];
+            for (i, slot) in inline[..dim].iter_mut().enumerate() {
+                *slot = f(i);
+            }
+            Vector {
+                storage: Storage::Inline {
+                    len: dim,
+                    data: inline,
+                },
+            }
+        } else {
+            Vector {
+                storage: Storage::Heap((0..dim).map(f).collect()),
+            }
+        }
+    }
+
+    /// Number of components.
+    pub fn dim(&self) -> usize {
+        match &self.storage {
+            Storage::Inline { len, .. } => *len,
+            Storage::Heap(v) => v.len(),
+        }
+    }
+
+    /// Returns the `i`-th component. Panics if out of range.
+    pub fn get(&self, i: usize) -> f64 {
+        self.as_slice()[i]
+    }
+
+    /// Sets the `i`-th component. Panics if out of range.
+    pub fn set(&mut self, i: usize, value: f64) {
+        self.slice_mut()[i] = value;
+    }
+
+    /// Borrow the components as a slice.
+    pub fn as_slice(&self) -> &[f64] {
+        match &self.storage {
+            Storage::Inline { len, data } => &data[..*len],
+            Storage::Heap(v) => v,
+        }
+    }
+
+    fn slice_mut(&mut self) -> &mut [f64] {
+        match &mut self.storage {
+            Storage::Inline { len, data } => &mut data[..*len],
+            Storage::Heap(v) => v,
+        }
+    }
+
+    /// Component-wise sum.
+    pub fn add(&self, other: &Vector) -> Vector {
+        assert_eq!(self.dim(), other.dim(), "dimension mismatch in add");
+        Vector::build(self.dim(), |i| self.as_slice()[i] + other.as_slice()[i])
+    }
+
+    /// Component-wise difference (`self - other`).
+    pub fn sub(&self, other: &Vector) -> Vector {
+        assert_eq!(self.dim(), other.dim(), "dimension mismatch in sub");
+        Vector::build(self.dim(), |i| self.as_slice()[i] - other.as_slice()[i])
+    }
+
+    /// Scalar multiple.
+    pub fn scale(&self, k: f64) -> Vector {
+        Vector::build(self.dim(), |i| self.as_slice()[i] * k)
+    }
+
+    /// Dot product.
+    pub fn dot(&self, other: &Vector) -> f64 {
+        assert_eq!(self.dim(), other.dim(), "dimension mismatch in dot");
+        self.as_slice()
+            .iter()
+            .zip(other.as_slice())
+            .map(|(a, b)| a * b)
+            .sum()
+    }
+
+    /// Euclidean norm.
+    pub fn norm(&self) -> f64 {
+        self.dot(self).sqrt()
+    }
+
+    /// Whether any component is NaN.
+    pub fn has_nan(&self) -> bool {
+        self.as_slice().iter().any(|x| x.is_nan())
+    }
+
+    /// Whether every component is finite (no NaN, no ±∞).
+    pub fn is_finite(&self) -> bool {
+        self.as_slice().iter().all(|x| x.is_finite())
+    }
+
+    /// Euclidean distance to another point.
+    pub fn distance(&self, other: &Vector) -> f64 {
+        self.sub(other).norm()
+    }
+
+    /// Unit vector in the same direction, or `None` for (near-)zero
+    /// vectors where the direction is undefined.
+    pub fn normalized(&self) -> Option<Vector> {
+        let n = self.norm();
+        if n < crate::EPSILON {
+            None
+        } else {
+            Some(self.scale(1.0 / n))
+        }
+    }
+
+    /// Linear interpolation: `self` at `t = 0`, `other` at `t = 1`.
+    pub fn lerp(&self, other: &Vector, t: f64) -> Vector {
+        self.add(&other.sub(self).scale(t))
+    }
+
+    /// Component-wise minimum with `other`.
+    pub fn component_min(&self, other: &Vector) -> Vector {
+        assert_eq!(self.dim(), other.dim(), "dimension mismatch in component_min");
+        Vector::build(self.dim(), |i| self.as_slice()[i].min(other.as_slice()[i]))
+    }
+
+    /// Component-wise maximum with `other`.
+    pub fn component_max(&self, other: &Vector) -> Vector {
+        assert_eq!(self.dim(), other.dim(), "dimension mismatch in component_max");
+        Vector::build(self.dim(), |i| self.as_slice()[i].max(other.as_slice()[i]))
+    }
+
+    /// Component-wise absolute value.
+    pub fn component_abs(&self) -> Vector {
+        Vector::build(self.dim(), |i| self.as_slice()[i].abs())
+    }
+
+    /// Sum of the components (zero for the empty vector).
+    pub fn sum(&self) -> f64 {
+        self.as_slice().iter().sum()
+    }
+
+    /// Smallest component. Panics on the empty vector, where no
+    /// element exists.
+    pub fn min_element(&self) -> f64 {
+        assert!(self.dim() > 0, "min_element of an empty vector");
+        self.as_slice().iter().fold(f64::INFINITY, |a, &b| a.min(b))
+    }
+
+    /// Largest component. Panics on the empty vector.
+    pub fn max_element(&self) -> f64 {
+        assert!(self.dim() > 0, "max_element of an empty vector");
+        self.as_slice().iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b))
+    }
+
+    /// Index of the largest component; the first wins ties. Panics on
+    /// the empty vector.
+    pub fn argmax(&self) -> usize {
+        assert!(self.dim() > 0, "argmax of an empty vector");
+        let mut best = 0;
+        for (i, &x) in self.as_slice().iter().enumerate() {
+            if x > self.as_slice()[best] {
+                best = i;
+            }
+        }
+        best
+    }
+
+    /// Angle to `other` in radians, in `[0, π]`, or `None` when either
+    /// vector is (near-)zero and the angle is undefined.
+    pub fn angle_between(&self, other: &Vector) -> Option<f64> {
+        assert_eq!(self.dim(), other.dim(), "dimension mismatch in angle_between");
+        let denom = self.norm() * other.norm();
+        if denom < crate::EPSILON {
+            None
+        } else {
+            Some((self.dot(other) / denom).clamp(-1.0, 1.0).acos())
+        }
+    }
+
+    /// Component of `self` along `direction` (which need not be
+    /// normalized). Panics on a (near-)zero direction, where the
+    /// projection is undefined.
+    pub fn project_onto(&self, direction: &Vector) -> Vector {
+        assert_eq!(self.dim(), direction.dim(), "dimension mismatch in project_onto");
+        let d2 = direction.dot(direction);
+        assert!(d2.sqrt() >= crate::EPSILON, "direction must be nonzero");
+        direction.scale(self.dot(direction) / d2)
+    }
+
+    /// Component of `self` perpendicular to `direction`:
+    /// `self - self.project_onto(direction)`. Same panics as
+    /// [`project_onto`](Vector::project_onto).
+    pub fn reject_from(&self, direction: &Vector) -> Vector {
+        self.sub(&self.project_onto(direction))
+    }
+
+    /// Rotates the first two components by `theta` radians
+    /// (counter-clockwise); any further components pass through,
+    /// matching the engine's convention that the first two dimensions
+    /// are the canvas plane. Panics below two dimensions.
+    pub fn rotate_2d(&self, theta: f64) -> Vector {
+        assert!(self.dim() >= 2, "rotate_2d needs at least two dimensions");
+        let (s, c) = theta.sin_cos();
+        let mut out = self.clone();
+        out.set(0, c * self.get(0) - s * self.get(1));
+        out.set(1, s * self.get(0) + c * self.get(1));
+        out
+    }
+}
+
+impl Vector {
+    /// Read-only view of the whole vector.
+    pub fn view(&self) -> VectorView<'_> {
+        VectorView::new(self.as_slice())
+    }
+
+    /// Mutable view of the whole vector.
+    pub fn view_mut(&mut self) -> VectorViewMut<'_> {
+        VectorViewMut::new(self.slice_mut())
+    }
+}
+
+/// A borrowed, read-only window onto consecutive components of a
+/// [`Vector`] (or any `&[f64]`).
+///
+/// Multi-object constraints work on concatenated states — object `i`
+/// owns components `[i * object_dim, (i + 1) * object_dim)` — and
+/// slicing one object out of a 400-dimensional scene state should not
+/// copy 4 floats into a fresh allocation per constraint per sweep.
+/// Sub-slicing a view is free; [`to_vector`](VectorView::to_vector) is
+/// the explicit point where a copy happens.
+#[derive(Debug, Clone, Copy)]
+pub struct VectorView<'a> {
+    data: &'a [f64],
+}
+
+impl<'a> VectorView<'a> {
+    /// Wraps a component slice.
+    pub fn new(data: &'a [f64]) -> Self {
+        VectorView { data }
+    }
+
+    /// Number of components in view.
+    pub fn dim(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the `i`-th component. Panics if out of range.
+    pub fn get(&self, i: usize) -> f64 {
+        self.data[i]
+    }
+
+    /// Borrow the viewed components as a slice.
+    pub fn as_slice(&self) -> &'a [f64] {
+        self.data
+    }
+
+    /// Zero-copy sub-view of components `[start, end)`. Panics if the
+    /// range is out of bounds.
+    pub fn slice(&self, range: std::ops::Range<usize>) -> VectorView<'a> {
+        VectorView::new(&self.data[range])
+    }
+
+    /// Zero-copy view of object `i` in a concatenated multi-object
+    /// state of `object_dim` components each. Panics if the object
+    /// lies outside the view.
+    pub fn object(&self, i: usize, object_dim: usize) -> VectorView<'a> {
+        self.slice(i * object_dim..(i + 1) * object_dim)
+    }
+
+    /// Gathers arbitrary (not necessarily contiguous) dimensions into
+    /// an owned vector — the copying escape hatch for dimension masks.
+    /// Panics if any index is out of range.
+    pub fn select(&self, dims: &[usize]) -> Vector {
+        Vector::build(dims.len(), |i| self.data[dims[i]])
+    }
+
+    /// Copies the viewed components into an owned [`Vector`].
+    pub fn to_vector(&self) -> Vector {
+        Vector::build(self.data.len(), |i| self.data[i])
+    }
+
+    /// Dot product of two equal-dimension views.
+    pub fn dot(&self, other: &VectorView<'_>) -> f64 {
+        assert_eq!(self.dim(), other.dim(), "dimension mismatch in dot");
+        self.data.iter().zip(other.data).map(|(a, b)| a * b).sum()
+    }
+
+    /// Euclidean norm of the viewed components.
+    pub fn norm(&self) -> f64 {
+        self.dot(self).sqrt()
+    }
+
+    /// Euclidean distance to another equal-dimension view.
+    pub fn distance(&self, other: &VectorView<'_>) -> f64 {
+        assert_eq!(self.dim(), other.dim(), "dimension mismatch in distance");
+        self.data
+            .iter()
+            .zip(other.data)
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum::<f64>()
+            .sqrt()
+    }
+}
+
+/// The mutable counterpart of [`VectorView`]: writes go straight into
+/// the underlying storage, so per-object updates of a concatenated
+/// state need no scatter step afterwards.
+#[derive(Debug)]
+pub struct VectorViewMut<'a> {
+    data: &'a mut [f64],
+}
+
+impl<'a> VectorViewMut<'a> {
+    /// Wraps a mutable component slice.
+    pub fn new(data: &'a mut [f64]) -> Self {
+        VectorViewMut { data }
+    }
+
+    /// Number of components in view.
+    pub fn dim(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the `i`-th component. Panics if out of range.
+    pub fn get(&self, i: usize) -> f64 {
+        self.data[i]
+    }
+
+    /// Sets the `i`-th component. Panics if out of range.
+    pub fn set(&mut self, i: usize, value: f64) {
+        self.data[i] = value;
+    }
+
+    /// Reborrows a mutable sub-view of components `[start, end)`.
+    /// Panics if the range is out of bounds.
+    pub fn slice_mut(&mut self, range: std::ops::Range<usize>) -> VectorViewMut<'_> {
+        VectorViewMut::new(&mut self.data[range])
+    }
+
+    /// Mutable view of object `i` in a concatenated multi-object state
+    /// of `object_dim` components each. Panics if the object lies
+    /// outside the view.
+    pub fn object_mut(&mut self, i: usize, object_dim: usize) -> VectorViewMut<'_> {
+        self.slice_mut(i * object_dim..(i + 1) * object_dim)
+    }
+
+    /// Overwrites the viewed components from an equal-dimension view.
+    pub fn copy_from(&mut self, source: &VectorView<'_>) {
+        assert_eq!(
+            self.dim(),
+            source.dim(),
+            "dimension mismatch in copy_from"
+        );
+        self.data.copy_from_slice(source.as_slice());
+    }
+
+    /// Downgrades to a read-only view of the same components.
+    pub fn as_view(&self) -> VectorView<'_> {
+        VectorView::new(self.data)
+    }
+}
+
+impl PartialEq for Vector {
+    fn eq(&self, other: &Vector) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+/// Canonical textual form: `[1.0, 2.5]`. Components print with
+/// shortest round-trip precision, so `parse` of the output restores
+/// the exact vector. This — not the `Debug` of the internals — is what
+/// logs, error messages, and the CLI harness show.
+impl std::fmt::Display for Vector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[")?;
+        for (i, x) in self.as_slice().iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{x:?}")?;
+        }
+        write!(f, "]")
+    }
+}
+
+/// Why a vector failed to parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseVectorError {
+    /// The text is not wrapped in `[` ... `]`.
+    MissingBrackets,
+    /// A component is not a valid `f64`.
+    BadComponent,
+}
+
+impl std::str::FromStr for Vector {
+    type Err = ParseVectorError;
+
+    /// Parses the canonical `Display` form. Whitespace around the
+    /// brackets and components is tolerated; `[]` is the
+    /// zero-dimensional vector.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let inner = s
+            .trim()
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+            .ok_or(ParseVectorError::MissingBrackets)?
+            .trim();
+        if inner.is_empty() {
+            return Ok(Vector::new(Vec::new()));
+        }
+        let components: Result<Vec<f64>, _> = inner
+            .split(',')
+            .map(|part| part.trim().parse::<f64>())
+            .collect();
+        match components {
+            Ok(data) => Ok(Vector::new(data)),
+            Err(_) => Err(ParseVectorError::BadComponent),
+        }
+    }
+}
+
+impl From<Vec<f64>> for Vector {
+    fn from(data: Vec<f64>) -> Self {
+        Vector::new(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_basics() {
+        let a = Vector::new(vec![1.0, 2.0]);
+        let b = Vector::new(vec![3.0, -1.0]);
+        assert_eq!(a.add(&b), Vector::new(vec![4.0, 1.0]));
+        assert_eq!(a.sub(&b), Vector::new(vec![-2.0, 3.0]));
+        assert_eq!(a.scale(2.0), Vector::new(vec![2.0, 4.0]));
+        assert_eq!(a.dot(&b), 1.0);
+    }
+
+    #[test]
+    fn norm_and_distance() {
+        let a = Vector::new(vec![3.0, 4.0]);
+        assert_eq!(a.norm(), 5.0);
+        assert_eq!(a.distance(&Vector::zeros(2)), 5.0);
+    }
+
+    #[test]
+    fn normalized_rejects_zero() {
+        assert!(Vector::zeros(3).normalized().is_none());
+        let u = Vector::new(vec![0.0, 2.0]).normalized().unwrap();
+        assert!((u.norm() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn inline_and_heap_storage_agree() {
+        // Either side of the inline threshold, every operation sees the
+        // same components.
+        for dim in [1, INLINE_DIM, INLINE_DIM + 1, 12] {
+            let a = Vector::new((0..dim).map(|i| i as f64).collect());
+            assert_eq!(a.dim(), dim);
+            assert_eq!(a.as_slice().len(), dim);
+            assert_eq!(a.add(&a), a.scale(2.0));
+            let mut b = a.clone();
+            b.set(dim - 1, -1.0);
+            assert_eq!(b.get(dim - 1), -1.0);
+            assert_ne!(a, b);
+        }
+    }
+
+    #[test]
+    fn views_slice_objects_out_of_a_scene_state() {
+        let scene = Vector::new((0..8).map(|i| i as f64).collect());
+        let view = scene.view();
+        let obj = view.object(1, 4);
+        assert_eq!(obj.dim(), 4);
+        assert_eq!(obj.as_slice(), &scene.as_slice()[4..8]);
+        assert_eq!(obj.to_vector(), Vector::new(vec![4.0, 5.0, 6.0, 7.0]));
+        // Non-contiguous picks gather into an owned vector.
+        assert_eq!(view.select(&[0, 5]), Vector::new(vec![0.0, 5.0]));
+        assert_eq!(view.slice(3..5).norm(), 5.0);
+    }
+
+    #[test]
+    fn mutable_views_write_through() {
+        let mut scene = Vector::zeros(6);
+        {
+            let mut view = scene.view_mut();
+            let mut obj = view.object_mut(1, 2);
+            obj.set(0, 3.0);
+            obj.set(1, 4.0);
+            assert_eq!(obj.as_view().norm(), 5.0);
+        }
+        assert_eq!(scene.get(2), 3.0);
+        let replacement = Vector::new(vec![7.0, 8.0]);
+        scene.view_mut().slice_mut(4..6).copy_from(&replacement.view());
+        assert_eq!(scene.get(5), 8.0);
+    }
+
+    #[test]
+    fn component_wise_ops_and_reductions() {
+        let a = Vector::new(vec![1.0, -4.0, 3.0]);
+        let b = Vector::new(vec![2.0, -5.0, 0.0]);
+        assert_eq!(a.component_min(&b), Vector::new(vec![1.0, -5.0, 0.0]));
+        assert_eq!(a.component_max(&b), Vector::new(vec![2.0, -4.0, 3.0]));
+        assert_eq!(a.component_abs(), Vector::new(vec![1.0, 4.0, 3.0]));
+        assert_eq!(a.sum(), 0.0);
+        assert_eq!(a.min_element(), -4.0);
+        assert_eq!(a.max_element(), 3.0);
+        assert_eq!(a.argmax(), 2);
+        // Ties go to the first index.
+        assert_eq!(Vector::new(vec![5.0, 5.0]).argmax(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "empty vector")]
+    fn reductions_reject_the_empty_vector() {
+        Vector::new(Vec::new()).max_element();
+    }
+
+    #[test]
+    fn angles_and_directional_components() {
+        let x = Vector::new(vec![1.0, 0.0]);
+        let y = Vector::new(vec![0.0, 2.0]);
+        assert!((x.angle_between(&y).unwrap() - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+        assert_eq!(x.angle_between(&Vector::zeros(2)), None);
+
+        let a = Vector::new(vec![3.0, 4.0]);
+        // Direction length must not matter.
+        assert_eq!(a.project_onto(&x), a.project_onto(&x.scale(10.0)));
+        assert_eq!(a.project_onto(&x), Vector::new(vec![3.0, 0.0]));
+        assert_eq!(a.reject_from(&x), Vector::new(vec![0.0, 4.0]));
+        // Projection and rejection recompose the vector.
+        assert_eq!(a.project_onto(&y).add(&a.reject_from(&y)), a);
+    }
+
+    #[test]
+    #[should_panic(expected = "direction")]
+    fn projecting_onto_zero_is_a_caller_bug() {
+        Vector::new(vec![1.0, 2.0]).project_onto(&Vector::zeros(2));
+    }
+
+    #[test]
+    fn rotate_2d_turns_the_canvas_plane_only() {
+        let a = Vector::new(vec![1.0, 0.0, 7.0]);
+        let r = a.rotate_2d(std::f64::consts::FRAC_PI_2);
+        assert!(r.distance(&Vector::new(vec![0.0, 1.0, 7.0])) < 1e-12);
+        // A full turn is the identity, within rounding.
+        assert!(a.rotate_2d(std::f64::consts::TAU).distance(&a) < 1e-12);
+    }
+
+    #[test]
+    fn display_and_parse_round_trip() {
+        let a = Vector::new(vec![1.0, 2.5, -0.125]);
+        assert_eq!(a.to_string(), "[1.0, 2.5, -0.125]");
+        assert_eq!(a.to_string().parse::<Vector>().unwrap(), a);
+        // Shortest round-trip printing still restores exact bits.
+        let precise = Vector::new(vec![0.1 + 0.2]);
+        assert_eq!(precise.to_string().parse::<Vector>().unwrap(), precise);
+        assert_eq!("[]".parse::<Vector>().unwrap(), Vector::new(Vec::new()));
+        assert_eq!(" [ 1.0 , 2.0 ] ".parse::<Vector>().unwrap(), Vector::new(vec![1.0, 2.0]));
+    }
+
+    #[test]
+    fn parse_rejects_malformed_text() {
+        assert_eq!("1.0, 2.0".parse::<Vector>(), Err(ParseVectorError::MissingBrackets));
+        assert_eq!("[1.0; 2.0]".parse::<Vector>(), Err(ParseVectorError::BadComponent));
+        assert_eq!("[1.0, ]".parse::<Vector>(), Err(ParseVectorError::BadComponent));
+    }
+
+    #[test]
+    fn lerp_endpoints() {
+        let a = Vector::new(vec![0.0, 0.0]);
+        let b = Vector::new(vec![10.0, -10.0]);
+        assert_eq!(a.lerp(&b, 0.0), a);
+        assert_eq!(a.lerp(&b, 1.0), b);
+        assert_eq!(a.lerp(&b, 0.5), Vector::new(vec![5.0, -5.0]));
+    }
+}
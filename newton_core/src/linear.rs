@@ -0,0 +1,695 @@
+//! Cassowary-style linear layout solving.
+//!
+//! Dykstra projection treats every constraint as a black-box geometric
+//! set and re-converges from scratch each query. Purely *linear*
+//! systems — the bread and butter of layout — deserve better: a
+//! simplex tableau solves them exactly, and once solved, moving an
+//! *edit variable* re-solves incrementally with a handful of dual
+//! pivots instead of a fresh converge. [`LinearSolver`] implements that
+//! scheme (the Cassowary algorithm: slack/error symbols, artificial
+//! variables for required rows, dual simplex on suggested values), and
+//! [`LinearLayout`] wraps a solved system as a [`Constraint`] so linear
+//! and geometric constraints compose in one [`ConstraintSystem`].
+//!
+//! Non-required strengths minimise a weighted sum of deviations, so a
+//! solution is an L1-style compromise rather than the Euclidean
+//! projection the geometric backend computes; for pure layout that is
+//! exactly the Cassowary behaviour hosts expect.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use crate::constraint::Constraint;
+use crate::linalg::Vector;
+
+/// A solver variable: an index into the configuration space.
+pub type Var = usize;
+
+/// Strength of a required constraint; anything weaker is an error term
+/// the solver minimises.
+pub const REQUIRED: f64 = 1e9;
+pub const STRONG: f64 = 1e6;
+pub const MEDIUM: f64 = 1e3;
+pub const WEAK: f64 = 1.0;
+
+/// Numeric slop for tableau arithmetic; looser than [`crate::EPSILON`]
+/// because simplex pivots accumulate rounding.
+const NEAR_ZERO: f64 = 1e-8;
+
+/// Relation of a linear expression to zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    /// `expr <= 0`
+    Le,
+    /// `expr == 0`
+    Eq,
+    /// `expr >= 0`
+    Ge,
+}
+
+/// Why the solver rejected a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinearError {
+    /// A required constraint contradicts the ones already added.
+    Unsatisfiable,
+    /// Edit variables must be weaker than [`REQUIRED`].
+    EditStrengthRequired,
+    /// The variable has no edit declared for it.
+    UnknownEdit,
+    /// An edit for the variable already exists.
+    DuplicateEdit,
+}
+
+/// A linear expression `Σ cᵢ·xᵢ + constant`, built fluently:
+/// `LinearExpr::term(1.0, x).term(-1.0, y).constant(-10.0)` is
+/// `x - y - 10`.
+#[derive(Debug, Clone, Default)]
+pub struct LinearExpr {
+    terms: Vec<(f64, Var)>,
+    constant: f64,
+}
+
+impl LinearExpr {
+    pub fn new() -> Self {
+        LinearExpr::default()
+    }
+
+    /// Starts an expression with one term.
+    pub fn term(coefficient: f64, var: Var) -> Self {
+        LinearExpr::new().plus(coefficient, var)
+    }
+
+    /// Adds a term.
+    pub fn plus(mut self, coefficient: f64, var: Var) -> Self {
+        self.terms.push((coefficient, var));
+        self
+    }
+
+    /// Adds to the constant part.
+    pub fn constant(mut self, value: f64) -> Self {
+        self.constant += value;
+        self
+    }
+
+    /// The expression evaluated at `point`.
+    pub fn evaluate(&self, point: &Vector) -> f64 {
+        let mut total = self.constant;
+        for (c, v) in &self.terms {
+            total += c * point.get(*v);
+        }
+        total
+    }
+
+    /// Largest variable index mentioned, if any.
+    fn max_var(&self) -> Option<Var> {
+        self.terms.iter().map(|(_, v)| *v).max()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SymbolKind {
+    External,
+    Slack,
+    Error,
+    Dummy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Symbol {
+    kind: SymbolKind,
+    id: usize,
+}
+
+/// One tableau row: `0 = constant + Σ cells`. Basic rows are stored as
+/// `basic = constant + Σ cells` under their basic symbol.
+#[derive(Debug, Clone, Default)]
+struct Row {
+    cells: BTreeMap<Symbol, f64>,
+    constant: f64,
+}
+
+impl Row {
+    fn insert_symbol(&mut self, symbol: Symbol, coefficient: f64) {
+        let entry = self.cells.entry(symbol).or_insert(0.0);
+        *entry += coefficient;
+        if entry.abs() < NEAR_ZERO {
+            self.cells.remove(&symbol);
+        }
+    }
+
+    fn insert_row(&mut self, other: &Row, coefficient: f64) {
+        self.constant += other.constant * coefficient;
+        for (symbol, c) in &other.cells {
+            self.insert_symbol(*symbol, c * coefficient);
+        }
+    }
+
+    fn reverse_sign(&mut self) {
+        self.constant = -self.constant;
+        for c in self.cells.values_mut() {
+            *c = -*c;
+        }
+    }
+
+    /// Rewrites the row so `symbol` (currently a cell) is its subject.
+    fn solve_for(&mut self, symbol: Symbol) {
+        let coefficient = -1.0 / self.cells.remove(&symbol).expect("symbol not in row");
+        self.constant *= coefficient;
+        for c in self.cells.values_mut() {
+            *c *= coefficient;
+        }
+    }
+
+    /// Moves the subject from `lhs` to `rhs`.
+    fn change_subject(&mut self, lhs: Symbol, rhs: Symbol) {
+        self.insert_symbol(lhs, -1.0);
+        self.solve_for(rhs);
+    }
+
+    fn coefficient_for(&self, symbol: Symbol) -> f64 {
+        self.cells.get(&symbol).copied().unwrap_or(0.0)
+    }
+
+    fn substitute(&mut self, symbol: Symbol, row: &Row) {
+        if let Some(coefficient) = self.cells.remove(&symbol) {
+            self.insert_row(row, coefficient);
+        }
+    }
+}
+
+struct EditInfo {
+    marker: Symbol,
+    other: Option<Symbol>,
+    constant: f64,
+}
+
+/// Incremental simplex solver for linear constraints over configuration
+/// variables.
+pub struct LinearSolver {
+    rows: BTreeMap<Symbol, Row>,
+    objective: Row,
+    artificial: Option<Row>,
+    externals: BTreeMap<Var, Symbol>,
+    edits: BTreeMap<Var, EditInfo>,
+    infeasible: Vec<Symbol>,
+    next_symbol: usize,
+}
+
+impl LinearSolver {
+    pub fn new() -> Self {
+        LinearSolver {
+            rows: BTreeMap::new(),
+            objective: Row::default(),
+            artificial: None,
+            externals: BTreeMap::new(),
+            edits: BTreeMap::new(),
+            infeasible: Vec::new(),
+            next_symbol: 0,
+        }
+    }
+
+    fn fresh(&mut self, kind: SymbolKind) -> Symbol {
+        self.next_symbol += 1;
+        Symbol {
+            kind,
+            id: self.next_symbol,
+        }
+    }
+
+    fn external(&mut self, var: Var) -> Symbol {
+        if let Some(s) = self.externals.get(&var) {
+            return *s;
+        }
+        let s = self.fresh(SymbolKind::External);
+        self.externals.insert(var, s);
+        s
+    }
+
+    /// Adds `expr (relation) 0` at `strength`. Required constraints
+    /// must be satisfiable together; weaker ones contribute error terms
+    /// the solver minimises by strength.
+    pub fn add_constraint(
+        &mut self,
+        expr: &LinearExpr,
+        relation: Relation,
+        strength: f64,
+    ) -> Result<(), LinearError> {
+        self.add_constraint_internal(expr, relation, strength)
+            .map(|_| ())
+    }
+
+    fn add_constraint_internal(
+        &mut self,
+        expr: &LinearExpr,
+        relation: Relation,
+        strength: f64,
+    ) -> Result<(Symbol, Option<Symbol>), LinearError> {
+        let strength = strength.clamp(0.0, REQUIRED);
+        let mut row = Row {
+            cells: BTreeMap::new(),
+            constant: expr.constant,
+        };
+        for (coefficient, var) in &expr.terms {
+            if coefficient.abs() < NEAR_ZERO {
+                continue;
+            }
+            let symbol = self.external(*var);
+            if let Some(basic) = self.rows.get(&symbol) {
+                let basic = basic.clone();
+                row.insert_row(&basic, *coefficient);
+            } else {
+                row.insert_symbol(symbol, *coefficient);
+            }
+        }
+
+        let (marker, other) = match relation {
+            Relation::Le | Relation::Ge => {
+                let coefficient = if relation == Relation::Le { 1.0 } else { -1.0 };
+                let slack = self.fresh(SymbolKind::Slack);
+                row.insert_symbol(slack, coefficient);
+                if strength < REQUIRED {
+                    let error = self.fresh(SymbolKind::Error);
+                    row.insert_symbol(error, -coefficient);
+                    self.objective.insert_symbol(error, strength);
+                    (slack, Some(error))
+                } else {
+                    (slack, None)
+                }
+            }
+            Relation::Eq => {
+                if strength < REQUIRED {
+                    let plus = self.fresh(SymbolKind::Error);
+                    let minus = self.fresh(SymbolKind::Error);
+                    row.insert_symbol(plus, -1.0);
+                    row.insert_symbol(minus, 1.0);
+                    self.objective.insert_symbol(plus, strength);
+                    self.objective.insert_symbol(minus, strength);
+                    (plus, Some(minus))
+                } else {
+                    let dummy = self.fresh(SymbolKind::Dummy);
+                    row.insert_symbol(dummy, 1.0);
+                    (dummy, None)
+                }
+            }
+        };
+
+        if row.constant < 0.0 {
+            row.reverse_sign();
+        }
+
+        match self.choose_subject(&row, marker, other) {
+            Some(subject) => {
+                let mut row = row;
+                row.solve_for(subject);
+                self.substitute_out(subject, &row);
+                self.rows.insert(subject, row);
+            }
+            None => {
+                // All-dummy rows encode required equalities already
+                // implied (or contradicted) by the tableau.
+                if row.cells.keys().all(|s| s.kind == SymbolKind::Dummy) {
+                    if row.constant.abs() > NEAR_ZERO {
+                        return Err(LinearError::Unsatisfiable);
+                    }
+                } else if !self.add_with_artificial_variable(row) {
+                    return Err(LinearError::Unsatisfiable);
+                }
+            }
+        }
+        self.optimize(false);
+        Ok((marker, other))
+    }
+
+    fn choose_subject(&self, row: &Row, marker: Symbol, other: Option<Symbol>) -> Option<Symbol> {
+        for symbol in row.cells.keys() {
+            if symbol.kind == SymbolKind::External {
+                return Some(*symbol);
+            }
+        }
+        for candidate in [Some(marker), other].into_iter().flatten() {
+            if matches!(candidate.kind, SymbolKind::Slack | SymbolKind::Error)
+                && row.coefficient_for(candidate) < 0.0
+            {
+                return Some(candidate);
+            }
+        }
+        None
+    }
+
+    fn add_with_artificial_variable(&mut self, row: Row) -> bool {
+        let art = self.fresh(SymbolKind::Slack);
+        self.rows.insert(art, row.clone());
+        self.artificial = Some(row);
+        self.optimize(true);
+        let success = self
+            .artificial
+            .as_ref()
+            .is_some_and(|a| a.constant.abs() < NEAR_ZERO);
+        self.artificial = None;
+
+        if let Some(mut row) = self.rows.remove(&art) {
+            if row.cells.is_empty() {
+                return success && row.constant.abs() < NEAR_ZERO;
+            }
+            let entering = row
+                .cells
+                .keys()
+                .find(|s| s.kind != SymbolKind::Dummy)
+                .copied();
+            let Some(entering) = entering else {
+                return row.constant.abs() < NEAR_ZERO;
+            };
+            row.change_subject(art, entering);
+            self.substitute_out(entering, &row);
+            self.rows.insert(entering, row);
+        }
+        for row in self.rows.values_mut() {
+            row.cells.remove(&art);
+        }
+        self.objective.cells.remove(&art);
+        success
+    }
+
+    fn substitute_out(&mut self, symbol: Symbol, row: &Row) {
+        for (basic, r) in self.rows.iter_mut() {
+            r.substitute(symbol, row);
+            if basic.kind != SymbolKind::External && r.constant < 0.0 {
+                self.infeasible.push(*basic);
+            }
+        }
+        self.objective.substitute(symbol, row);
+        if let Some(artificial) = self.artificial.as_mut() {
+            artificial.substitute(symbol, row);
+        }
+    }
+
+    fn optimize(&mut self, use_artificial: bool) {
+        loop {
+            let objective = if use_artificial {
+                self.artificial.as_ref().expect("artificial phase")
+            } else {
+                &self.objective
+            };
+            let entering = objective
+                .cells
+                .iter()
+                .find(|(s, c)| s.kind != SymbolKind::Dummy && **c < 0.0)
+                .map(|(s, _)| *s);
+            let Some(entering) = entering else { return };
+
+            let mut ratio = f64::INFINITY;
+            let mut leaving = None;
+            for (basic, row) in &self.rows {
+                if basic.kind == SymbolKind::External {
+                    continue;
+                }
+                let coefficient = row.coefficient_for(entering);
+                if coefficient < 0.0 {
+                    let r = -row.constant / coefficient;
+                    if r < ratio {
+                        ratio = r;
+                        leaving = Some(*basic);
+                    }
+                }
+            }
+            let leaving = leaving.expect("linear objective is unbounded");
+            let mut row = self.rows.remove(&leaving).expect("leaving row exists");
+            row.change_subject(leaving, entering);
+            self.substitute_out(entering, &row);
+            self.rows.insert(entering, row);
+        }
+    }
+
+    /// Declares `var` as an edit variable at `strength` (must be below
+    /// [`REQUIRED`]); its value is then driven by
+    /// [`suggest_value`](Self::suggest_value).
+    pub fn add_edit_variable(&mut self, var: Var, strength: f64) -> Result<(), LinearError> {
+        if strength >= REQUIRED {
+            return Err(LinearError::EditStrengthRequired);
+        }
+        if self.edits.contains_key(&var) {
+            return Err(LinearError::DuplicateEdit);
+        }
+        let expr = LinearExpr::term(1.0, var);
+        let (marker, other) = self.add_constraint_internal(&expr, Relation::Eq, strength)?;
+        self.edits.insert(
+            var,
+            EditInfo {
+                marker,
+                other,
+                constant: 0.0,
+            },
+        );
+        Ok(())
+    }
+
+    /// Moves an edit variable to `value` and re-solves incrementally by
+    /// dual pivots, without rebuilding the tableau.
+    pub fn suggest_value(&mut self, var: Var, value: f64) -> Result<(), LinearError> {
+        let info = self.edits.get_mut(&var).ok_or(LinearError::UnknownEdit)?;
+        let delta = value - info.constant;
+        info.constant = value;
+        let marker = info.marker;
+        let other = info.other;
+
+        let adjust = |rows: &mut BTreeMap<Symbol, Row>, infeasible: &mut Vec<Symbol>| {
+            if let Some(row) = rows.get_mut(&marker) {
+                row.constant -= delta;
+                if row.constant < 0.0 {
+                    infeasible.push(marker);
+                }
+                return;
+            }
+            if let Some(o) = other {
+                if let Some(row) = rows.get_mut(&o) {
+                    row.constant += delta;
+                    if row.constant < 0.0 {
+                        infeasible.push(o);
+                    }
+                    return;
+                }
+            }
+            for (basic, row) in rows.iter_mut() {
+                let coefficient = row.coefficient_for(marker);
+                if coefficient != 0.0 {
+                    row.constant += coefficient * delta;
+                    if row.constant < 0.0 && basic.kind != SymbolKind::External {
+                        infeasible.push(*basic);
+                    }
+                }
+            }
+        };
+        adjust(&mut self.rows, &mut self.infeasible);
+        self.dual_optimize();
+        Ok(())
+    }
+
+    fn dual_optimize(&mut self) {
+        while let Some(leaving) = self.infeasible.pop() {
+            let Some(row) = self.rows.get(&leaving) else {
+                continue;
+            };
+            if row.constant >= 0.0 {
+                continue;
+            }
+            let mut ratio = f64::INFINITY;
+            let mut entering = None;
+            for (symbol, coefficient) in &row.cells {
+                if *coefficient > 0.0 && symbol.kind != SymbolKind::Dummy {
+                    let r = self.objective.coefficient_for(*symbol) / coefficient;
+                    if r < ratio {
+                        ratio = r;
+                        entering = Some(*symbol);
+                    }
+                }
+            }
+            let entering = entering.expect("dual optimize found no pivot");
+            let mut row = self.rows.remove(&leaving).expect("leaving row exists");
+            row.change_subject(leaving, entering);
+            self.substitute_out(entering, &row);
+            self.rows.insert(entering, row);
+        }
+    }
+
+    /// Current solved value of `var` (0 for variables the tableau never
+    /// mentions).
+    pub fn value_of(&self, var: Var) -> f64 {
+        let Some(symbol) = self.externals.get(&var) else {
+            return 0.0;
+        };
+        self.rows.get(symbol).map_or(0.0, |row| row.constant)
+    }
+}
+
+impl Default for LinearSolver {
+    fn default() -> Self {
+        LinearSolver::new()
+    }
+}
+
+/// A set of required linear relations usable as a [`Constraint`]:
+/// membership evaluates the relations directly, and projection drives
+/// one persistent [`LinearSolver`] through edit variables, so repeated
+/// projections re-solve incrementally.
+pub struct LinearLayout {
+    dim: usize,
+    relations: Vec<(LinearExpr, Relation)>,
+    solver: Mutex<LinearSolver>,
+}
+
+impl LinearLayout {
+    /// An empty layout over a `dim`-dimensional space, with an edit
+    /// variable per dimension.
+    pub fn new(dim: usize) -> Self {
+        let mut solver = LinearSolver::new();
+        for var in 0..dim {
+            solver
+                .add_edit_variable(var, WEAK)
+                .expect("fresh solver accepts edits");
+        }
+        LinearLayout {
+            dim,
+            relations: Vec::new(),
+            solver: Mutex::new(solver),
+        }
+    }
+
+    /// Adds the required relation `expr (relation) 0`. Panics if the
+    /// expression mentions a variable outside the space; returns
+    /// [`LinearError::Unsatisfiable`] when it contradicts earlier
+    /// relations.
+    pub fn add(&mut self, expr: LinearExpr, relation: Relation) -> Result<(), LinearError> {
+        if let Some(max) = expr.max_var() {
+            assert!(max < self.dim, "variable out of range");
+        }
+        self.solver
+            .lock()
+            .expect("solver lock")
+            .add_constraint(&expr, relation, REQUIRED)?;
+        self.relations.push((expr, relation));
+        Ok(())
+    }
+}
+
+impl Constraint for LinearLayout {
+    fn dim(&self) -> usize {
+        self.dim
+    }
+
+    fn contains(&self, point: &Vector) -> bool {
+        self.relations.iter().all(|(expr, relation)| {
+            let value = expr.evaluate(point);
+            match relation {
+                Relation::Le => value <= crate::EPSILON,
+                Relation::Eq => value.abs() <= crate::EPSILON,
+                Relation::Ge => value >= -crate::EPSILON,
+            }
+        })
+    }
+
+    fn project(&self, point: &Vector) -> Vector {
+        let mut solver = self.solver.lock().expect("solver lock");
+        for var in 0..self.dim {
+            solver
+                .suggest_value(var, point.get(var))
+                .expect("edit variable exists");
+        }
+        Vector::new((0..self.dim).map(|var| solver.value_of(var)).collect())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraint::ConstraintSystem;
+    use crate::rank::RankingCriteria;
+    use crate::suggest::suggest;
+
+    #[test]
+    fn required_inequality_bounds_an_edit_variable() {
+        let mut solver = LinearSolver::new();
+        solver.add_edit_variable(0, WEAK).unwrap();
+        // x >= 10
+        solver
+            .add_constraint(&LinearExpr::term(1.0, 0).constant(-10.0), Relation::Ge, REQUIRED)
+            .unwrap();
+        solver.suggest_value(0, 5.0).unwrap();
+        assert!((solver.value_of(0) - 10.0).abs() < 1e-6);
+        solver.suggest_value(0, 20.0).unwrap();
+        assert!((solver.value_of(0) - 20.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn equalities_propagate_through_the_tableau() {
+        let mut solver = LinearSolver::new();
+        // x == y and y == 10  →  x == 10.
+        solver
+            .add_constraint(
+                &LinearExpr::term(1.0, 0).plus(-1.0, 1),
+                Relation::Eq,
+                REQUIRED,
+            )
+            .unwrap();
+        solver
+            .add_constraint(&LinearExpr::term(1.0, 1).constant(-10.0), Relation::Eq, REQUIRED)
+            .unwrap();
+        assert!((solver.value_of(0) - 10.0).abs() < 1e-6);
+        assert!((solver.value_of(1) - 10.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn contradictory_required_constraints_are_rejected() {
+        let mut solver = LinearSolver::new();
+        solver
+            .add_constraint(&LinearExpr::term(1.0, 0).constant(-10.0), Relation::Eq, REQUIRED)
+            .unwrap();
+        let err = solver.add_constraint(
+            &LinearExpr::term(1.0, 0).constant(-20.0),
+            Relation::Eq,
+            REQUIRED,
+        );
+        assert_eq!(err, Err(LinearError::Unsatisfiable));
+    }
+
+    #[test]
+    fn incremental_suggests_track_a_moving_target() {
+        let mut solver = LinearSolver::new();
+        solver.add_edit_variable(0, WEAK).unwrap();
+        // 0 <= x <= 100.
+        solver
+            .add_constraint(&LinearExpr::term(1.0, 0), Relation::Ge, REQUIRED)
+            .unwrap();
+        solver
+            .add_constraint(&LinearExpr::term(1.0, 0).constant(-100.0), Relation::Le, REQUIRED)
+            .unwrap();
+        for (input, expected) in [(-5.0, 0.0), (42.0, 42.0), (130.0, 100.0), (60.0, 60.0)] {
+            solver.suggest_value(0, input).unwrap();
+            assert!(
+                (solver.value_of(0) - expected).abs() < 1e-6,
+                "suggest {input} solved to {}",
+                solver.value_of(0)
+            );
+        }
+    }
+
+    #[test]
+    fn layout_composes_with_the_geometric_engine() {
+        let mut layout = LinearLayout::new(2);
+        // x <= 50.
+        layout
+            .add(LinearExpr::term(1.0, 0).constant(-50.0), Relation::Le)
+            .unwrap();
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(layout);
+        let current = Vector::new(vec![0.0, 0.0]);
+        let intent = Vector::new(vec![80.0, 10.0]);
+        let r = suggest(&sys, &current, &intent, &RankingCriteria::default());
+        assert!(sys.is_feasible(&r.position));
+        assert!((r.position.get(0) - 50.0).abs() < 1e-6);
+        assert!((r.position.get(1) - 10.0).abs() < 1e-6);
+    }
+}
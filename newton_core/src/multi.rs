@@ -0,0 +1,401 @@
+//! Multi-object constraints.
+//!
+//! Constraints over several objects at once operate on a *stacked*
+//! configuration space: the states of `count` objects, each of
+//! dimension `object_dim`, concatenated in a fixed order. Joint
+//! suggestion then runs the ordinary engine over the stacked space.
+
+use crate::constraint::Constraint;
+use crate::linalg::Vector;
+
+/// Keeps N objects distributed with equal gaps along one axis.
+///
+/// The feasible set fixes only the *relationship*: any first position
+/// and any common gap are allowed, so "distribute horizontally" stays
+/// true while any one element is dragged. Projection is the exact
+/// least-squares fit of a start position and common gap to the current
+/// axis coordinates — the "averaging" that moves every element as
+/// little as possible.
+#[derive(Debug, Clone)]
+pub struct DistributionConstraint {
+    count: usize,
+    object_dim: usize,
+    axis: usize,
+    /// Extent of each object along `axis`; gaps are measured between
+    /// facing edges. All zeros gives equal center spacing.
+    sizes: Vec<f64>,
+    /// Gap deviation tolerated by `contains`.
+    tolerance: f64,
+}
+
+impl DistributionConstraint {
+    /// Panics unless `count >= 3` (fewer objects always satisfy equal
+    /// spacing), `axis < object_dim`, and `sizes.len() == count`.
+    pub fn new(count: usize, object_dim: usize, axis: usize, sizes: Vec<f64>, tolerance: f64) -> Self {
+        assert!(count >= 3, "distribution needs at least three objects");
+        assert!(axis < object_dim, "axis out of range");
+        assert_eq!(sizes.len(), count, "one size per object required");
+        assert!(tolerance >= 0.0, "tolerance must be non-negative");
+        DistributionConstraint {
+            count,
+            object_dim,
+            axis,
+            sizes,
+            tolerance,
+        }
+    }
+
+    /// Validating constructor for untrusted document data: surfaces
+    /// the same conditions [`new`](Self::new) panics on, plus
+    /// non-finite sizes, as errors.
+    pub fn try_new(
+        count: usize,
+        object_dim: usize,
+        axis: usize,
+        sizes: Vec<f64>,
+        tolerance: f64,
+    ) -> Result<Self, crate::error::NewtonError> {
+        if count < 3 {
+            return Err(crate::error::NewtonError::InvalidParameter(
+                "distribution needs at least three objects",
+            ));
+        }
+        if axis >= object_dim {
+            return Err(crate::error::NewtonError::InvalidParameter(
+                "distribution axis must be within the object dimension",
+            ));
+        }
+        if sizes.len() != count {
+            return Err(crate::error::NewtonError::DimensionMismatch {
+                expected: count,
+                actual: sizes.len(),
+            });
+        }
+        if !sizes.iter().all(|s| s.is_finite()) {
+            return Err(crate::error::NewtonError::InvalidParameter(
+                "object sizes must be finite",
+            ));
+        }
+        if !(tolerance.is_finite() && tolerance >= 0.0) {
+            return Err(crate::error::NewtonError::InvalidParameter(
+                "tolerance must be finite and non-negative",
+            ));
+        }
+        Ok(DistributionConstraint::new(
+            count, object_dim, axis, sizes, tolerance,
+        ))
+    }
+
+    /// Equal center spacing (all sizes zero).
+    pub fn centers(count: usize, object_dim: usize, axis: usize, tolerance: f64) -> Self {
+        Self::new(count, object_dim, axis, vec![0.0; count], tolerance)
+    }
+
+    /// Axis coordinate of object `i` within the stacked vector.
+    fn coord(&self, stacked: &Vector, i: usize) -> f64 {
+        stacked.get(i * self.object_dim + self.axis)
+    }
+
+    /// Cumulative edge-to-edge offsets from object 0, excluding gaps:
+    /// `b[i]` such that with a common gap `g`, centers satisfy
+    /// `c[i] = c[0] + b[i] + i * g`.
+    fn size_offsets(&self) -> Vec<f64> {
+        let mut b = vec![0.0; self.count];
+        for i in 1..self.count {
+            b[i] = b[i - 1] + (self.sizes[i - 1] + self.sizes[i]) / 2.0;
+        }
+        b
+    }
+
+    /// The gap between objects `i` and `i + 1` in `stacked`.
+    fn gap(&self, stacked: &Vector, i: usize) -> f64 {
+        self.coord(stacked, i + 1)
+            - self.coord(stacked, i)
+            - (self.sizes[i] + self.sizes[i + 1]) / 2.0
+    }
+}
+
+impl Constraint for DistributionConstraint {
+    fn dim(&self) -> usize {
+        self.count * self.object_dim
+    }
+
+    fn contains(&self, point: &Vector) -> bool {
+        let gaps: Vec<f64> = (0..self.count - 1).map(|i| self.gap(point, i)).collect();
+        let mean = gaps.iter().sum::<f64>() / gaps.len() as f64;
+        gaps.iter()
+            .all(|g| (g - mean).abs() <= self.tolerance + crate::EPSILON)
+    }
+
+    fn project(&self, point: &Vector) -> Vector {
+        // Least-squares fit of c[i] = alpha + i * g + b[i] over the
+        // axis coordinates; other axes pass through untouched.
+        let n = self.count as f64;
+        let b = self.size_offsets();
+        let ys: Vec<f64> = (0..self.count)
+            .map(|i| self.coord(point, i) - b[i])
+            .collect();
+        let xs_mean = (n - 1.0) / 2.0;
+        let ys_mean = ys.iter().sum::<f64>() / n;
+        let mut num = 0.0;
+        let mut den = 0.0;
+        for (i, y) in ys.iter().enumerate() {
+            let dx = i as f64 - xs_mean;
+            num += dx * (y - ys_mean);
+            den += dx * dx;
+        }
+        let g = num / den;
+        let alpha = ys_mean - g * xs_mean;
+        let mut out = point.clone();
+        for (i, bi) in b.iter().enumerate() {
+            out.set(i * self.object_dim + self.axis, alpha + g * i as f64 + bi);
+        }
+        out
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// The mirror a [`SymmetryConstraint`] reflects about.
+#[derive(Debug, Clone)]
+pub enum Mirror {
+    /// Central symmetry about a point: `b = 2c - a`.
+    Point(Vector),
+    /// Reflection across the axis-aligned hyperplane
+    /// `x[axis] = value`; the other components are tied equal.
+    Hyperplane { axis: usize, value: f64 },
+}
+
+/// Ties the second of two stacked objects to the mirror image of the
+/// first, so symmetric layouts stay symmetric while either side is
+/// edited.
+///
+/// Operates on the stacked space `[a..., b...]`. The feasible set is
+/// the affine subspace `b = mirror(a)`; projection is exact and moves
+/// both objects equally, so dragging either side drags its twin.
+#[derive(Debug, Clone)]
+pub struct SymmetryConstraint {
+    object_dim: usize,
+    mirror: Mirror,
+}
+
+impl SymmetryConstraint {
+    /// Panics if the mirror's dimensions are inconsistent with
+    /// `object_dim`.
+    pub fn new(object_dim: usize, mirror: Mirror) -> Self {
+        match &mirror {
+            Mirror::Point(c) => assert_eq!(c.dim(), object_dim, "mirror point dimension mismatch"),
+            Mirror::Hyperplane { axis, .. } => {
+                assert!(*axis < object_dim, "mirror axis out of range")
+            }
+        }
+        SymmetryConstraint { object_dim, mirror }
+    }
+
+    /// Validating constructor for untrusted document data: rejects
+    /// inconsistent or non-finite mirrors instead of panicking.
+    pub fn try_new(
+        object_dim: usize,
+        mirror: Mirror,
+    ) -> Result<Self, crate::error::NewtonError> {
+        match &mirror {
+            Mirror::Point(c) => {
+                if c.dim() != object_dim {
+                    return Err(crate::error::NewtonError::DimensionMismatch {
+                        expected: object_dim,
+                        actual: c.dim(),
+                    });
+                }
+                if !crate::constraint::all_finite(c) {
+                    return Err(crate::error::NewtonError::InvalidParameter(
+                        "mirror point must be finite",
+                    ));
+                }
+            }
+            Mirror::Hyperplane { axis, value } => {
+                if *axis >= object_dim {
+                    return Err(crate::error::NewtonError::InvalidParameter(
+                        "mirror axis must be within the object dimension",
+                    ));
+                }
+                if !value.is_finite() {
+                    return Err(crate::error::NewtonError::InvalidParameter(
+                        "mirror offset must be finite",
+                    ));
+                }
+            }
+        }
+        Ok(SymmetryConstraint::new(object_dim, mirror))
+    }
+
+    /// The mirror image of a single-object state.
+    pub fn reflect(&self, a: &Vector) -> Vector {
+        assert_eq!(a.dim(), self.object_dim, "dimension mismatch in reflect");
+        match &self.mirror {
+            Mirror::Point(c) => c.scale(2.0).sub(a),
+            Mirror::Hyperplane { axis, value } => {
+                let mut out = a.clone();
+                out.set(*axis, 2.0 * value - a.get(*axis));
+                out
+            }
+        }
+    }
+
+    fn split(&self, stacked: &Vector) -> (Vector, Vector) {
+        let view = stacked.view();
+        (
+            view.object(0, self.object_dim).to_vector(),
+            view.object(1, self.object_dim).to_vector(),
+        )
+    }
+}
+
+impl Constraint for SymmetryConstraint {
+    fn dim(&self) -> usize {
+        self.object_dim * 2
+    }
+
+    fn contains(&self, point: &Vector) -> bool {
+        let (a, b) = self.split(point);
+        b.distance(&self.reflect(&a)) < crate::EPSILON
+    }
+
+    fn project(&self, point: &Vector) -> Vector {
+        let (a, b) = self.split(point);
+        // Exact projection onto {(a, reflect(a))}: average each side
+        // with the mirror of the other, which moves both equally.
+        let a_fit = a.add(&self.reflect(&b)).scale(0.5);
+        let b_fit = self.reflect(&a_fit);
+        let mut data = Vec::with_capacity(self.object_dim * 2);
+        data.extend_from_slice(a_fit.as_slice());
+        data.extend_from_slice(b_fit.as_slice());
+        Vector::new(data)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hyperplane_symmetry_projection() {
+        // Mirror across x = 10.
+        let c = SymmetryConstraint::new(2, Mirror::Hyperplane { axis: 0, value: 10.0 });
+        // Perfectly mirrored pair: a at (4, 3), b at (16, 3).
+        let ok = Vector::new(vec![4.0, 3.0, 16.0, 3.0]);
+        assert!(c.contains(&ok));
+        // Drag a to (6, 3): projection shares the correction.
+        let dragged = Vector::new(vec![6.0, 3.0, 16.0, 3.0]);
+        let proj = c.project(&dragged);
+        assert!(c.contains(&proj));
+        assert_eq!(proj, Vector::new(vec![5.0, 3.0, 15.0, 3.0]));
+    }
+
+    #[test]
+    fn point_symmetry_reflects_all_axes() {
+        let c = SymmetryConstraint::new(2, Mirror::Point(Vector::new(vec![0.0, 0.0])));
+        let ok = Vector::new(vec![3.0, -2.0, -3.0, 2.0]);
+        assert!(c.contains(&ok));
+        let off = Vector::new(vec![3.0, -2.0, -3.0, 4.0]);
+        let proj = c.project(&off);
+        assert!(c.contains(&proj));
+        // y components meet in the middle: a_y = -3, b_y = 3.
+        assert_eq!(proj, Vector::new(vec![3.0, -3.0, -3.0, 3.0]));
+    }
+
+    #[test]
+    fn equal_center_spacing_is_feasible() {
+        let c = DistributionConstraint::centers(3, 2, 0, 0.0);
+        // Centers at x = 0, 10, 20.
+        let ok = Vector::new(vec![0.0, 5.0, 10.0, 5.0, 20.0, 5.0]);
+        assert!(c.contains(&ok));
+        let bad = Vector::new(vec![0.0, 5.0, 12.0, 5.0, 20.0, 5.0]);
+        assert!(!c.contains(&bad));
+    }
+
+    #[test]
+    fn projection_redistributes_minimally() {
+        let c = DistributionConstraint::centers(3, 1, 0, 0.0);
+        // Middle element dragged off the rhythm.
+        let p = Vector::new(vec![0.0, 13.0, 20.0]);
+        let proj = c.project(&p);
+        assert!(c.contains(&proj));
+        // The fit shares the disturbance across all three.
+        let gap = proj.get(1) - proj.get(0);
+        assert!((proj.get(2) - proj.get(1) - gap).abs() < 1e-9);
+        // Mean position is preserved by least squares.
+        let mean_before = (0.0 + 13.0 + 20.0) / 3.0;
+        let mean_after = (proj.get(0) + proj.get(1) + proj.get(2)) / 3.0;
+        assert!((mean_before - mean_after).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sized_objects_measure_edge_gaps() {
+        // Objects 4, 2, 4 wide: centers at 0, 8, 16 give edge gaps of
+        // 5 and 5.
+        let c = DistributionConstraint::new(3, 1, 0, vec![4.0, 2.0, 4.0], 0.0);
+        let ok = Vector::new(vec![0.0, 8.0, 16.0]);
+        assert!(c.contains(&ok));
+        // Equal center spacing is NOT equal gaps once sizes differ.
+        let centers_equal = Vector::new(vec![0.0, 8.0, 15.0]);
+        assert!(!c.contains(&centers_equal));
+    }
+
+    #[test]
+    fn tolerance_admits_near_misses() {
+        let c = DistributionConstraint::centers(3, 1, 0, 0.5);
+        let nearly = Vector::new(vec![0.0, 10.3, 20.0]);
+        assert!(c.contains(&nearly));
+    }
+
+    #[test]
+    fn other_axes_pass_through() {
+        let c = DistributionConstraint::centers(3, 2, 0, 0.0);
+        let p = Vector::new(vec![0.0, 1.0, 13.0, 2.0, 20.0, 3.0]);
+        let proj = c.project(&p);
+        assert_eq!(proj.get(1), 1.0);
+        assert_eq!(proj.get(3), 2.0);
+        assert_eq!(proj.get(5), 3.0);
+    }
+
+    #[test]
+    fn try_new_validates_multi_object_parameters() {
+        use crate::error::NewtonError;
+        assert_eq!(
+            DistributionConstraint::try_new(2, 2, 0, vec![0.0, 0.0], 0.0).err(),
+            Some(NewtonError::InvalidParameter(
+                "distribution needs at least three objects"
+            ))
+        );
+        assert_eq!(
+            DistributionConstraint::try_new(3, 2, 0, vec![0.0, 0.0], 0.0).err(),
+            Some(NewtonError::DimensionMismatch {
+                expected: 3,
+                actual: 2
+            })
+        );
+        assert!(DistributionConstraint::try_new(3, 2, 0, vec![0.0; 3], 0.5).is_ok());
+        assert_eq!(
+            SymmetryConstraint::try_new(2, Mirror::Point(Vector::zeros(3))).err(),
+            Some(NewtonError::DimensionMismatch {
+                expected: 2,
+                actual: 3
+            })
+        );
+        assert_eq!(
+            SymmetryConstraint::try_new(2, Mirror::Hyperplane { axis: 2, value: 0.0 }).err(),
+            Some(NewtonError::InvalidParameter(
+                "mirror axis must be within the object dimension"
+            ))
+        );
+        assert!(
+            SymmetryConstraint::try_new(2, Mirror::Hyperplane { axis: 0, value: 5.0 }).is_ok()
+        );
+    }
+}
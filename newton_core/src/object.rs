@@ -0,0 +1,339 @@
+//! Scene objects.
+//!
+//! `newton_core` is UI-toolkit agnostic: an [`NTObject`] is just an
+//! identity plus a point in configuration space, with its constraints
+//! held in the owning [`Scene`] or externally by the host document.
+
+use crate::bounds::Bounds;
+use crate::linalg::Vector;
+
+/// Identifier for an object within a [`Scene`].
+pub type ObjectId = u64;
+
+/// Which point of an object its position refers to, as a fraction of
+/// the object's extent per dimension: `0` is the minimum corner
+/// (top-left in screen coordinates), `0.5` the center, `1` the maximum
+/// corner.
+///
+/// Constraints are typically authored on centers while UIs drag
+/// corners; converting through a shared `Anchor` stops the two from
+/// disagreeing by half a size.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anchor {
+    fractions: Vec<f64>,
+}
+
+impl Anchor {
+    /// Anchor at arbitrary per-dimension fractions. Panics if any
+    /// fraction is outside `[0, 1]`.
+    pub fn new(fractions: Vec<f64>) -> Self {
+        assert!(
+            fractions.iter().all(|f| (0.0..=1.0).contains(f)),
+            "anchor fractions must lie in [0, 1]"
+        );
+        Anchor { fractions }
+    }
+
+    /// Minimum-corner anchor (top-left in 2D screen coordinates).
+    pub fn min_corner(dim: usize) -> Self {
+        Anchor { fractions: vec![0.0; dim] }
+    }
+
+    /// Center anchor.
+    pub fn center(dim: usize) -> Self {
+        Anchor { fractions: vec![0.5; dim] }
+    }
+
+    /// Maximum-corner anchor.
+    pub fn max_corner(dim: usize) -> Self {
+        Anchor { fractions: vec![1.0; dim] }
+    }
+
+    pub fn dim(&self) -> usize {
+        self.fractions.len()
+    }
+
+    /// Offset of the anchor point from the object's minimum corner for
+    /// an object of the given extent.
+    pub fn offset(&self, size: &Vector) -> Vector {
+        assert_eq!(size.dim(), self.dim(), "dimension mismatch in offset");
+        Vector::new(
+            self.fractions
+                .iter()
+                .zip(size.as_slice())
+                .map(|(f, s)| f * s)
+                .collect(),
+        )
+    }
+
+    /// Converts a position expressed at this anchor to the object's
+    /// minimum corner.
+    pub fn to_min_corner(&self, anchored: &Vector, size: &Vector) -> Vector {
+        anchored.sub(&self.offset(size))
+    }
+
+    /// Converts a minimum-corner position to this anchor.
+    pub fn from_min_corner(&self, min_corner: &Vector, size: &Vector) -> Vector {
+        min_corner.add(&self.offset(size))
+    }
+
+    /// Re-expresses a position held at this anchor in terms of another
+    /// anchor, for an object of the given extent.
+    pub fn convert_to(&self, other: &Anchor, position: &Vector, size: &Vector) -> Vector {
+        other.from_min_corner(&self.to_min_corner(position, size), size)
+    }
+}
+
+/// A manipulable object: identity plus current configuration.
+#[derive(Debug, Clone)]
+pub struct NTObject {
+    id: ObjectId,
+    /// Host-facing label; not interpreted by the engine.
+    pub name: String,
+    /// Current position in configuration space, expressed at `anchor`.
+    pub position: Vector,
+    /// Which point of the object `position` refers to.
+    pub anchor: Anchor,
+    /// Extent per dimension. Zero (the default) means a point object.
+    pub size: Vector,
+    /// Rotation pivot as an offset from the anchor point, when it
+    /// differs from the anchor itself. Metadata for hosts and future
+    /// angular constraints; translation ignores it.
+    pub pivot: Option<Vector>,
+}
+
+impl NTObject {
+    pub(crate) fn new(id: ObjectId, name: impl Into<String>, position: Vector) -> Self {
+        let dim = position.dim();
+        NTObject {
+            id,
+            name: name.into(),
+            position,
+            anchor: Anchor::center(dim),
+            size: Vector::zeros(dim),
+            pivot: None,
+        }
+    }
+
+    pub fn id(&self) -> ObjectId {
+        self.id
+    }
+
+    /// This object's position re-expressed at another anchor, given its
+    /// extent.
+    pub fn position_at(&self, anchor: &Anchor, size: &Vector) -> Vector {
+        self.anchor.convert_to(anchor, &self.position, size)
+    }
+
+    /// The rectangle this object occupies (degenerate for point
+    /// objects).
+    pub fn bounds(&self) -> Bounds {
+        let min = self.anchor.to_min_corner(&self.position, &self.size);
+        Bounds::new(min.clone(), min.add(&self.size))
+    }
+}
+
+/// A flat collection of objects sharing one configuration-space
+/// dimension. Ids are unique per scene and never reused.
+pub struct Scene {
+    dim: usize,
+    next_id: ObjectId,
+    objects: Vec<NTObject>,
+}
+
+impl Scene {
+    /// Creates an empty scene over a `dim`-dimensional space.
+    pub fn new(dim: usize) -> Self {
+        Scene {
+            dim,
+            next_id: 1,
+            objects: Vec::new(),
+        }
+    }
+
+    /// Dimension of the configuration space.
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    /// Adds an object and returns its id. Panics on dimension mismatch.
+    pub fn add(&mut self, name: impl Into<String>, position: Vector) -> ObjectId {
+        assert_eq!(position.dim(), self.dim, "object dimension does not match scene");
+        let id = self.next_id;
+        self.next_id += 1;
+        self.objects.push(NTObject::new(id, name, position));
+        id
+    }
+
+    /// Removes an object; returns it if present.
+    pub fn remove(&mut self, id: ObjectId) -> Option<NTObject> {
+        let idx = self.objects.iter().position(|o| o.id == id)?;
+        Some(self.objects.remove(idx))
+    }
+
+    pub fn get(&self, id: ObjectId) -> Option<&NTObject> {
+        self.objects.iter().find(|o| o.id == id)
+    }
+
+    pub fn get_mut(&mut self, id: ObjectId) -> Option<&mut NTObject> {
+        self.objects.iter_mut().find(|o| o.id == id)
+    }
+
+    /// Objects in insertion order.
+    pub fn objects(&self) -> &[NTObject] {
+        &self.objects
+    }
+
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// Objects whose occupied rectangle contains `point`, front-most
+    /// (latest-added) first: the usual picking order.
+    ///
+    /// Queries scan linearly; scenes are document-scale (tens to
+    /// hundreds of objects), so an index would cost more to maintain
+    /// than it saves.
+    pub fn objects_at(&self, point: &Vector) -> Vec<&NTObject> {
+        self.objects
+            .iter()
+            .rev()
+            .filter(|o| o.bounds().contains(point))
+            .collect()
+    }
+
+    /// The object nearest to `point` by distance to its occupied
+    /// rectangle (zero if the point is inside). Later-added objects win
+    /// exact ties. `None` for an empty scene.
+    pub fn nearest_object(&self, point: &Vector) -> Option<&NTObject> {
+        let mut best: Option<(&NTObject, f64)> = None;
+        for o in &self.objects {
+            let d = point.distance(&o.bounds().clamp(point));
+            match best {
+                Some((_, bd)) if d > bd => {}
+                _ => best = Some((o, d)),
+            }
+        }
+        best.map(|(o, _)| o)
+    }
+
+    /// Objects whose occupied rectangle overlaps `bounds`, in insertion
+    /// order.
+    pub fn objects_intersecting(&self, bounds: &Bounds) -> Vec<&NTObject> {
+        self.objects
+            .iter()
+            .filter(|o| o.bounds().intersects(bounds))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_are_unique_and_stable() {
+        let mut scene = Scene::new(2);
+        let a = scene.add("a", Vector::zeros(2));
+        let b = scene.add("b", Vector::zeros(2));
+        assert_ne!(a, b);
+        scene.remove(a);
+        let c = scene.add("c", Vector::zeros(2));
+        assert_ne!(b, c);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn anchor_conversions_round_trip() {
+        let size = Vector::new(vec![10.0, 4.0]);
+        let center = Anchor::center(2);
+        let top_left = Anchor::min_corner(2);
+        // An object whose center sits at (5, 2) has its top-left at the
+        // origin.
+        let at_center = Vector::new(vec![5.0, 2.0]);
+        let at_corner = center.convert_to(&top_left, &at_center, &size);
+        assert_eq!(at_corner, Vector::zeros(2));
+        assert_eq!(top_left.convert_to(&center, &at_corner, &size), at_center);
+    }
+
+    #[test]
+    fn object_position_at_other_anchor() {
+        let mut scene = Scene::new(2);
+        let id = scene.add("card", Vector::new(vec![5.0, 2.0]));
+        let obj = scene.get(id).unwrap();
+        assert_eq!(obj.anchor, Anchor::center(2));
+        let size = Vector::new(vec![10.0, 4.0]);
+        assert_eq!(
+            obj.position_at(&Anchor::min_corner(2), &size),
+            Vector::zeros(2)
+        );
+    }
+
+    fn sized(scene: &mut Scene, name: &str, cx: f64, cy: f64, w: f64, h: f64) -> ObjectId {
+        let id = scene.add(name, Vector::new(vec![cx, cy]));
+        scene.get_mut(id).unwrap().size = Vector::new(vec![w, h]);
+        id
+    }
+
+    #[test]
+    fn hit_testing_prefers_frontmost() {
+        let mut scene = Scene::new(2);
+        let back = sized(&mut scene, "back", 5.0, 5.0, 10.0, 10.0);
+        let front = sized(&mut scene, "front", 5.0, 5.0, 4.0, 4.0);
+        let hits = scene.objects_at(&Vector::new(vec![5.0, 5.0]));
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].id(), front);
+        assert_eq!(hits[1].id(), back);
+        // Outside the small object, only the big one hits.
+        let hits = scene.objects_at(&Vector::new(vec![9.0, 9.0]));
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].id(), back);
+    }
+
+    #[test]
+    fn nearest_object_uses_rectangle_distance() {
+        let mut scene = Scene::new(2);
+        let a = sized(&mut scene, "a", 0.0, 0.0, 2.0, 2.0);
+        let b = sized(&mut scene, "b", 10.0, 0.0, 2.0, 2.0);
+        // (4, 0) is 3 from a's right edge and 5 from b's left edge.
+        assert_eq!(
+            scene.nearest_object(&Vector::new(vec![4.0, 0.0])).unwrap().id(),
+            a
+        );
+        assert_eq!(
+            scene.nearest_object(&Vector::new(vec![8.0, 0.0])).unwrap().id(),
+            b
+        );
+        assert!(Scene::new(2).nearest_object(&Vector::zeros(2)).is_none());
+    }
+
+    #[test]
+    fn intersection_query() {
+        let mut scene = Scene::new(2);
+        let a = sized(&mut scene, "a", 0.0, 0.0, 2.0, 2.0);
+        let _b = sized(&mut scene, "b", 10.0, 0.0, 2.0, 2.0);
+        let region = Bounds::new(Vector::new(vec![-5.0, -5.0]), Vector::new(vec![5.0, 5.0]));
+        let found = scene.objects_intersecting(&region);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id(), a);
+    }
+
+    #[test]
+    #[should_panic(expected = "anchor fractions")]
+    fn out_of_range_fractions_are_rejected() {
+        Anchor::new(vec![0.5, 1.5]);
+    }
+
+    #[test]
+    fn get_and_mutate() {
+        let mut scene = Scene::new(2);
+        let id = scene.add("box", Vector::zeros(2));
+        scene.get_mut(id).unwrap().position = Vector::new(vec![3.0, 4.0]);
+        assert_eq!(scene.get(id).unwrap().position, Vector::new(vec![3.0, 4.0]));
+        assert!(scene.get(999).is_none());
+    }
+}
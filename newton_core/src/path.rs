@@ -0,0 +1,191 @@
+//! Path constraints.
+//!
+//! Restricts a point to lie on a curve — or within a tube around it —
+//! with closest-point projection. Models "the slider thumb stays on its
+//! track" and "the object follows the guide curve" directly instead of
+//! approximating them with stacks of boxes.
+
+use crate::constraint::Constraint;
+use crate::linalg::Vector;
+
+/// The curve a [`PathConstraint`] follows.
+#[derive(Debug, Clone)]
+pub enum PathGeometry {
+    /// Straight segments through the given points (at least two).
+    Polyline(Vec<Vector>),
+    /// A cubic Bézier, flattened to `samples` segments for projection.
+    /// Accuracy is the flattening error; 16–32 samples are plenty for
+    /// screen-scale curves.
+    CubicBezier {
+        p0: Vector,
+        p1: Vector,
+        p2: Vector,
+        p3: Vector,
+        samples: usize,
+    },
+}
+
+/// Keeps a point on a path, or within `tube_radius` of it.
+#[derive(Debug, Clone)]
+pub struct PathConstraint {
+    /// Flattened vertices of the path.
+    vertices: Vec<Vector>,
+    tube_radius: f64,
+}
+
+impl PathConstraint {
+    /// Panics on degenerate geometry (fewer than two vertices, mixed
+    /// dimensions, negative radius, or too few Bézier samples).
+    pub fn new(geometry: PathGeometry, tube_radius: f64) -> Self {
+        assert!(tube_radius >= 0.0, "tube radius must be non-negative");
+        let vertices = match geometry {
+            PathGeometry::Polyline(points) => points,
+            PathGeometry::CubicBezier { p0, p1, p2, p3, samples } => {
+                assert!(samples >= 2, "Bézier flattening needs at least two samples");
+                (0..=samples)
+                    .map(|k| {
+                        let t = k as f64 / samples as f64;
+                        cubic_point(&p0, &p1, &p2, &p3, t)
+                    })
+                    .collect()
+            }
+        };
+        assert!(vertices.len() >= 2, "path needs at least two vertices");
+        let d = vertices[0].dim();
+        assert!(
+            vertices.iter().all(|v| v.dim() == d),
+            "path vertices must share a dimension"
+        );
+        PathConstraint { vertices, tube_radius }
+    }
+
+    /// Thin path (radius zero) through the given points.
+    pub fn polyline(points: Vec<Vector>) -> Self {
+        Self::new(PathGeometry::Polyline(points), 0.0)
+    }
+
+    pub fn tube_radius(&self) -> f64 {
+        self.tube_radius
+    }
+
+    /// Closest point on the (flattened) path to `point`.
+    pub fn closest_point(&self, point: &Vector) -> Vector {
+        let mut best = self.vertices[0].clone();
+        let mut best_d = f64::INFINITY;
+        for seg in self.vertices.windows(2) {
+            let c = closest_on_segment(&seg[0], &seg[1], point);
+            let d = c.distance(point);
+            if d < best_d {
+                best_d = d;
+                best = c;
+            }
+        }
+        best
+    }
+}
+
+impl Constraint for PathConstraint {
+    fn dim(&self) -> usize {
+        self.vertices[0].dim()
+    }
+
+    fn contains(&self, point: &Vector) -> bool {
+        self.closest_point(point).distance(point) <= self.tube_radius + crate::EPSILON
+    }
+
+    fn project(&self, point: &Vector) -> Vector {
+        let on_path = self.closest_point(point);
+        let offset = point.sub(&on_path);
+        let d = offset.norm();
+        if d <= self.tube_radius {
+            point.clone()
+        } else if self.tube_radius == 0.0 || d < crate::EPSILON {
+            on_path
+        } else {
+            // Pull to the tube surface along the offset direction.
+            on_path.add(&offset.scale(self.tube_radius / d))
+        }
+    }
+
+    fn signed_distance(&self, point: &Vector) -> f64 {
+        self.tube_radius - self.closest_point(point).distance(point)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Closest point to `p` on the segment `a`–`b`.
+fn closest_on_segment(a: &Vector, b: &Vector, p: &Vector) -> Vector {
+    let ab = b.sub(a);
+    let len2 = ab.dot(&ab);
+    if len2 < crate::EPSILON {
+        return a.clone();
+    }
+    let t = (p.sub(a).dot(&ab) / len2).clamp(0.0, 1.0);
+    a.add(&ab.scale(t))
+}
+
+/// De Casteljau evaluation of a cubic Bézier at `t`.
+fn cubic_point(p0: &Vector, p1: &Vector, p2: &Vector, p3: &Vector, t: f64) -> Vector {
+    let a = p0.lerp(p1, t);
+    let b = p1.lerp(p2, t);
+    let c = p2.lerp(p3, t);
+    let ab = a.lerp(&b, t);
+    let bc = b.lerp(&c, t);
+    ab.lerp(&bc, t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    #[test]
+    fn polyline_projection_hits_nearest_segment() {
+        let c = PathConstraint::polyline(vec![v(0.0, 0.0), v(10.0, 0.0), v(10.0, 10.0)]);
+        assert_eq!(c.project(&v(4.0, 3.0)), v(4.0, 0.0));
+        assert_eq!(c.project(&v(13.0, 8.0)), v(10.0, 8.0));
+        // Beyond an endpoint clamps to it.
+        assert_eq!(c.project(&v(-5.0, 1.0)), v(0.0, 0.0));
+        assert!(c.contains(&v(10.0, 5.0)));
+        assert!(!c.contains(&v(5.0, 1.0)));
+    }
+
+    #[test]
+    fn tube_admits_nearby_points() {
+        let c = PathConstraint::new(
+            PathGeometry::Polyline(vec![v(0.0, 0.0), v(10.0, 0.0)]),
+            2.0,
+        );
+        assert!(c.contains(&v(5.0, 1.5)));
+        assert!(!c.contains(&v(5.0, 3.0)));
+        // Projection lands on the tube surface, not the centerline.
+        assert_eq!(c.project(&v(5.0, 4.0)), v(5.0, 2.0));
+        assert_eq!(c.signed_distance(&v(5.0, 1.5)), 0.5);
+    }
+
+    #[test]
+    fn bezier_endpoints_are_on_the_path() {
+        let c = PathConstraint::new(
+            PathGeometry::CubicBezier {
+                p0: v(0.0, 0.0),
+                p1: v(0.0, 10.0),
+                p2: v(10.0, 10.0),
+                p3: v(10.0, 0.0),
+                samples: 32,
+            },
+            0.0,
+        );
+        assert!(c.contains(&v(0.0, 0.0)));
+        assert!(c.contains(&v(10.0, 0.0)));
+        // The curve's apex for this symmetric case is (5, 7.5).
+        let apex = c.project(&v(5.0, 20.0));
+        assert!((apex.get(0) - 5.0).abs() < 0.1);
+        assert!((apex.get(1) - 7.5).abs() < 0.1);
+    }
+}
@@ -0,0 +1,308 @@
+//! Mixed-unit preconditioning.
+//!
+//! Distance is unit-blind: a system mixing pixel positions (0–4000)
+//! and normalized opacity (0–1) measures a full opacity flip as a
+//! one-pixel nudge, so projection, ranking, and engagement are all
+//! decided by the pixel dimensions alone. [`DimensionScaling`] fixes
+//! the metric instead of every call site: each dimension gets a factor
+//! (derived from the system's box extents, or supplied explicitly from
+//! unit metadata), states are scaled into a space where every
+//! dimension is comparably sized, the ordinary suggestion search runs
+//! there, and the answer is unscaled on the way out.
+//!
+//! The constraints are re-expressed over the scaled space by a wrapper
+//! in the style of [`crate::group`]'s translation space, so the search
+//! machinery runs unchanged.
+
+use crate::constraint::{Constraint, ConstraintRef, ConstraintSystem};
+use crate::linalg::Vector;
+use crate::rank::RankingCriteria;
+use crate::suggest::{suggest, SuggestResponse};
+
+/// Per-dimension scale factors mapping host units into a comparable
+/// space: `scaled[d] = raw[d] · factor[d]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DimensionScaling {
+    factors: Vector,
+}
+
+impl DimensionScaling {
+    /// The identity scaling: every dimension keeps its host units.
+    pub fn uniform(dim: usize) -> Self {
+        DimensionScaling {
+            factors: Vector::new(vec![1.0; dim]),
+        }
+    }
+
+    /// Explicit per-dimension factors, for hosts that know their units
+    /// (e.g. `1/4000` for a pixel axis, `1` for an opacity axis).
+    /// Panics unless every factor is strictly positive and finite.
+    pub fn from_factors(factors: Vector) -> Self {
+        assert!(
+            factors.as_slice().iter().all(|&f| f.is_finite() && f > 0.0),
+            "scale factors must be positive and finite"
+        );
+        DimensionScaling { factors }
+    }
+
+    /// Derives factors from the system's [`BoxConstraint`](
+    /// crate::constraint::BoxConstraint) extents: each dimension maps
+    /// its widest box extent to unit length, so a 0–4000 pixel axis
+    /// and a 0–1 opacity axis come out the same size. Dimensions no
+    /// box constrains (or constrains to a degenerate extent) keep
+    /// their host units.
+    pub fn from_system(system: &ConstraintSystem) -> Self {
+        let mut extents = vec![0.0_f64; system.dim()];
+        for c in system.constraints() {
+            let Some(b) = c
+                .as_any()
+                .downcast_ref::<crate::constraint::BoxConstraint>()
+            else {
+                continue;
+            };
+            let bounds = b.bounds();
+            for (d, extent) in extents.iter_mut().enumerate() {
+                *extent = extent.max(bounds.max().get(d) - bounds.min().get(d));
+            }
+        }
+        let factors = extents
+            .into_iter()
+            .map(|e| if e > crate::EPSILON { 1.0 / e } else { 1.0 })
+            .collect();
+        DimensionScaling {
+            factors: Vector::new(factors),
+        }
+    }
+
+    /// Dimension count the factors cover.
+    pub fn dim(&self) -> usize {
+        self.factors.dim()
+    }
+
+    /// The factor applied to dimension `d`.
+    pub fn factor(&self, d: usize) -> f64 {
+        self.factors.get(d)
+    }
+
+    /// A point mapped into scaled space.
+    pub fn scale(&self, point: &Vector) -> Vector {
+        assert_eq!(point.dim(), self.dim(), "point dimension mismatch");
+        Vector::new(
+            point
+                .as_slice()
+                .iter()
+                .zip(self.factors.as_slice())
+                .map(|(x, f)| x * f)
+                .collect(),
+        )
+    }
+
+    /// A scaled-space point mapped back into host units.
+    pub fn unscale(&self, point: &Vector) -> Vector {
+        assert_eq!(point.dim(), self.dim(), "point dimension mismatch");
+        Vector::new(
+            point
+                .as_slice()
+                .iter()
+                .zip(self.factors.as_slice())
+                .map(|(x, f)| x / f)
+                .collect(),
+        )
+    }
+
+    /// The smallest factor — the one belonging to the coarsest
+    /// dimension. Lengths multiplied by it stay meaningful for that
+    /// dimension in scaled space.
+    fn min_factor(&self) -> f64 {
+        self.factors
+            .as_slice()
+            .iter()
+            .copied()
+            .fold(f64::INFINITY, f64::min)
+    }
+}
+
+/// A constraint re-expressed over scaled space: `s` satisfies it
+/// exactly when its unscaled preimage satisfies the inner constraint.
+///
+/// Projection maps the inner projection through the scaling, which is
+/// the exact scaled-space projection for everything axis-aligned or
+/// separable (boxes, lattices, discrete sets, collisions) and an
+/// approximation for oblique constraints, whose projections do not
+/// commute with anisotropic scaling.
+struct ScaledConstraint {
+    inner: ConstraintRef,
+    scaling: DimensionScaling,
+}
+
+impl Constraint for ScaledConstraint {
+    fn dim(&self) -> usize {
+        self.inner.dim()
+    }
+
+    fn contains(&self, s: &Vector) -> bool {
+        self.inner.contains(&self.scaling.unscale(s))
+    }
+
+    fn project(&self, s: &Vector) -> Vector {
+        self.scaling.scale(&self.inner.project(&self.scaling.unscale(s)))
+    }
+
+    fn signed_distance(&self, s: &Vector) -> f64 {
+        // Anisotropic scaling distorts the inner distance by a
+        // direction-dependent amount; bound it conservatively by the
+        // smallest factor so slack is never overstated.
+        let raw = self.inner.signed_distance(&self.scaling.unscale(s));
+        if raw >= 0.0 {
+            raw * self.scaling.min_factor()
+        } else {
+            -s.distance(&self.project(s))
+        }
+    }
+
+    fn boundary_normal(&self, s: &Vector) -> Option<Vector> {
+        // Normals transform by the inverse scaling, renormalized.
+        let n = self.inner.boundary_normal(&self.scaling.unscale(s))?;
+        let mapped = self.scaling.unscale(&n);
+        let len = mapped.norm();
+        if len > crate::EPSILON {
+            Some(mapped.scale(1.0 / len))
+        } else {
+            None
+        }
+    }
+
+    fn interior_point(&self) -> Option<Vector> {
+        self.inner.interior_point().map(|p| self.scaling.scale(&p))
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// The system re-expressed over scaled space. The search radius is
+/// rescaled by the coarsest dimension's factor so the escape ring
+/// stays the same size where it mattered most; the numeric policy and
+/// angular metadata carry over unchanged.
+fn scaled_space(system: &ConstraintSystem, scaling: &DimensionScaling) -> ConstraintSystem {
+    let mut out = ConstraintSystem::new(system.dim());
+    let mut policy = system.search_policy().clone();
+    policy.set_search_radius(system.search_policy().search_radius() * scaling.min_factor());
+    out.set_search_policy(policy);
+    out.set_numeric_policy(system.numeric_policy().clone());
+    out.set_angular_dims(system.angular_dims().to_vec());
+    for c in system.constraints() {
+        out.add(ScaledConstraint {
+            inner: c.clone(),
+            scaling: scaling.clone(),
+        });
+    }
+    out
+}
+
+/// [`suggest`] with the distance metric preconditioned by `scaling`:
+/// the search runs in scaled space, where every dimension is
+/// comparably sized, and the response's positions are mapped back to
+/// host units. Panics on dimension mismatches.
+pub fn suggest_preconditioned(
+    system: &ConstraintSystem,
+    current: &Vector,
+    intent: &Vector,
+    criteria: &RankingCriteria,
+    scaling: &DimensionScaling,
+) -> SuggestResponse {
+    assert_eq!(scaling.dim(), system.dim(), "scaling dimension mismatch");
+    let space = scaled_space(system, scaling);
+    let mut response = suggest(
+        &space,
+        &scaling.scale(current),
+        &scaling.scale(intent),
+        criteria,
+    );
+    response.position = scaling.unscale(&response.position);
+    for alt in &mut response.alternatives {
+        alt.position = scaling.unscale(&alt.position);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bounds::Bounds;
+    use crate::constraint::BoxConstraint;
+    use crate::suggest::SuggestionQuality;
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    /// 4000-pixel canvas position stacked with a 0–1 opacity.
+    fn mixed_system() -> ConstraintSystem {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(4000.0, 1.0))));
+        sys
+    }
+
+    #[test]
+    fn factors_derive_from_box_extents() {
+        let scaling = DimensionScaling::from_system(&mixed_system());
+        assert!((scaling.factor(0) - 1.0 / 4000.0).abs() < 1e-12);
+        assert!((scaling.factor(1) - 1.0).abs() < 1e-12);
+        // Unconstrained dimensions keep host units.
+        assert_eq!(
+            DimensionScaling::from_system(&ConstraintSystem::new(2)),
+            DimensionScaling::uniform(2)
+        );
+    }
+
+    #[test]
+    fn scale_and_unscale_round_trip() {
+        let scaling = DimensionScaling::from_factors(v(1.0 / 4000.0, 1.0));
+        let p = v(2000.0, 0.25);
+        assert_eq!(scaling.scale(&p), v(0.5, 0.25));
+        assert!(scaling.unscale(&scaling.scale(&p)).distance(&p) < 1e-9);
+    }
+
+    #[test]
+    fn feasible_intents_come_back_in_host_units() {
+        let sys = mixed_system();
+        let scaling = DimensionScaling::from_system(&sys);
+        let r = suggest_preconditioned(
+            &sys,
+            &v(100.0, 0.5),
+            &v(200.0, 0.75),
+            &RankingCriteria::default(),
+            &scaling,
+        );
+        assert_eq!(r.quality, SuggestionQuality::Exact);
+        assert!(r.position.distance(&v(200.0, 0.75)) < 1e-9);
+    }
+
+    #[test]
+    fn small_dimensions_are_not_drowned_out() {
+        // Both dimensions overshoot their range by the same *relative*
+        // amount; the preconditioned projection pulls both back to
+        // their boundaries rather than treating the opacity excess as
+        // rounding noise next to 400 pixels.
+        let sys = mixed_system();
+        let scaling = DimensionScaling::from_system(&sys);
+        let r = suggest_preconditioned(
+            &sys,
+            &v(3000.0, 0.9),
+            &v(4400.0, 1.1),
+            &RankingCriteria::default(),
+            &scaling,
+        );
+        assert_eq!(r.quality, SuggestionQuality::Projected);
+        assert!((r.position.get(0) - 4000.0).abs() < 1e-6);
+        assert!((r.position.get(1) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    #[should_panic(expected = "scale factors must be positive and finite")]
+    fn non_positive_factors_are_rejected() {
+        DimensionScaling::from_factors(v(1.0, 0.0));
+    }
+}
@@ -0,0 +1,622 @@
+//! Projection onto the intersection of constraint sets.
+//!
+//! The workhorse is Dykstra's algorithm, which — unlike plain
+//! alternating projection — converges to the *nearest* point of the
+//! intersection when all sets are convex. With nonconvex constraints
+//! (collision, discrete) it degrades to a heuristic, which is why the
+//! suggestion layer samples candidates instead of trusting a single
+//! projection.
+
+use crate::constraint::ConstraintSystem;
+use crate::linalg::Vector;
+
+/// Iteration and convergence controls for the projection routines.
+#[derive(Debug, Clone)]
+pub struct ProjectionOptions {
+    /// Upper bound on full sweeps over the constraint set.
+    pub max_iterations: usize,
+    /// Sweep-to-sweep movement below this is treated as converged.
+    pub tolerance: f64,
+}
+
+impl Default for ProjectionOptions {
+    fn default() -> Self {
+        ProjectionOptions {
+            max_iterations: 100,
+            tolerance: 1e-7,
+        }
+    }
+}
+
+impl ProjectionOptions {
+    /// Default options with the tolerance taken from the system's
+    /// [`crate::constraint::NumericPolicy`], so convergence is judged
+    /// at the problem's own coordinate scale.
+    pub fn for_system(system: &ConstraintSystem) -> Self {
+        ProjectionOptions {
+            tolerance: system.numeric_policy().tolerance(),
+            ..ProjectionOptions::default()
+        }
+    }
+}
+
+/// Outcome of an iterative projection.
+#[derive(Debug, Clone)]
+pub struct ProjectionResult {
+    /// Final iterate. Feasibility is not guaranteed when `converged` is
+    /// false or the system is nonconvex.
+    pub point: Vector,
+    /// Sweeps actually performed.
+    pub iterations: usize,
+    /// True if the iterate stabilised within tolerance.
+    pub converged: bool,
+    /// Magnitude of each constraint's final Dykstra correction vector,
+    /// in constraint order. A substantial magnitude at convergence
+    /// marks a constraint as active in the solution; the vector is
+    /// empty for routines that keep no corrections
+    /// ([`project_alternating`]).
+    pub correction_magnitudes: Vec<f64>,
+    /// On a non-converged run, the constraints whose projection still
+    /// moved the iterate beyond tolerance in the final sweep — the
+    /// constraints the iterate was cycling between, and the first thing
+    /// to look at when attributing the failure. Empty when `converged`
+    /// is true.
+    pub active_constraints: Vec<usize>,
+}
+
+/// Dykstra's alternating projection with correction vectors.
+pub fn project_dykstra(
+    system: &ConstraintSystem,
+    point: &Vector,
+    options: &ProjectionOptions,
+) -> ProjectionResult {
+    dykstra_sweeps(system, point, options, None)
+}
+
+/// [`project_dykstra`], also recording the iterate after every sweep —
+/// the starting point first, the final iterate last — so developer
+/// tooling can visualize how a specific interaction converged (or
+/// cycled) without recompiling with ad-hoc logging. The trail costs
+/// one clone per sweep; the plain entry point records nothing.
+pub fn project_dykstra_with_history(
+    system: &ConstraintSystem,
+    point: &Vector,
+    options: &ProjectionOptions,
+) -> (ProjectionResult, Vec<Vector>) {
+    let mut history = vec![point.clone()];
+    let result = dykstra_sweeps(system, point, options, Some(&mut history));
+    (result, history)
+}
+
+/// The sweep loop behind both Dykstra entry points; `history`, when
+/// supplied, receives the iterate after every sweep.
+fn dykstra_sweeps(
+    system: &ConstraintSystem,
+    point: &Vector,
+    options: &ProjectionOptions,
+    mut history: Option<&mut Vec<Vector>>,
+) -> ProjectionResult {
+    let constraints = system.constraints();
+    if constraints.is_empty() {
+        return ProjectionResult {
+            point: point.clone(),
+            iterations: 0,
+            converged: true,
+            correction_magnitudes: Vec::new(),
+            active_constraints: Vec::new(),
+        };
+    }
+    let mut x = point.clone();
+    let mut corrections = vec![Vector::zeros(point.dim()); constraints.len()];
+    let mut moves = vec![0.0; constraints.len()];
+    let finish = |x: Vector,
+                  iterations: usize,
+                  converged: bool,
+                  corrections: &[Vector],
+                  moves: &[f64]| {
+        ProjectionResult {
+            point: x,
+            iterations,
+            converged,
+            correction_magnitudes: corrections.iter().map(Vector::norm).collect(),
+            active_constraints: if converged {
+                Vec::new()
+            } else {
+                (0..moves.len())
+                    .filter(|&i| moves[i] >= options.tolerance)
+                    .collect()
+            },
+        }
+    };
+    for sweep in 0..options.max_iterations {
+        let before = x.clone();
+        // The iterate alone can stall for a few sweeps while the
+        // corrections still shift (and the limit moves on), so
+        // convergence requires both to stabilise.
+        let mut correction_shift = 0.0_f64;
+        for (i, (c, correction)) in constraints.iter().zip(corrections.iter_mut()).enumerate() {
+            let y = x.add(correction);
+            let projected = c.project(&y);
+            let updated = y.sub(&projected);
+            correction_shift = correction_shift.max(correction.distance(&updated));
+            *correction = updated;
+            moves[i] = x.distance(&projected);
+            x = projected;
+        }
+        if let Some(history) = history.as_deref_mut() {
+            history.push(x.clone());
+        }
+        if x.distance(&before) < options.tolerance && correction_shift < options.tolerance {
+            return finish(x, sweep + 1, true, &corrections, &moves);
+        }
+    }
+    finish(x, options.max_iterations, false, &corrections, &moves)
+}
+
+/// [`project_dykstra`] with its failure modes surfaced as values
+/// instead of panics and best-effort iterates, for untrusted input and
+/// host bindings (see [`crate::error`]). A dimension mismatch is an
+/// error rather than a panic; a result that stopped at the iteration
+/// cap or converged somewhere infeasible (nonconvex cycling) is an
+/// error rather than a quietly bad point.
+pub fn try_project_dykstra(
+    system: &ConstraintSystem,
+    point: &Vector,
+    options: &ProjectionOptions,
+) -> Result<ProjectionResult, crate::error::NewtonError> {
+    use crate::error::NewtonError;
+    if point.dim() != system.dim() {
+        return Err(NewtonError::DimensionMismatch {
+            expected: system.dim(),
+            actual: point.dim(),
+        });
+    }
+    let result = project_dykstra(system, point, options);
+    if !result.converged {
+        return Err(NewtonError::NonConvergence {
+            iterations: result.iterations,
+        });
+    }
+    if system.margin(&result.point) < -options.tolerance {
+        return Err(NewtonError::Infeasible);
+    }
+    Ok(result)
+}
+
+/// Plain alternating projection (von Neumann / POCS). Converges to *a*
+/// point of the intersection for convex sets, not necessarily the
+/// nearest one. Cheaper per sweep than Dykstra.
+pub fn project_alternating(
+    system: &ConstraintSystem,
+    point: &Vector,
+    options: &ProjectionOptions,
+) -> ProjectionResult {
+    let mut x = point.clone();
+    for sweep in 0..options.max_iterations {
+        let before = x.clone();
+        for c in system.constraints() {
+            x = c.project(&x);
+        }
+        if x.distance(&before) < options.tolerance {
+            return ProjectionResult {
+                point: x,
+                iterations: sweep + 1,
+                converged: true,
+                correction_magnitudes: Vec::new(),
+                active_constraints: Vec::new(),
+            };
+        }
+    }
+    ProjectionResult {
+        point: x,
+        iterations: options.max_iterations,
+        converged: false,
+        correction_magnitudes: Vec::new(),
+        active_constraints: Vec::new(),
+    }
+}
+
+/// Rotated orderings tried by [`audit_projection_order`], beyond the
+/// original and reversed orders.
+const AUDIT_ROTATIONS: usize = 4;
+
+/// Result of an order-independence audit.
+#[derive(Debug, Clone)]
+pub struct OrderAudit {
+    /// Projected point under each ordering, original order first, then
+    /// reversed, then the rotations.
+    pub points: Vec<Vector>,
+    /// Largest pairwise distance between those points. Near zero for a
+    /// well-conditioned convex system; anything visible means the
+    /// answer depends on constraint order and the system deserves a
+    /// look (nonconvexity, or corrections fighting each other).
+    pub max_divergence: f64,
+}
+
+/// Debug/authoring check: runs [`project_dykstra`] under several
+/// deterministic constraint orderings (original, reversed, and up to
+/// [`AUDIT_ROTATIONS`] rotations) and reports how far the answers
+/// spread. Dykstra's limit is order-independent on convex systems, so
+/// divergence here localises trouble that a single run cannot see. Not
+/// for hot paths — it costs one full projection per ordering.
+pub fn audit_projection_order(
+    system: &ConstraintSystem,
+    point: &Vector,
+    options: &ProjectionOptions,
+) -> OrderAudit {
+    let n = system.constraints().len();
+    let mut orderings: Vec<Vec<usize>> = vec![(0..n).collect()];
+    if n > 1 {
+        orderings.push((0..n).rev().collect());
+        for k in 1..=AUDIT_ROTATIONS.min(n - 1) {
+            orderings.push((0..n).map(|i| (i + k) % n).collect());
+        }
+    }
+
+    let points: Vec<Vector> = orderings
+        .iter()
+        .map(|order| {
+            let mut permuted = ConstraintSystem::new(system.dim());
+            for &i in order {
+                permuted.add_ref(system.constraints()[i].clone());
+            }
+            project_dykstra(&permuted, point, options).point
+        })
+        .collect();
+
+    let mut max_divergence = 0.0_f64;
+    for i in 0..points.len() {
+        for j in i + 1..points.len() {
+            max_divergence = max_divergence.max(points[i].distance(&points[j]));
+        }
+    }
+    OrderAudit {
+        points,
+        max_divergence,
+    }
+}
+
+/// One sweep through the constraints, no convergence loop. The fast
+/// approximation used on hot paths where an exact answer is not worth a
+/// frame.
+pub fn project_single_pass(system: &ConstraintSystem, point: &Vector) -> Vector {
+    let mut x = point.clone();
+    for c in system.constraints() {
+        x = c.project(&x);
+    }
+    x
+}
+
+/// [`project_single_pass`] writing the result into a caller-provided
+/// buffer. With the result landing in `out` and intermediates stored
+/// inline for low-dimensional states, the pass performs no heap
+/// allocation — for real-time hosts running a strict allocation
+/// budget per frame. Panics when `out`'s dimension does not match.
+pub fn project_single_pass_into(system: &ConstraintSystem, point: &Vector, out: &mut Vector) {
+    let x = project_single_pass(system, point);
+    out.view_mut().copy_from(&x.view());
+}
+
+/// [`project_dykstra`] writing the final iterate into `out`. Returns
+/// sweeps performed and whether the iterate converged; the diagnostic
+/// vectors of [`ProjectionResult`] are deliberately absent because
+/// building them allocates. The sweep's own correction bookkeeping
+/// still allocates per call — the fully allocation-free hot path is
+/// [`project_single_pass_into`] and
+/// [`suggest_fast_into`](crate::suggest::suggest_fast_into). Panics
+/// when `out`'s dimension does not match.
+pub fn project_dykstra_into(
+    system: &ConstraintSystem,
+    point: &Vector,
+    options: &ProjectionOptions,
+    out: &mut Vector,
+) -> (usize, bool) {
+    let result = project_dykstra(system, point, options);
+    out.view_mut().copy_from(&result.point.view());
+    (result.iterations, result.converged)
+}
+
+/// Alternating sweeps spent chasing an upper bound in
+/// [`distance_to_intersection`].
+const DISTANCE_BOUND_SWEEPS: usize = 8;
+
+/// Lower and upper bounds on the distance from a point to the feasible
+/// intersection.
+#[derive(Debug, Clone, Copy)]
+pub struct DistanceBound {
+    /// Never above the true distance: the deepest single-constraint
+    /// violation (distance to each set individually is a lower bound
+    /// on distance to their intersection).
+    pub lower: f64,
+    /// Distance to an actually feasible point found by a few cheap
+    /// sweeps, or infinity when none was found within the budget.
+    pub upper: f64,
+}
+
+/// How far `point` is from the feasible intersection, bounded cheaply
+/// instead of computed exactly. Engagement classification
+/// ([`crate::fgstate`]) needs "how badly blocked is this?" every input
+/// event but has no use for the projected point itself, so it gets the
+/// bounds at a fraction of a full Dykstra run. The bounds coincide for
+/// a feasible point (both zero) and for a single violated constraint.
+pub fn distance_to_intersection(system: &ConstraintSystem, point: &Vector) -> DistanceBound {
+    let lower = system
+        .constraints()
+        .iter()
+        .map(|c| (-c.signed_distance(point)).max(0.0))
+        .fold(0.0_f64, f64::max);
+    if lower == 0.0 && system.is_feasible(point) {
+        return DistanceBound {
+            lower: 0.0,
+            upper: 0.0,
+        };
+    }
+    // Any feasible point caps the distance from above; take the first
+    // one the cheap sweeps produce.
+    let single = project_single_pass(system, point);
+    if system.is_feasible(&single) {
+        return DistanceBound {
+            lower,
+            upper: point.distance(&single),
+        };
+    }
+    let options = ProjectionOptions {
+        max_iterations: DISTANCE_BOUND_SWEEPS,
+        ..ProjectionOptions::default()
+    };
+    let alternating = project_alternating(system, point, &options);
+    let upper = if system.is_feasible(&alternating.point) {
+        point.distance(&alternating.point)
+    } else {
+        f64::INFINITY
+    };
+    DistanceBound { lower, upper }
+}
+
+/// [`project_alternating`], but with the validity contract restored:
+/// when the fast result still violates any constraint beyond the
+/// tolerance, the call falls back to full [`project_dykstra`] with the
+/// same options. Hot paths get the cheap sweep in the common case and
+/// only pay for Dykstra when the cheap answer is actually wrong.
+pub fn project_alternating_checked(
+    system: &ConstraintSystem,
+    point: &Vector,
+    options: &ProjectionOptions,
+) -> ProjectionResult {
+    let fast = project_alternating(system, point, options);
+    if system.margin(&fast.point) >= -options.tolerance {
+        return fast;
+    }
+    project_dykstra(system, point, options)
+}
+
+/// [`project_single_pass`] with the same guard as
+/// [`project_alternating_checked`]: the single sweep's result is kept
+/// only if it satisfies every constraint to within `options.tolerance`,
+/// otherwise a full Dykstra run (under `options`) replaces it.
+pub fn project_single_pass_checked(
+    system: &ConstraintSystem,
+    point: &Vector,
+    options: &ProjectionOptions,
+) -> Vector {
+    let fast = project_single_pass(system, point);
+    if system.margin(&fast) >= -options.tolerance {
+        return fast;
+    }
+    project_dykstra(system, point, options).point
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bounds::Bounds;
+    use crate::constraint::{BoxConstraint, CollisionConstraint, HalfspaceConstraint};
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    fn box_and_halfspace() -> ConstraintSystem {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(10.0, 10.0))));
+        sys.add(HalfspaceConstraint::new(v(1.0, 1.0), 10.0));
+        sys
+    }
+
+    #[test]
+    fn options_for_system_use_its_numeric_policy() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.set_numeric_policy(crate::constraint::NumericPolicy::for_magnitude(1e12));
+        let options = ProjectionOptions::for_system(&sys);
+        assert_eq!(options.tolerance, 1e-7 * 1e12);
+        assert_eq!(
+            options.max_iterations,
+            ProjectionOptions::default().max_iterations
+        );
+    }
+
+    #[test]
+    fn into_variants_write_the_same_answers() {
+        let sys = box_and_halfspace();
+        let options = ProjectionOptions::default();
+        let start = v(15.0, 15.0);
+        let mut out = Vector::zeros(2);
+        project_single_pass_into(&sys, &start, &mut out);
+        assert_eq!(out, project_single_pass(&sys, &start));
+        let plain = project_dykstra(&sys, &start, &options);
+        let (iterations, converged) = project_dykstra_into(&sys, &start, &options, &mut out);
+        assert_eq!(out, plain.point);
+        assert_eq!(iterations, plain.iterations);
+        assert_eq!(converged, plain.converged);
+    }
+
+    #[test]
+    fn history_matches_the_plain_projection() {
+        let sys = box_and_halfspace();
+        let options = ProjectionOptions::default();
+        let start = v(15.0, 15.0);
+        let plain = project_dykstra(&sys, &start, &options);
+        let (traced, history) = project_dykstra_with_history(&sys, &start, &options);
+        assert_eq!(traced.point, plain.point);
+        assert_eq!(traced.iterations, plain.iterations);
+        // The starting point first, one entry per sweep after.
+        assert_eq!(history.len(), traced.iterations + 1);
+        assert_eq!(history[0], start);
+        assert_eq!(*history.last().unwrap(), traced.point);
+    }
+
+    #[test]
+    fn try_projection_reports_failures_as_errors() {
+        use crate::error::NewtonError;
+        let sys = box_and_halfspace();
+        let options = ProjectionOptions::default();
+        let ok = try_project_dykstra(&sys, &v(20.0, 20.0), &options).unwrap();
+        assert!(sys.is_feasible(&ok.point));
+        assert_eq!(
+            try_project_dykstra(&sys, &Vector::zeros(3), &options).unwrap_err(),
+            NewtonError::DimensionMismatch {
+                expected: 2,
+                actual: 3
+            }
+        );
+        // Disjoint boxes: the iterate cannot settle anywhere feasible.
+        let mut disjoint = ConstraintSystem::new(2);
+        disjoint.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(1.0, 1.0))));
+        disjoint.add(BoxConstraint::new(Bounds::new(v(5.0, 5.0), v(6.0, 6.0))));
+        assert!(try_project_dykstra(&disjoint, &v(3.0, 3.0), &options).is_err());
+    }
+
+    #[test]
+    fn dykstra_finds_feasible_point() {
+        let sys = box_and_halfspace();
+        let r = project_dykstra(&sys, &v(12.0, 12.0), &ProjectionOptions::default());
+        assert!(r.converged);
+        assert!(sys.is_feasible(&r.point));
+    }
+
+    #[test]
+    fn dykstra_identity_on_feasible_input() {
+        let sys = box_and_halfspace();
+        let p = v(2.0, 2.0);
+        let r = project_dykstra(&sys, &p, &ProjectionOptions::default());
+        assert!(r.converged);
+        assert!(r.point.distance(&p) < 1e-9);
+    }
+
+    #[test]
+    fn diagnostics_attribute_convergence_and_cycling() {
+        // Converged: the halfspace is the active constraint (nonzero
+        // final correction), the box never engages, nothing is still
+        // moving.
+        let sys = box_and_halfspace();
+        let r = project_dykstra(&sys, &v(12.0, 12.0), &ProjectionOptions::default());
+        assert!(r.converged);
+        assert!(r.active_constraints.is_empty());
+        assert_eq!(r.correction_magnitudes.len(), 2);
+        assert!(r.correction_magnitudes[1] > 1.0);
+
+        // Cycling: a corridor walled off by a wider obstacle bounces
+        // the iterate between the two constraints forever; both are
+        // reported as still active.
+        let mut blocked = ConstraintSystem::new(2);
+        blocked.add(BoxConstraint::new(Bounds::new(v(40.0, -300.0), v(60.0, 300.0))));
+        blocked.add(CollisionConstraint::new(Bounds::new(
+            v(0.0, -200.0),
+            v(100.0, 200.0),
+        )));
+        let r = project_dykstra(&blocked, &v(50.0, 0.0), &ProjectionOptions::default());
+        assert!(!r.converged);
+        assert_eq!(r.active_constraints, vec![0, 1]);
+    }
+
+    #[test]
+    fn empty_system_is_identity() {
+        let sys = ConstraintSystem::new(2);
+        let r = project_dykstra(&sys, &v(3.0, 4.0), &ProjectionOptions::default());
+        assert_eq!(r.point, v(3.0, 4.0));
+        assert_eq!(r.iterations, 0);
+    }
+
+    #[test]
+    fn single_pass_runs_each_constraint_once() {
+        let sys = box_and_halfspace();
+        let out = project_single_pass(&sys, &v(20.0, 5.0));
+        // Clamped into the box first; may still need more sweeps for
+        // the halfspace to be exact, but must not be worse than input.
+        assert!(out.get(0) <= 10.0);
+    }
+
+    #[test]
+    fn distance_bounds_bracket_the_truth() {
+        let sys = box_and_halfspace();
+        // Feasible: both bounds collapse to zero.
+        let b = distance_to_intersection(&sys, &v(2.0, 2.0));
+        assert_eq!((b.lower, b.upper), (0.0, 0.0));
+        // One violated box: the bounds coincide at the exact distance.
+        let b = distance_to_intersection(&sys, &v(5.0, -3.0));
+        assert_eq!(b.lower, 3.0);
+        assert_eq!(b.upper, 3.0);
+        // Corner region: the true distance (to (5,5)) sits inside the
+        // bracket.
+        let b = distance_to_intersection(&sys, &v(12.0, 12.0));
+        let truth = v(12.0, 12.0).distance(&v(5.0, 5.0));
+        assert!(b.lower <= truth + 1e-9);
+        assert!(b.upper >= truth - 1e-9);
+        assert!(b.upper.is_finite());
+    }
+
+    #[test]
+    fn checked_single_pass_restores_validity() {
+        // Halfspace first, box second: the single sweep's box clamp
+        // re-violates the halfspace, so the guard must kick in.
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(HalfspaceConstraint::new(v(1.0, 1.0), 5.0));
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(10.0, 10.0))));
+        let p = v(-10.0, 30.0);
+        let options = ProjectionOptions::default();
+        let fast = project_single_pass(&sys, &p);
+        assert!(sys.margin(&fast) < -1.0);
+        let checked = project_single_pass_checked(&sys, &p, &options);
+        assert!(sys.margin(&checked) >= -1e-5);
+    }
+
+    #[test]
+    fn checked_alternating_keeps_valid_fast_results() {
+        let sys = box_and_halfspace();
+        let options = ProjectionOptions::default();
+        let plain = project_alternating(&sys, &v(12.0, 12.0), &options);
+        let checked = project_alternating_checked(&sys, &v(12.0, 12.0), &options);
+        assert!(checked.converged);
+        assert_eq!(checked.point, plain.point);
+    }
+
+    #[test]
+    fn order_audit_is_quiet_on_convex_systems() {
+        let sys = box_and_halfspace();
+        let audit = audit_projection_order(&sys, &v(12.0, 12.0), &ProjectionOptions::default());
+        assert_eq!(audit.points.len(), 3); // original, reversed, one rotation
+        assert!(audit.max_divergence < 1e-5);
+    }
+
+    #[test]
+    fn order_audit_exposes_order_dependence() {
+        // Box beside an obstacle: whichever constraint projects last
+        // wins, so different orders land on different sides.
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(50.0, 100.0))));
+        sys.add(CollisionConstraint::new(Bounds::new(
+            v(40.0, -100.0),
+            v(120.0, 200.0),
+        )));
+        let audit = audit_projection_order(&sys, &v(200.0, 50.0), &ProjectionOptions::default());
+        assert!(audit.max_divergence > 1.0);
+    }
+
+    #[test]
+    fn alternating_reaches_intersection() {
+        let sys = box_and_halfspace();
+        let r = project_alternating(&sys, &v(12.0, 12.0), &ProjectionOptions::default());
+        assert!(r.converged);
+        assert!(sys.is_feasible(&r.point));
+    }
+}
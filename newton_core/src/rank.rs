@@ -0,0 +1,458 @@
+//! Candidate ranking.
+//!
+//! The suggestion search produces a handful of feasible candidate
+//! points; ranking decides which one the user actually sees. Scores
+//! combine built-in terms (intent fidelity, constraint margin,
+//! stability relative to the previous state) with any caller-provided
+//! [`Scorer`] components. Higher scores are better.
+
+use crate::constraint::ConstraintSystem;
+use crate::linalg::Vector;
+
+/// Score differences below this are numerically meaningless and must
+/// not be used to order candidates.
+pub const SCORE_EPSILON: f64 = 1e-6;
+
+/// True if two scores are indistinguishable at [`SCORE_EPSILON`].
+pub fn scores_equal(a: f64, b: f64) -> bool {
+    scores_equal_within(a, b, SCORE_EPSILON)
+}
+
+/// True if two scores are indistinguishable at a caller-chosen
+/// tolerance.
+pub fn scores_equal_within(a: f64, b: f64, tolerance: f64) -> bool {
+    (a - b).abs() < tolerance
+}
+
+/// Number of deterministic perturbations used by the robustness term
+/// (a ring in the first two dimensions, axis offsets otherwise).
+const ROBUSTNESS_SAMPLES: usize = 8;
+
+/// Everything a scoring component may look at when scoring one
+/// candidate.
+pub struct ScoreContext<'a> {
+    /// The candidate position being scored. Always feasible.
+    pub candidate: &'a Vector,
+    /// Where the raw gesture wanted to go.
+    pub intent: &'a Vector,
+    /// Where the object currently is.
+    pub current: &'a Vector,
+    /// The constraint set the candidate was validated against, when the
+    /// caller has one. Enables margin-aware terms.
+    pub system: Option<&'a ConstraintSystem>,
+}
+
+impl ScoreContext<'_> {
+    /// Slack of the candidate against the constraint set: its minimum
+    /// signed distance over all constraints, clamped at zero (a
+    /// candidate on the boundary has no slack; infeasible candidates
+    /// should have been filtered before ranking). Zero when no system
+    /// is available.
+    pub fn margin(&self) -> f64 {
+        match self.system {
+            Some(sys) if !sys.is_empty() => sys.margin(self.candidate).max(0.0),
+            _ => 0.0,
+        }
+    }
+
+    /// Fraction of deterministic perturbations of the candidate (ring
+    /// of [`ROBUSTNESS_SAMPLES`] offsets at `radius`) that remain
+    /// feasible, in `[0, 1]`. Candidates in the middle of a feasible
+    /// pocket score 1; ones wedged against boundaries lose a share per
+    /// escaping perturbation. 1.0 when no system is available.
+    pub fn robustness(&self, radius: f64) -> f64 {
+        let Some(sys) = self.system else {
+            return 1.0;
+        };
+        let dim = self.candidate.dim();
+        let mut offsets: Vec<Vector> = Vec::new();
+        if dim >= 2 {
+            for k in 0..ROBUSTNESS_SAMPLES {
+                let theta = std::f64::consts::TAU * (k as f64) / (ROBUSTNESS_SAMPLES as f64);
+                let mut p = self.candidate.clone();
+                p.set(0, p.get(0) + radius * theta.cos());
+                p.set(1, p.get(1) + radius * theta.sin());
+                offsets.push(p);
+            }
+        } else {
+            for i in 0..dim {
+                for sign in [-1.0, 1.0] {
+                    let mut p = self.candidate.clone();
+                    p.set(i, p.get(i) + sign * radius);
+                    offsets.push(p);
+                }
+            }
+        }
+        let valid = offsets.iter().filter(|p| sys.is_feasible(p)).count();
+        valid as f64 / offsets.len() as f64
+    }
+}
+
+/// A pluggable scoring component.
+///
+/// Implemented for closures, so ad-hoc terms do not need a named type:
+///
+/// ```
+/// use newton_core::rank::{RankingCriteria, ScoreContext};
+/// let criteria = RankingCriteria::default()
+///     .with_scorer(1.0, |ctx: &ScoreContext| -ctx.candidate.get(1).abs());
+/// ```
+pub trait Scorer: Send + Sync {
+    /// Higher is better. Components are weighted and summed.
+    fn score(&self, ctx: &ScoreContext) -> f64;
+}
+
+impl<F> Scorer for F
+where
+    F: Fn(&ScoreContext) -> f64 + Send + Sync,
+{
+    fn score(&self, ctx: &ScoreContext) -> f64 {
+        self(ctx)
+    }
+}
+
+/// Weights for the built-in scoring terms plus any custom components.
+pub struct RankingCriteria {
+    /// Weight of closeness to the gesture's intended position.
+    pub intent_weight: f64,
+    /// Weight of constraint margin (distance from the nearest
+    /// constraint boundary). Rewards candidates with comfortable slack
+    /// over ones hugging a boundary; contributes zero when the scoring
+    /// context has no constraint system.
+    pub margin_weight: f64,
+    /// Weight of closeness to the current position (damps jitter).
+    pub stability_weight: f64,
+    /// Weight of perturbation robustness (see
+    /// [`ScoreContext::robustness`]). Off by default because it costs
+    /// a feasibility check per perturbation per candidate.
+    pub robustness_weight: f64,
+    /// Perturbation radius used by the robustness term, in
+    /// configuration-space units.
+    pub robustness_radius: f64,
+    scorers: Vec<(f64, Box<dyn Scorer>)>,
+}
+
+impl Default for RankingCriteria {
+    fn default() -> Self {
+        RankingCriteria {
+            intent_weight: 1.0,
+            margin_weight: 0.25,
+            stability_weight: 0.1,
+            robustness_weight: 0.0,
+            robustness_radius: 4.0,
+            scorers: Vec::new(),
+        }
+    }
+}
+
+impl RankingCriteria {
+    /// Adds a weighted custom scoring component.
+    pub fn with_scorer(mut self, weight: f64, scorer: impl Scorer + 'static) -> Self {
+        self.scorers.push((weight, Box::new(scorer)));
+        self
+    }
+
+    /// The custom components added via [`with_scorer`](Self::with_scorer).
+    pub fn scorers(&self) -> impl Iterator<Item = (f64, &dyn Scorer)> {
+        self.scorers.iter().map(|(w, s)| (*w, s.as_ref()))
+    }
+
+    /// Total score of one candidate under these criteria.
+    pub fn score(&self, ctx: &ScoreContext) -> f64 {
+        let intent_term = -self.intent_weight * ctx.candidate.distance(ctx.intent);
+        let stability_term = -self.stability_weight * ctx.candidate.distance(ctx.current);
+        let margin_term = self.margin_weight * ctx.margin();
+        let robustness_term = if self.robustness_weight > 0.0 {
+            self.robustness_weight * ctx.robustness(self.robustness_radius)
+        } else {
+            0.0
+        };
+        let custom: f64 = self
+            .scorers
+            .iter()
+            .map(|(w, s)| w * s.score(ctx))
+            .sum();
+        intent_term + stability_term + margin_term + robustness_term + custom
+    }
+}
+
+/// A candidate with its total score attached.
+#[derive(Debug, Clone)]
+pub struct ScoredCandidate {
+    pub position: Vector,
+    pub score: f64,
+}
+
+/// Scores and sorts candidates, best first. Ties at [`SCORE_EPSILON`]
+/// keep their input order, so deterministic candidate generation gives
+/// deterministic output.
+pub fn rank_candidates(
+    candidates: Vec<Vector>,
+    intent: &Vector,
+    current: &Vector,
+    system: Option<&ConstraintSystem>,
+    criteria: &RankingCriteria,
+) -> Vec<ScoredCandidate> {
+    let mut scored: Vec<ScoredCandidate> = candidates
+        .into_iter()
+        .map(|position| {
+            let score = criteria.score(&ScoreContext {
+                candidate: &position,
+                intent,
+                current,
+                system,
+            });
+            ScoredCandidate { position, score }
+        })
+        .collect();
+    scored.sort_by(|a, b| {
+        if scores_equal(a.score, b.score) {
+            std::cmp::Ordering::Equal
+        } else {
+            b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal)
+        }
+    });
+    scored
+}
+
+/// A run of candidates whose scores are mutually indistinguishable.
+///
+/// All members are within the grouping tolerance of the group's best
+/// score, so presenting them in any order (or as explicit "equally
+/// good" alternatives) is honest.
+#[derive(Debug, Clone)]
+pub struct RankGroup {
+    /// Members in ranked order. Never empty.
+    pub members: Vec<ScoredCandidate>,
+}
+
+impl RankGroup {
+    /// Best (first) score in the group.
+    pub fn score(&self) -> f64 {
+        self.members[0].score
+    }
+}
+
+/// Partitions already-ranked candidates into equivalence classes of
+/// indistinguishable score.
+///
+/// A candidate joins the current group when its score is within
+/// `tolerance` of the group's *best* score (not its neighbour's), so a
+/// chain of tiny steps cannot smear genuinely different scores into one
+/// group. Pass [`SCORE_EPSILON`] for the engine's own notion of a tie.
+pub fn group_by_score(ranked: Vec<ScoredCandidate>, tolerance: f64) -> Vec<RankGroup> {
+    let mut groups: Vec<RankGroup> = Vec::new();
+    for candidate in ranked {
+        match groups.last_mut() {
+            Some(group) if scores_equal_within(group.score(), candidate.score, tolerance) => {
+                group.members.push(candidate);
+            }
+            _ => groups.push(RankGroup {
+                members: vec![candidate],
+            }),
+        }
+    }
+    groups
+}
+
+/// Ranks candidates and groups ties in one call. See [`rank_candidates`]
+/// and [`group_by_score`].
+pub fn rank_candidates_grouped(
+    candidates: Vec<Vector>,
+    intent: &Vector,
+    current: &Vector,
+    system: Option<&ConstraintSystem>,
+    criteria: &RankingCriteria,
+    tolerance: f64,
+) -> Vec<RankGroup> {
+    group_by_score(
+        rank_candidates(candidates, intent, current, system, criteria),
+        tolerance,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    #[test]
+    fn closer_to_intent_ranks_first() {
+        let intent = v(10.0, 0.0);
+        let current = v(0.0, 0.0);
+        let ranked = rank_candidates(
+            vec![v(0.0, 0.0), v(9.0, 0.0), v(5.0, 0.0)],
+            &intent,
+            &current,
+            None,
+            &RankingCriteria::default(),
+        );
+        assert_eq!(ranked[0].position, v(9.0, 0.0));
+        assert_eq!(ranked[2].position, v(0.0, 0.0));
+    }
+
+    #[test]
+    fn custom_scorer_can_override_intent() {
+        let intent = v(10.0, 0.0);
+        let current = v(0.0, 0.0);
+        // Strongly prefer candidates near the origin.
+        let criteria = RankingCriteria::default()
+            .with_scorer(100.0, |ctx: &ScoreContext| -ctx.candidate.norm());
+        let ranked = rank_candidates(
+            vec![v(9.0, 0.0), v(1.0, 0.0)],
+            &intent,
+            &current,
+            None,
+            &criteria,
+        );
+        assert_eq!(ranked[0].position, v(1.0, 0.0));
+    }
+
+    #[test]
+    fn margin_breaks_ties_away_from_boundaries() {
+        use crate::bounds::Bounds;
+        use crate::constraint::BoxConstraint;
+
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(10.0, 10.0))));
+
+        // Both candidates are 5 units from the intent, but one hugs the
+        // box edge (margin 0) while the other keeps 1 unit of slack.
+        let intent = v(5.0, 5.0);
+        let current = v(5.0, 5.0);
+        let hugging = v(0.0, 5.0);
+        let interior = v(2.0, 1.0);
+        assert!((intent.distance(&hugging) - 5.0).abs() < 1e-12);
+        assert!((intent.distance(&interior) - 5.0).abs() < 1e-12);
+
+        let ranked = rank_candidates(
+            vec![hugging.clone(), interior.clone()],
+            &intent,
+            &current,
+            Some(&sys),
+            &RankingCriteria::default(),
+        );
+        assert_eq!(ranked[0].position, interior);
+        assert_eq!(ranked[1].position, hugging);
+    }
+
+    #[test]
+    fn robustness_measures_pocket_depth() {
+        use crate::bounds::Bounds;
+        use crate::constraint::BoxConstraint;
+
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(100.0, 100.0))));
+        let center = v(50.0, 50.0);
+        let corner = v(1.0, 1.0);
+        let mk = |candidate| ScoreContext {
+            candidate,
+            intent: &center,
+            current: &center,
+            system: Some(&sys),
+        };
+        assert_eq!(mk(&center).robustness(4.0), 1.0);
+        assert!(mk(&corner).robustness(4.0) < 1.0);
+    }
+
+    #[test]
+    fn robustness_weight_prefers_pocket_interiors() {
+        use crate::bounds::Bounds;
+        use crate::constraint::BoxConstraint;
+
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(100.0, 100.0))));
+        // Ignore everything but robustness: the corner candidate loses
+        // perturbations off the canvas, the interior one keeps all.
+        let criteria = RankingCriteria {
+            intent_weight: 0.0,
+            margin_weight: 0.0,
+            stability_weight: 0.0,
+            robustness_weight: 1.0,
+            ..RankingCriteria::default()
+        };
+        let ranked = rank_candidates(
+            vec![v(1.0, 1.0), v(50.0, 50.0)],
+            &v(0.0, 0.0),
+            &v(0.0, 0.0),
+            Some(&sys),
+            &criteria,
+        );
+        assert_eq!(ranked[0].position, v(50.0, 50.0));
+    }
+
+    #[test]
+    fn margin_is_zero_without_system() {
+        let intent = v(0.0, 0.0);
+        let ctx = ScoreContext {
+            candidate: &intent,
+            intent: &intent,
+            current: &intent,
+            system: None,
+        };
+        assert_eq!(ctx.margin(), 0.0);
+    }
+
+    #[test]
+    fn scores_equal_tolerance() {
+        assert!(scores_equal(1.0, 1.0 + SCORE_EPSILON / 2.0));
+        assert!(!scores_equal(1.0, 1.0 + SCORE_EPSILON * 2.0));
+    }
+
+    #[test]
+    fn grouping_separates_distinguishable_scores() {
+        let intent = v(0.0, 0.0);
+        let current = v(0.0, 0.0);
+        // Two symmetric ties at distance 1, one clear loser at 5.
+        let groups = rank_candidates_grouped(
+            vec![v(5.0, 0.0), v(1.0, 0.0), v(0.0, 1.0)],
+            &intent,
+            &current,
+            None,
+            &RankingCriteria::default(),
+            SCORE_EPSILON,
+        );
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].members.len(), 2);
+        assert_eq!(groups[1].members.len(), 1);
+        assert_eq!(groups[1].members[0].position, v(5.0, 0.0));
+    }
+
+    #[test]
+    fn grouping_anchors_to_group_leader() {
+        // Scores step down by 0.6 · tolerance each: neighbours are
+        // indistinguishable pairwise, but chaining must not merge the
+        // whole run into one group.
+        let tol = 1.0;
+        let ranked: Vec<ScoredCandidate> = (0..4)
+            .map(|i| ScoredCandidate {
+                position: v(i as f64, 0.0),
+                score: -0.6 * i as f64,
+            })
+            .collect();
+        let groups = group_by_score(ranked, tol);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].members.len(), 2);
+        assert_eq!(groups[1].members.len(), 2);
+    }
+
+    #[test]
+    fn tied_candidates_keep_input_order() {
+        let intent = v(0.0, 0.0);
+        let current = v(0.0, 0.0);
+        // Symmetric candidates: identical scores.
+        let ranked = rank_candidates(
+            vec![v(1.0, 0.0), v(0.0, 1.0), v(-1.0, 0.0)],
+            &intent,
+            &current,
+            None,
+            &RankingCriteria::default(),
+        );
+        assert_eq!(ranked[0].position, v(1.0, 0.0));
+        assert_eq!(ranked[1].position, v(0.0, 1.0));
+        assert_eq!(ranked[2].position, v(-1.0, 0.0));
+    }
+}
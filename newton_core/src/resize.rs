@@ -0,0 +1,260 @@
+//! Resize-aware suggestions.
+//!
+//! A resize gesture manipulates a stacked state of position and size
+//! dimensions, and generic box math is wrong for the size half: drag a
+//! corner past the opposite edge and the "suggested" width goes
+//! negative. This module gives sizes their own semantics — a hard
+//! non-negative floor, optional minimum sizes, an optional aspect
+//! lock — expressed as ordinary constraints appended to the system, so
+//! the projection machinery enforces them jointly with the document's
+//! own constraints instead of a fixup pass fighting the projection.
+//!
+//! The response also says *why* a size differs from the intent
+//! ("size 0 clamped to minimum 24"), in the spirit of
+//! [`crate::analyze`]'s human-readable details, so the editor can
+//! show the reason at the cursor instead of silently snapping.
+//!
+//! The stacked layout is `[position..., size...]`, matching the
+//! per-object stacking used by [`crate::multi`].
+
+use crate::constraint::{ConstraintSystem, HalfspaceConstraint};
+use crate::linalg::Vector;
+use crate::rank::RankingCriteria;
+use crate::suggest::{suggest, SuggestionQuality};
+
+/// Size semantics for a resize gesture.
+#[derive(Debug, Clone)]
+pub struct ResizeSpec {
+    object_dim: usize,
+    /// Per-dimension minimum size; at least zero everywhere.
+    min_size: Vector,
+    /// Locked width/height ratio (`size[0] / size[1]`), if any.
+    aspect: Option<f64>,
+}
+
+impl ResizeSpec {
+    /// Sizes may shrink to zero but never below.
+    pub fn new(object_dim: usize) -> Self {
+        ResizeSpec {
+            object_dim,
+            min_size: Vector::zeros(object_dim),
+            aspect: None,
+        }
+    }
+
+    /// Sets per-dimension minimum sizes. Panics on dimension mismatch
+    /// or a negative minimum.
+    pub fn with_min_size(mut self, min_size: Vector) -> Self {
+        assert_eq!(
+            min_size.dim(),
+            self.object_dim,
+            "minimum size dimension mismatch"
+        );
+        assert!(
+            min_size.as_slice().iter().all(|&m| m >= 0.0),
+            "minimum sizes must be non-negative"
+        );
+        self.min_size = min_size;
+        self
+    }
+
+    /// Locks `size[0] / size[1]` to `ratio`. Panics unless the object
+    /// has at least two dimensions and the ratio is positive and
+    /// finite.
+    pub fn with_aspect(mut self, ratio: f64) -> Self {
+        assert!(
+            self.object_dim >= 2,
+            "aspect lock needs at least two size dimensions"
+        );
+        assert!(
+            ratio.is_finite() && ratio > 0.0,
+            "aspect ratio must be positive and finite"
+        );
+        self.aspect = Some(ratio);
+        self
+    }
+
+    pub fn object_dim(&self) -> usize {
+        self.object_dim
+    }
+
+    pub fn min_size(&self) -> &Vector {
+        &self.min_size
+    }
+
+    pub fn aspect(&self) -> Option<f64> {
+        self.aspect
+    }
+}
+
+/// A suggested resize, split back into its halves.
+#[derive(Debug, Clone)]
+pub struct ResizeResponse {
+    pub position: Vector,
+    pub size: Vector,
+    pub quality: SuggestionQuality,
+    /// Human-readable reasons the result differs from the intent,
+    /// for display at the cursor.
+    pub explanations: Vec<String>,
+}
+
+/// Stacks position and size halves into one state.
+fn stack(position: &Vector, size: &Vector) -> Vector {
+    let mut out = Vec::with_capacity(position.dim() + size.dim());
+    out.extend_from_slice(position.as_slice());
+    out.extend_from_slice(size.as_slice());
+    Vector::new(out)
+}
+
+/// Suggests the nearest valid resize. `system` constrains the stacked
+/// `[position..., size...]` state (use an empty system when only the
+/// spec's own size semantics apply); the spec's minimums and aspect
+/// lock are appended as constraints so the whole state projects
+/// jointly. Panics on dimension mismatches.
+pub fn suggest_resize(
+    system: &ConstraintSystem,
+    current_position: &Vector,
+    current_size: &Vector,
+    intent_position: &Vector,
+    intent_size: &Vector,
+    spec: &ResizeSpec,
+    criteria: &RankingCriteria,
+) -> ResizeResponse {
+    let d = spec.object_dim();
+    assert_eq!(system.dim(), 2 * d, "system must cover position and size");
+    assert_eq!(current_position.dim(), d, "current position dimension");
+    assert_eq!(current_size.dim(), d, "current size dimension");
+    assert_eq!(intent_position.dim(), d, "intent position dimension");
+    assert_eq!(intent_size.dim(), d, "intent size dimension");
+
+    // The spec as constraints over the stacked state.
+    let mut constrained = ConstraintSystem::new(2 * d);
+    constrained.set_search_policy(system.search_policy().clone());
+    constrained.set_numeric_policy(system.numeric_policy().clone());
+    for c in system.constraints() {
+        constrained.add_ref(c.clone());
+    }
+    for i in 0..d {
+        // size[i] >= min  ⇔  -size[i] <= -min.
+        let mut normal = Vector::zeros(2 * d);
+        normal.set(d + i, -1.0);
+        constrained.add(HalfspaceConstraint::new(normal, -spec.min_size().get(i)));
+    }
+    if let Some(ratio) = spec.aspect() {
+        // size[0] - ratio·size[1] = 0, as an opposing halfspace pair.
+        let mut normal = Vector::zeros(2 * d);
+        normal.set(d, 1.0);
+        normal.set(d + 1, -ratio);
+        constrained.add(HalfspaceConstraint::new(normal.clone(), 0.0));
+        constrained.add(HalfspaceConstraint::new(normal.scale(-1.0), 0.0));
+    }
+
+    let response = suggest(
+        &constrained,
+        &stack(current_position, current_size),
+        &stack(intent_position, intent_size),
+        criteria,
+    );
+    let view = response.position.view();
+    let position = view.slice(0..d).to_vector();
+    let size = view.slice(d..2 * d).to_vector();
+
+    let tolerance = constrained.numeric_policy().tolerance().max(1e-6);
+    let mut explanations = Vec::new();
+    for i in 0..d {
+        let min = spec.min_size().get(i);
+        if intent_size.get(i) < min - tolerance && (size.get(i) - min).abs() <= tolerance {
+            explanations.push(format!("size {i} clamped to minimum {min}"));
+        }
+    }
+    if let Some(ratio) = spec.aspect() {
+        if (intent_size.get(0) - ratio * intent_size.get(1)).abs() > tolerance {
+            explanations.push(format!("aspect ratio locked to {ratio}"));
+        }
+    }
+
+    ResizeResponse {
+        position,
+        size,
+        quality: response.quality,
+        explanations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    #[test]
+    fn sizes_never_go_negative() {
+        let spec = ResizeSpec::new(2);
+        let r = suggest_resize(
+            &ConstraintSystem::new(4),
+            &v(10.0, 10.0),
+            &v(50.0, 30.0),
+            &v(10.0, 10.0),
+            &v(-20.0, 30.0), // corner dragged past the opposite edge
+            &spec,
+            &RankingCriteria::default(),
+        );
+        assert!(r.size.get(0) >= -1e-9);
+        assert!((r.size.get(1) - 30.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn minimum_sizes_clamp_and_explain() {
+        let spec = ResizeSpec::new(2).with_min_size(v(24.0, 16.0));
+        let r = suggest_resize(
+            &ConstraintSystem::new(4),
+            &v(0.0, 0.0),
+            &v(50.0, 50.0),
+            &v(0.0, 0.0),
+            &v(10.0, 50.0),
+            &spec,
+            &RankingCriteria::default(),
+        );
+        assert!((r.size.get(0) - 24.0).abs() < 1e-6);
+        assert_eq!(r.explanations, vec!["size 0 clamped to minimum 24"]);
+    }
+
+    #[test]
+    fn aspect_lock_holds_the_ratio() {
+        let spec = ResizeSpec::new(2).with_aspect(2.0);
+        let r = suggest_resize(
+            &ConstraintSystem::new(4),
+            &v(0.0, 0.0),
+            &v(40.0, 20.0),
+            &v(0.0, 0.0),
+            &v(60.0, 20.0), // widens without matching height
+            &spec,
+            &RankingCriteria::default(),
+        );
+        assert!((r.size.get(0) - 2.0 * r.size.get(1)).abs() < 1e-4);
+        assert!(r
+            .explanations
+            .iter()
+            .any(|e| e.contains("aspect ratio locked")));
+    }
+
+    #[test]
+    fn compatible_resizes_pass_through_silently() {
+        let spec = ResizeSpec::new(2).with_min_size(v(10.0, 10.0));
+        let r = suggest_resize(
+            &ConstraintSystem::new(4),
+            &v(5.0, 5.0),
+            &v(40.0, 40.0),
+            &v(8.0, 5.0),
+            &v(50.0, 45.0),
+            &spec,
+            &RankingCriteria::default(),
+        );
+        assert_eq!(r.quality, SuggestionQuality::Exact);
+        assert_eq!(r.position, v(8.0, 5.0));
+        assert_eq!(r.size, v(50.0, 45.0));
+        assert!(r.explanations.is_empty());
+    }
+}
@@ -0,0 +1,165 @@
+//! Deterministic pseudo-randomness for stochastic features.
+//!
+//! The engine's determinism contract — same inputs, same answer —
+//! extends to anything randomized: jittered candidate sampling,
+//! annealing schedules, robustness probing. Those features must draw
+//! from an explicitly seeded stream, never from ambient entropy, or a
+//! captured scenario stops reproducing the bug it was captured for.
+//! This module is the one sanctioned source: a counter-based generator
+//! (the SplitMix64 mixing function over an affine counter), so the
+//! `n`-th draw is a pure function of `(seed, n)`. That buys O(1)
+//! [`seek`](CounterRng::seek) for replaying from the middle of a log
+//! and cheap independent [`stream`](CounterRng::stream)s for parallel
+//! candidate evaluation, neither of which a stateful generator gives.
+
+/// Weyl-sequence increment (the 64-bit golden ratio), the standard
+/// SplitMix64 counter step.
+const GOLDEN: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// The SplitMix64 finalizer: a bijective avalanche mix of one word.
+/// Exposed because hashing-adjacent callers (stream derivation, seed
+/// whitening) want exactly this function.
+pub fn mix64(mut x: u64) -> u64 {
+    x ^= x >> 30;
+    x = x.wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x ^= x >> 27;
+    x = x.wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+/// Counter-based deterministic generator: draw `n` is
+/// `mix64(seed + (n + 1) · GOLDEN)`, nothing more. Equal seeds give
+/// equal sequences on every platform and toolchain forever; that is a
+/// compatibility promise, like the fingerprint format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CounterRng {
+    seed: u64,
+    counter: u64,
+}
+
+impl CounterRng {
+    /// A stream at draw zero. Seeds need no quality: `0` and `1` give
+    /// unrelated sequences, courtesy of the mixer.
+    pub fn new(seed: u64) -> Self {
+        CounterRng { seed, counter: 0 }
+    }
+
+    /// An independent substream, e.g. one per candidate or per worker.
+    /// Derived from the parent's seed and the stream id only, so the
+    /// substream layout does not depend on how much the parent has
+    /// drawn.
+    pub fn stream(&self, id: u64) -> CounterRng {
+        CounterRng::new(mix64(self.seed ^ mix64(id)))
+    }
+
+    /// How many draws have been made.
+    pub fn index(&self) -> u64 {
+        self.counter
+    }
+
+    /// Jumps directly to draw `index` — replaying from the middle of a
+    /// log costs the same as from the start.
+    pub fn seek(&mut self, index: u64) {
+        self.counter = index;
+    }
+
+    /// The next raw word.
+    pub fn next_u64(&mut self) -> u64 {
+        self.counter += 1;
+        mix64(self.seed.wrapping_add(self.counter.wrapping_mul(GOLDEN)))
+    }
+
+    /// The next draw in `[0, 1)`, using the top 53 bits so every value
+    /// is exactly representable.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// The next draw in `[lo, hi)`. Panics on an inverted or non-finite
+    /// interval.
+    pub fn range(&mut self, lo: f64, hi: f64) -> f64 {
+        assert!(
+            lo.is_finite() && hi.is_finite() && lo < hi,
+            "range requires a finite non-empty interval"
+        );
+        lo + (hi - lo) * self.next_f64()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equal_seeds_replay_exactly() {
+        let mut a = CounterRng::new(42);
+        let mut b = CounterRng::new(42);
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+        let mut c = CounterRng::new(43);
+        assert_ne!(a.next_u64(), c.next_u64());
+    }
+
+    #[test]
+    fn seeking_matches_sequential_draws() {
+        let mut sequential = CounterRng::new(7);
+        let draws: Vec<u64> = (0..32).map(|_| sequential.next_u64()).collect();
+        let mut seeker = CounterRng::new(7);
+        seeker.seek(19);
+        assert_eq!(seeker.next_u64(), draws[19]);
+        assert_eq!(seeker.index(), 20);
+        assert_eq!(seeker.next_u64(), draws[20]);
+    }
+
+    #[test]
+    fn streams_are_independent_of_parent_position() {
+        let parent = CounterRng::new(7);
+        let mut advanced = CounterRng::new(7);
+        for _ in 0..100 {
+            advanced.next_u64();
+        }
+        // The substream layout only depends on seed and id.
+        assert_eq!(parent.stream(3), advanced.stream(3));
+        assert_ne!(parent.stream(3), parent.stream(4));
+    }
+
+    #[test]
+    fn floats_land_in_the_unit_interval_and_look_uniform() {
+        let mut rng = CounterRng::new(0);
+        let mut sum = 0.0;
+        let n = 10_000;
+        for _ in 0..n {
+            let x = rng.next_f64();
+            assert!((0.0..1.0).contains(&x));
+            sum += x;
+        }
+        let mean = sum / n as f64;
+        assert!((mean - 0.5).abs() < 0.02, "mean {mean} is not near 0.5");
+    }
+
+    #[test]
+    fn range_spans_the_interval() {
+        let mut rng = CounterRng::new(11);
+        for _ in 0..1000 {
+            let x = rng.range(-3.0, 5.0);
+            assert!((-3.0..5.0).contains(&x));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "range requires a finite non-empty interval")]
+    fn inverted_ranges_are_rejected() {
+        CounterRng::new(0).range(5.0, -3.0);
+    }
+
+    #[test]
+    fn known_answer_is_pinned() {
+        // The sequence is a compatibility promise: replay logs encode
+        // only the seed, so these values must never change.
+        let mut rng = CounterRng::new(0);
+        assert_eq!(rng.next_u64(), mix64(GOLDEN));
+        assert_eq!(mix64(0), 0);
+        assert_eq!(mix64(1), 0x5692_161D_100B_05E5);
+    }
+}
@@ -0,0 +1,198 @@
+//! NaN/∞ screening for untrusted input.
+//!
+//! Corrupted input devices and buggy host bindings occasionally
+//! deliver states or deltas containing NaN or ±∞. Left alone, those
+//! values flow into projections, poison every arithmetic result they
+//! touch, and surface much later as a cursor stuck at `[NaN, NaN]`
+//! with no trail back to the bad event. [`Vector::has_nan`] and
+//! [`Vector::is_finite`] can detect the corruption, but detection only
+//! helps if something at the front door actually consults them.
+//!
+//! [`InputSanitizer`] is that front door. Hosts feed every incoming
+//! state and delta through it before the engine sees them, and choose
+//! per-pipeline what a non-finite value means:
+//!
+//! - [`SanitizePolicy::Reject`] — surface
+//!   [`NewtonError::NonFiniteInput`] and let the host drop or log the
+//!   event. Right for document data, where corruption is a bug worth
+//!   hearing about.
+//! - [`SanitizePolicy::Repair`] — deterministically replace each bad
+//!   component: states fall back to the last admitted good state
+//!   (component-wise), deltas to zero movement. Right for live input
+//!   streams, where dropping a frame feels worse than holding still.
+//!
+//! Repair is component-wise and history-free beyond the last good
+//! state, so replaying the same event stream always reproduces the
+//! same positions.
+
+use crate::delta::Delta;
+use crate::error::NewtonError;
+use crate::linalg::Vector;
+
+/// What to do with an incoming value containing NaN or ±∞.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SanitizePolicy {
+    /// Refuse the value with [`NewtonError::NonFiniteInput`].
+    #[default]
+    Reject,
+    /// Replace bad components deterministically: states take the last
+    /// good state's component, deltas take zero.
+    Repair,
+}
+
+/// Screens incoming states and deltas for non-finite values.
+///
+/// One sanitizer guards one input pipeline; it remembers the last
+/// admitted state so [`SanitizePolicy::Repair`] has something sensible
+/// to fall back to.
+#[derive(Debug, Clone)]
+pub struct InputSanitizer {
+    policy: SanitizePolicy,
+    dim: usize,
+    last_good: Vector,
+}
+
+impl InputSanitizer {
+    /// A sanitizer for `dim`-dimensional input under `policy`. Until a
+    /// state is admitted, repairs fall back to the origin.
+    pub fn new(dim: usize, policy: SanitizePolicy) -> Self {
+        InputSanitizer {
+            policy,
+            dim,
+            last_good: Vector::zeros(dim),
+        }
+    }
+
+    /// The active policy.
+    pub fn policy(&self) -> SanitizePolicy {
+        self.policy
+    }
+
+    /// Screens an incoming state. Finite states pass through untouched
+    /// and become the new repair fallback. Panics if the state's
+    /// dimension disagrees with the sanitizer's.
+    pub fn admit_state(&mut self, state: &Vector) -> Result<Vector, NewtonError> {
+        assert_eq!(state.dim(), self.dim, "dimension mismatch in admit_state");
+        if state.is_finite() {
+            self.last_good = state.clone();
+            return Ok(state.clone());
+        }
+        match self.policy {
+            SanitizePolicy::Reject => Err(NewtonError::NonFiniteInput),
+            SanitizePolicy::Repair => {
+                let repaired = Vector::new(
+                    (0..self.dim)
+                        .map(|i| {
+                            let x = state.get(i);
+                            if x.is_finite() {
+                                x
+                            } else {
+                                self.last_good.get(i)
+                            }
+                        })
+                        .collect(),
+                );
+                self.last_good = repaired.clone();
+                Ok(repaired)
+            }
+        }
+    }
+
+    /// Screens an incoming delta. Bad components repair to zero — no
+    /// movement — preserving source and timestamp. Panics if the
+    /// delta's dimension disagrees with the sanitizer's.
+    pub fn admit_delta(&self, delta: &Delta) -> Result<Delta, NewtonError> {
+        assert_eq!(
+            delta.change.dim(),
+            self.dim,
+            "dimension mismatch in admit_delta"
+        );
+        if delta.change.is_finite() {
+            return Ok(delta.clone());
+        }
+        match self.policy {
+            SanitizePolicy::Reject => Err(NewtonError::NonFiniteInput),
+            SanitizePolicy::Repair => {
+                let change = Vector::new(
+                    delta
+                        .change
+                        .as_slice()
+                        .iter()
+                        .map(|&x| if x.is_finite() { x } else { 0.0 })
+                        .collect(),
+                );
+                Ok(Delta::with_timestamp(
+                    change,
+                    delta.source,
+                    delta.timestamp_us,
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::delta::DeltaSource;
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    #[test]
+    fn finite_input_passes_through() {
+        let mut s = InputSanitizer::new(2, SanitizePolicy::Reject);
+        assert_eq!(s.admit_state(&v(3.0, 4.0)), Ok(v(3.0, 4.0)));
+        let d = Delta::with_timestamp(v(1.0, -1.0), DeltaSource::Pointer, 5);
+        assert_eq!(s.admit_delta(&d), Ok(d));
+    }
+
+    #[test]
+    fn reject_surfaces_a_typed_error() {
+        let mut s = InputSanitizer::new(2, SanitizePolicy::Reject);
+        assert_eq!(
+            s.admit_state(&v(f64::NAN, 4.0)),
+            Err(NewtonError::NonFiniteInput)
+        );
+        let d = Delta::with_timestamp(v(f64::INFINITY, 0.0), DeltaSource::Pointer, 5);
+        assert_eq!(s.admit_delta(&d), Err(NewtonError::NonFiniteInput));
+    }
+
+    #[test]
+    fn repair_falls_back_to_the_last_good_state() {
+        let mut s = InputSanitizer::new(2, SanitizePolicy::Repair);
+        s.admit_state(&v(3.0, 4.0)).unwrap();
+        // Only the corrupted component is replaced.
+        assert_eq!(s.admit_state(&v(f64::NAN, 7.0)), Ok(v(3.0, 7.0)));
+        // The repaired state becomes the new fallback.
+        assert_eq!(
+            s.admit_state(&v(f64::INFINITY, f64::NEG_INFINITY)),
+            Ok(v(3.0, 7.0))
+        );
+    }
+
+    #[test]
+    fn repair_zeroes_bad_delta_components() {
+        let s = InputSanitizer::new(2, SanitizePolicy::Repair);
+        let d = Delta::with_timestamp(v(f64::NAN, 2.0), DeltaSource::Keyboard, 9);
+        let repaired = s.admit_delta(&d).unwrap();
+        assert_eq!(repaired.change, v(0.0, 2.0));
+        assert_eq!(repaired.source, DeltaSource::Keyboard);
+        assert_eq!(repaired.timestamp_us, 9);
+    }
+
+    #[test]
+    fn repairs_before_any_good_state_use_the_origin() {
+        let mut s = InputSanitizer::new(2, SanitizePolicy::Repair);
+        assert_eq!(s.admit_state(&v(f64::NAN, f64::NAN)), Ok(v(0.0, 0.0)));
+    }
+
+    #[test]
+    fn detection_helpers_agree_on_what_is_bad() {
+        assert!(v(f64::NAN, 0.0).has_nan());
+        assert!(!v(f64::INFINITY, 0.0).has_nan());
+        assert!(!v(f64::INFINITY, 0.0).is_finite());
+        assert!(v(1.0, 2.0).is_finite());
+    }
+}
@@ -0,0 +1,457 @@
+//! Captured-document scenarios for benchmarks and tests.
+//!
+//! Performance work and bug fixes should be judged against the same
+//! inputs: a constraint system captured from a real document plus the
+//! replay log of the gesture that exposed the behaviour. This module
+//! defines the persisted text format and its loader, so a capture
+//! checked into the repository feeds both `cargo bench` and
+//! regression tests unchanged.
+//!
+//! The format is line-oriented and human-editable. Vectors use the
+//! canonical [`Display`](crate::linalg::Vector) form, so values
+//! round-trip bit-exactly:
+//!
+//! ```text
+//! # captured 2026-05-14, issue #812
+//! version 2
+//! dim 2
+//! box [0.0, 0.0] [1000.0, 1000.0]
+//! obstacle [40.0, 40.0] [60.0, 60.0] 2.0
+//! halfspace [1.0, 0.0] 50.0
+//! discrete [10.0, 10.0] [20.0, 20.0]
+//! lattice 0.25
+//! event [50.0, 50.0] [120.0, 50.0]
+//! ```
+//!
+//! The optional `version` header must come before anything else and
+//! names the format revision the file was written with ([`FORMAT_VERSION`]
+//! is current; files without the header are version 1). Directives
+//! from older revisions are migrated on load, so captures checked in
+//! under an old crate keep loading after the format moves on. `dim`
+//! must come next; `event` lines form the replay log in order; `#`
+//! starts a comment.
+
+use crate::bounds::Bounds;
+use crate::constraint::{
+    BoxConstraint, CollisionConstraint, ConstraintSystem, DiscreteConstraint, HalfspaceConstraint,
+    LatticeConstraint,
+};
+use crate::linalg::Vector;
+
+/// Current revision of the persisted format, written by
+/// [`Scenario::to_text`]. Files without a `version` header are
+/// revision 1, the format before versioning shipped. Bump this and
+/// teach [`migrate_args`] the step whenever a directive's shape
+/// changes, and old captures keep loading.
+///
+/// Revision history:
+///
+/// * 1 — original format.
+/// * 2 — `obstacle` gained a trailing margin argument.
+pub const FORMAT_VERSION: u32 = 2;
+
+/// One replayed input event: where the object was and where the
+/// gesture wanted it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayEvent {
+    pub current: Vector,
+    pub intent: Vector,
+}
+
+/// A captured constraint system plus its replay log.
+pub struct Scenario {
+    pub system: ConstraintSystem,
+    pub events: Vec<ReplayEvent>,
+}
+
+/// Why a scenario failed to load. Line numbers are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScenarioError {
+    /// The `dim` header is missing or not the first directive.
+    MissingDim,
+    /// The line's directive is not recognised.
+    UnknownDirective(usize),
+    /// The file's `version` header names a revision newer than this
+    /// crate's [`FORMAT_VERSION`]; migration only runs forward.
+    UnsupportedVersion(u32),
+    /// The line's arguments are malformed for its directive.
+    BadArguments(usize),
+    /// Writing: the system contains a constraint type the text format
+    /// cannot express.
+    UnsupportedConstraint,
+    /// The file could not be read.
+    Io(String),
+}
+
+/// Rewrites the arguments of a directive parsed from a `from_version`
+/// file into their current shape, one revision step at a time, so each
+/// format bump only has to describe its own change. This is the
+/// migration hook: when [`FORMAT_VERSION`] moves, add the step here
+/// and every older capture keeps loading with the old defaults filled
+/// in.
+fn migrate_args(from_version: u32, directive: &str, mut args: Vec<String>) -> Vec<String> {
+    for step in from_version..FORMAT_VERSION {
+        #[allow(clippy::single_match)]
+        match (step, directive) {
+            // 1 → 2: `obstacle` gained a trailing margin; old lines
+            // mean a margin of zero.
+            (1, "obstacle") => args.push("0.0".to_string()),
+            _ => {}
+        }
+    }
+    args
+}
+
+/// Builds [`Bounds`] for a `dim`-dimensional system from parsed corner
+/// vectors, turning the shape errors [`Bounds::new`] would panic on —
+/// corners of the wrong dimension, an axis where min exceeds max —
+/// into [`ScenarioError::BadArguments`]. The comparison is written so
+/// a NaN corner fails it too.
+fn checked_bounds(
+    min: Vector,
+    max: Vector,
+    dim: usize,
+    line_no: usize,
+) -> Result<Bounds, ScenarioError> {
+    if min.dim() != dim || max.dim() != dim {
+        return Err(ScenarioError::BadArguments(line_no));
+    }
+    let inverted = |i: usize| {
+        min.get(i)
+            .partial_cmp(&max.get(i))
+            .is_none_or(|o| o == std::cmp::Ordering::Greater)
+    };
+    if (0..dim).any(inverted) {
+        return Err(ScenarioError::BadArguments(line_no));
+    }
+    Ok(Bounds::new(min, max))
+}
+
+/// Parses a scenario from its persisted text, migrating directives
+/// written by older format revisions as it goes.
+pub fn parse_scenario(text: &str) -> Result<Scenario, ScenarioError> {
+    let mut system: Option<ConstraintSystem> = None;
+    let mut version: Option<u32> = None;
+    let mut events = Vec::new();
+    for (index, raw) in text.lines().enumerate() {
+        let line_no = index + 1;
+        let line = raw.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (directive, rest) = match line.split_once(char::is_whitespace) {
+            Some((d, r)) => (d, r.trim()),
+            None => (line, ""),
+        };
+        if directive == "version" {
+            // The header must precede everything, `dim` included, so
+            // migration is settled before the first constraint.
+            if version.is_some() || system.is_some() {
+                return Err(ScenarioError::BadArguments(line_no));
+            }
+            let v: u32 = rest.parse().map_err(|_| ScenarioError::BadArguments(line_no))?;
+            if v == 0 {
+                return Err(ScenarioError::BadArguments(line_no));
+            }
+            if v > FORMAT_VERSION {
+                return Err(ScenarioError::UnsupportedVersion(v));
+            }
+            version = Some(v);
+            continue;
+        }
+        if directive == "dim" {
+            if system.is_some() {
+                return Err(ScenarioError::BadArguments(line_no));
+            }
+            let dim: usize = rest.parse().map_err(|_| ScenarioError::BadArguments(line_no))?;
+            system = Some(ConstraintSystem::new(dim));
+            continue;
+        }
+        let Some(sys) = system.as_mut() else {
+            return Err(ScenarioError::MissingDim);
+        };
+        let args = migrate_args(version.unwrap_or(1), directive, split_args(rest));
+        let bad = || ScenarioError::BadArguments(line_no);
+        match directive {
+            "box" => {
+                let [min, max] = two_vectors(&args).ok_or_else(bad)?;
+                let bounds = checked_bounds(min, max, sys.dim(), line_no)?;
+                sys.add(BoxConstraint::try_new(bounds).map_err(|_| bad())?);
+            }
+            "obstacle" => {
+                if args.len() != 3 {
+                    return Err(bad());
+                }
+                let min: Vector = args[0].parse().map_err(|_| bad())?;
+                let max: Vector = args[1].parse().map_err(|_| bad())?;
+                let margin: f64 = args[2].parse().map_err(|_| bad())?;
+                let bounds = checked_bounds(min, max, sys.dim(), line_no)?;
+                sys.add(CollisionConstraint::try_with_margin(bounds, margin).map_err(|_| bad())?);
+            }
+            "halfspace" => {
+                if args.len() != 2 {
+                    return Err(bad());
+                }
+                let normal: Vector = args[0].parse().map_err(|_| bad())?;
+                let offset: f64 = args[1].parse().map_err(|_| bad())?;
+                if normal.dim() != sys.dim() {
+                    return Err(bad());
+                }
+                sys.add(HalfspaceConstraint::try_new(normal, offset).map_err(|_| bad())?);
+            }
+            "discrete" => {
+                let points: Result<Vec<Vector>, _> = args.iter().map(|a| a.parse()).collect();
+                let points = points.map_err(|_| bad())?;
+                if points.iter().any(|p| p.dim() != sys.dim()) {
+                    return Err(bad());
+                }
+                sys.add(DiscreteConstraint::try_new(points).map_err(|_| bad())?);
+            }
+            "lattice" => {
+                if args.len() != 1 {
+                    return Err(bad());
+                }
+                let step: f64 = args[0].parse().map_err(|_| bad())?;
+                sys.add(LatticeConstraint::try_new(sys.dim(), step).map_err(|_| bad())?);
+            }
+            "event" => {
+                let [current, intent] = two_vectors(&args).ok_or_else(bad)?;
+                events.push(ReplayEvent { current, intent });
+            }
+            _ => return Err(ScenarioError::UnknownDirective(line_no)),
+        }
+    }
+    let system = system.ok_or(ScenarioError::MissingDim)?;
+    Ok(Scenario { system, events })
+}
+
+/// Loads a scenario from a file in the persisted format.
+pub fn load_scenario(path: impl AsRef<std::path::Path>) -> Result<Scenario, ScenarioError> {
+    let text =
+        std::fs::read_to_string(path).map_err(|e| ScenarioError::Io(e.to_string()))?;
+    parse_scenario(&text)
+}
+
+impl Scenario {
+    /// Serialises to the current revision of the persisted text format
+    /// ([`FORMAT_VERSION`]). Fails with
+    /// [`ScenarioError::UnsupportedConstraint`] when the system holds a
+    /// constraint type the format cannot express.
+    pub fn to_text(&self) -> Result<String, ScenarioError> {
+        use std::fmt::Write;
+        let mut out = String::new();
+        let _ = writeln!(out, "version {FORMAT_VERSION}");
+        let _ = writeln!(out, "dim {}", self.system.dim());
+        for c in self.system.constraints() {
+            let any = c.as_any();
+            if let Some(b) = any.downcast_ref::<BoxConstraint>() {
+                let _ = writeln!(out, "box {} {}", b.bounds().min(), b.bounds().max());
+            } else if let Some(o) = any.downcast_ref::<CollisionConstraint>() {
+                let _ = writeln!(
+                    out,
+                    "obstacle {} {} {:?}",
+                    o.obstacle().min(),
+                    o.obstacle().max(),
+                    o.margin()
+                );
+            } else if let Some(h) = any.downcast_ref::<HalfspaceConstraint>() {
+                let _ = writeln!(out, "halfspace {} {:?}", h.normal(), h.offset());
+            } else if let Some(d) = any.downcast_ref::<DiscreteConstraint>() {
+                let _ = write!(out, "discrete");
+                for p in d.points() {
+                    let _ = write!(out, " {p}");
+                }
+                let _ = writeln!(out);
+            } else if let Some(l) = any.downcast_ref::<LatticeConstraint>() {
+                let _ = writeln!(out, "lattice {:?}", l.step());
+            } else {
+                return Err(ScenarioError::UnsupportedConstraint);
+            }
+        }
+        for e in &self.events {
+            let _ = writeln!(out, "event {} {}", e.current, e.intent);
+        }
+        Ok(out)
+    }
+}
+
+/// Splits a directive's argument list on whitespace outside brackets,
+/// so `[1.0, 2.0] [3.0, 4.0]` yields two arguments. Shared with the
+/// service protocol ([`crate::service`]), which reuses this argument
+/// syntax.
+pub(crate) fn split_args(rest: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut depth = 0usize;
+    let mut arg = String::new();
+    for ch in rest.chars() {
+        match ch {
+            '[' => {
+                depth += 1;
+                arg.push(ch);
+            }
+            ']' => {
+                depth = depth.saturating_sub(1);
+                arg.push(ch);
+            }
+            c if c.is_whitespace() && depth == 0 => {
+                if !arg.is_empty() {
+                    args.push(std::mem::take(&mut arg));
+                }
+            }
+            c => arg.push(c),
+        }
+    }
+    if !arg.is_empty() {
+        args.push(arg);
+    }
+    args
+}
+
+pub(crate) fn two_vectors(args: &[String]) -> Option<[Vector; 2]> {
+    if args.len() != 2 {
+        return None;
+    }
+    Some([args[0].parse().ok()?, args[1].parse().ok()?])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+# captured sample
+dim 2
+box [0.0, 0.0] [100.0, 100.0]
+obstacle [40.0, 40.0] [60.0, 60.0]
+halfspace [1.0, 0.0] 90.0
+discrete [10.0, 10.0] [20.0, 20.0]
+lattice 0.5
+event [50.0, 50.0] [120.0, 50.0]  # drag off the right edge
+event [50.0, 50.0] [50.0, 50.0]
+";
+
+    #[test]
+    fn parses_the_documented_format() {
+        let scenario = parse_scenario(SAMPLE).unwrap();
+        assert_eq!(scenario.system.dim(), 2);
+        assert_eq!(scenario.system.constraints().len(), 5);
+        assert_eq!(scenario.events.len(), 2);
+        assert_eq!(scenario.events[0].intent, Vector::new(vec![120.0, 50.0]));
+    }
+
+    #[test]
+    fn round_trips_through_text() {
+        let scenario = parse_scenario(SAMPLE).unwrap();
+        let text = scenario.to_text().unwrap();
+        let again = parse_scenario(&text).unwrap();
+        assert_eq!(again.system.constraints().len(), 5);
+        assert_eq!(again.events, scenario.events);
+        // Structural fingerprints certify the systems are bit-identical.
+        assert_eq!(
+            crate::fingerprint::fingerprint_system(&again.system),
+            crate::fingerprint::fingerprint_system(&scenario.system)
+        );
+    }
+
+    #[test]
+    fn replayed_events_drive_the_engine() {
+        let scenario = parse_scenario(SAMPLE).unwrap();
+        let criteria = crate::rank::RankingCriteria::default();
+        for e in &scenario.events {
+            let r = crate::suggest::suggest(&scenario.system, &e.current, &e.intent, &criteria);
+            assert!(scenario.system.is_feasible(&r.position));
+        }
+    }
+
+    #[test]
+    fn version_1_documents_migrate_cleanly() {
+        // SAMPLE has no version header: revision 1. Its obstacle line
+        // predates margins and must load with a margin of zero.
+        let scenario = parse_scenario(SAMPLE).unwrap();
+        let o = scenario.system.constraints()[1]
+            .as_any()
+            .downcast_ref::<CollisionConstraint>()
+            .unwrap();
+        assert_eq!(o.margin(), 0.0);
+        // Re-saving writes the current revision.
+        assert!(scenario.to_text().unwrap().starts_with("version 2\n"));
+    }
+
+    #[test]
+    fn margins_survive_the_current_revision() {
+        let mut system = ConstraintSystem::new(2);
+        system.add(CollisionConstraint::with_margin(
+            Bounds::new(Vector::new(vec![40.0, 40.0]), Vector::new(vec![60.0, 60.0])),
+            2.5,
+        ));
+        let scenario = Scenario { system, events: Vec::new() };
+        let again = parse_scenario(&scenario.to_text().unwrap()).unwrap();
+        assert_eq!(
+            crate::fingerprint::fingerprint_system(&again.system),
+            crate::fingerprint::fingerprint_system(&scenario.system)
+        );
+    }
+
+    #[test]
+    fn future_revisions_are_refused_not_guessed() {
+        assert_eq!(
+            parse_scenario("version 99\ndim 2").err(),
+            Some(ScenarioError::UnsupportedVersion(99))
+        );
+        // The header comes first, exactly once, and revision 0 does
+        // not exist.
+        assert_eq!(
+            parse_scenario("dim 2\nversion 2").err(),
+            Some(ScenarioError::BadArguments(2))
+        );
+        assert_eq!(
+            parse_scenario("version 0\ndim 2").err(),
+            Some(ScenarioError::BadArguments(1))
+        );
+    }
+
+    #[test]
+    fn malformed_input_reports_the_line() {
+        assert_eq!(
+            parse_scenario("box [0.0] [1.0]").err(),
+            Some(ScenarioError::MissingDim)
+        );
+        assert_eq!(
+            parse_scenario("dim 2\nwall [0.0, 0.0]").err(),
+            Some(ScenarioError::UnknownDirective(2))
+        );
+        assert_eq!(
+            parse_scenario("dim 2\nhalfspace [1.0, 0.0]").err(),
+            Some(ScenarioError::BadArguments(2))
+        );
+    }
+
+    #[test]
+    fn corrupt_values_are_errors_not_panics() {
+        // Well-formed lines whose values no constraint can accept:
+        // each must report its line, not die in a constructor assert.
+        for text in [
+            "dim 2\nbox [1.0, 1.0] [0.0, 0.0]",        // inverted corners
+            "dim 2\nbox [0.0] [1.0]",                  // corner dimension mismatch
+            "dim 2\nbox [0.0, 0.0] [1.0]",             // corners disagree with dim
+            "dim 2\nobstacle [5.0, 5.0] [1.0, 1.0] 0.0",
+            "dim 2\nhalfspace [0.0, 0.0] 5.0",         // zero normal
+            "dim 2\nhalfspace [1.0] 5.0",              // normal dimension mismatch
+            "dim 2\ndiscrete [1.0, 1.0] [2.0]",        // point dimension mismatch
+            "dim 2\nlattice 0.0",                      // non-positive step
+            "dim 2\nlattice -0.5",
+            "dim 2\nbox [0.0, 0.0] [inf, 1.0]",        // non-finite corner
+            "dim 2\nobstacle [0.0, 0.0] [1.0, 1.0] inf",
+            "dim 2\nobstacle [0.0, 0.0] [1.0, 1.0] -1.0",
+            "dim 2\nhalfspace [1.0, inf] 5.0",
+            "dim 2\ndiscrete",                         // empty set
+            "dim 2\nlattice inf",
+        ] {
+            assert_eq!(
+                parse_scenario(text).err(),
+                Some(ScenarioError::BadArguments(2)),
+                "for {text:?}"
+            );
+        }
+    }
+}
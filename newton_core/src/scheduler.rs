@@ -0,0 +1,242 @@
+//! Frame-budget scheduling of suggestion work.
+//!
+//! A large scene can have far more pending suggestion requests than one
+//! frame can afford: every object touched by a multi-select drag, plus
+//! background revalidation, all competing for the same few
+//! milliseconds. [`Scheduler`] queues requests per object, orders them
+//! by priority, and runs each frame against an explicit *work budget*
+//! measured in projection sweeps rather than wall-clock time — the
+//! same input sequence therefore schedules identically on every device,
+//! which wall-clock deadlines cannot guarantee.
+//!
+//! Requests are either completed within a frame or carried over whole
+//! to the next one; a search is never left half-finished, so carry-over
+//! needs no hidden iterator state and stays deterministic. Re-enqueuing
+//! an object preempts its pending request: only the newest intent per
+//! object is ever solved.
+
+use crate::constraint::ConstraintSystem;
+use crate::linalg::Vector;
+use crate::object::ObjectId;
+use crate::rank::RankingCriteria;
+use crate::suggest::{suggest, SuggestResponse};
+
+/// Default per-frame budget in projection sweeps. Generous enough for
+/// dozens of easy requests, small enough to bound a frame.
+pub const DEFAULT_FRAME_BUDGET: usize = 4096;
+
+/// A queued suggestion request for one object.
+struct PendingRequest {
+    object: ObjectId,
+    current: Vector,
+    intent: Vector,
+    criteria: RankingCriteria,
+    priority: i32,
+    /// Submission tick, for stable ordering within a priority.
+    sequence: u64,
+}
+
+/// One completed request from a frame.
+pub struct ScheduledResult {
+    pub object: ObjectId,
+    pub response: SuggestResponse,
+}
+
+/// What a frame actually did, for instrumentation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FrameReport {
+    /// Requests completed this frame.
+    pub completed: usize,
+    /// Requests still queued for the next frame.
+    pub carried_over: usize,
+    /// Projection sweeps spent this frame.
+    pub work_spent: usize,
+}
+
+/// Priority queue of suggestion requests with a per-frame work budget.
+pub struct Scheduler {
+    pending: Vec<PendingRequest>,
+    budget: usize,
+    next_sequence: u64,
+}
+
+impl Scheduler {
+    /// A scheduler with [`DEFAULT_FRAME_BUDGET`].
+    pub fn new() -> Self {
+        Scheduler::with_budget(DEFAULT_FRAME_BUDGET)
+    }
+
+    /// A scheduler spending at most `budget` projection sweeps per
+    /// frame. Panics on a zero budget, which could never finish any
+    /// request.
+    pub fn with_budget(budget: usize) -> Self {
+        assert!(budget > 0, "frame budget must be positive");
+        Scheduler {
+            pending: Vec::new(),
+            budget,
+            next_sequence: 0,
+        }
+    }
+
+    /// Queues a request. A pending request for the same object is
+    /// preempted (replaced), so only the newest intent per object is
+    /// solved. Higher `priority` runs first; ties run in submission
+    /// order.
+    pub fn enqueue(
+        &mut self,
+        object: ObjectId,
+        current: Vector,
+        intent: Vector,
+        criteria: RankingCriteria,
+        priority: i32,
+    ) {
+        self.pending.retain(|r| r.object != object);
+        self.pending.push(PendingRequest {
+            object,
+            current,
+            intent,
+            criteria,
+            priority,
+            sequence: self.next_sequence,
+        });
+        self.next_sequence += 1;
+    }
+
+    /// Pending request count.
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Drops the pending request for `object`, if any. Returns whether
+    /// one was dropped.
+    pub fn cancel(&mut self, object: ObjectId) -> bool {
+        let before = self.pending.len();
+        self.pending.retain(|r| r.object != object);
+        self.pending.len() != before
+    }
+
+    /// Runs one frame: completes requests in priority order until the
+    /// work budget is spent, pushing results into `results`. A request
+    /// whose cost overruns the budget still completes — budgets bound
+    /// *additional* work, not a single search — but ends the frame.
+    /// Everything unprocessed carries over intact.
+    pub fn run_frame(
+        &mut self,
+        system: &ConstraintSystem,
+        results: &mut Vec<ScheduledResult>,
+    ) -> FrameReport {
+        // Highest priority first; stable within a priority via the
+        // submission sequence.
+        self.pending
+            .sort_by(|a, b| b.priority.cmp(&a.priority).then(a.sequence.cmp(&b.sequence)));
+
+        let mut report = FrameReport::default();
+        let mut index = 0;
+        while index < self.pending.len() && report.work_spent < self.budget {
+            let request = &self.pending[index];
+            let response = suggest(system, &request.current, &request.intent, &request.criteria);
+            // Every search costs at least one unit so a frame of
+            // zero-iteration fast paths still terminates the budget.
+            report.work_spent += response.stats.projection_iterations.max(1);
+            results.push(ScheduledResult {
+                object: request.object,
+                response,
+            });
+            report.completed += 1;
+            index += 1;
+        }
+        self.pending.drain(..index);
+        report.carried_over = self.pending.len();
+        report
+    }
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Scheduler::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bounds::Bounds;
+    use crate::constraint::BoxConstraint;
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    fn canvas() -> ConstraintSystem {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(100.0, 100.0))));
+        sys
+    }
+
+    #[test]
+    fn priorities_order_completion() {
+        let sys = canvas();
+        let mut sched = Scheduler::new();
+        sched.enqueue(1, v(0.0, 0.0), v(10.0, 10.0), RankingCriteria::default(), 0);
+        sched.enqueue(2, v(0.0, 0.0), v(20.0, 20.0), RankingCriteria::default(), 5);
+        let mut results = Vec::new();
+        let report = sched.run_frame(&sys, &mut results);
+        assert_eq!(report.completed, 2);
+        assert_eq!(results[0].object, 2);
+        assert_eq!(results[1].object, 1);
+    }
+
+    #[test]
+    fn budget_carries_work_over_deterministically() {
+        // Intents outside the canvas force real projection work, so a
+        // tiny budget completes one request per frame.
+        let sys = canvas();
+        let mut sched = Scheduler::with_budget(1);
+        for id in 0..3u64 {
+            sched.enqueue(
+                id,
+                v(50.0, 50.0),
+                v(200.0, 50.0),
+                RankingCriteria::default(),
+                0,
+            );
+        }
+        let mut results = Vec::new();
+        let first = sched.run_frame(&sys, &mut results);
+        assert_eq!(first.completed, 1);
+        assert_eq!(first.carried_over, 2);
+        assert_eq!(results[0].object, 0);
+        let second = sched.run_frame(&sys, &mut results);
+        assert_eq!(second.completed, 1);
+        assert_eq!(results[1].object, 1);
+        sched.run_frame(&sys, &mut results);
+        assert!(sched.is_empty());
+        assert_eq!(results[2].object, 2);
+    }
+
+    #[test]
+    fn reenqueue_preempts_stale_intent() {
+        let sys = canvas();
+        let mut sched = Scheduler::new();
+        sched.enqueue(7, v(0.0, 0.0), v(10.0, 0.0), RankingCriteria::default(), 0);
+        sched.enqueue(7, v(0.0, 0.0), v(30.0, 0.0), RankingCriteria::default(), 0);
+        assert_eq!(sched.len(), 1);
+        let mut results = Vec::new();
+        sched.run_frame(&sys, &mut results);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].response.position, v(30.0, 0.0));
+    }
+
+    #[test]
+    fn cancel_drops_pending_request() {
+        let mut sched = Scheduler::new();
+        sched.enqueue(3, v(0.0, 0.0), v(1.0, 1.0), RankingCriteria::default(), 0);
+        assert!(sched.cancel(3));
+        assert!(!sched.cancel(3));
+        assert!(sched.is_empty());
+    }
+}
@@ -0,0 +1,255 @@
+//! Sensitivity of a projection to its active constraints.
+//!
+//! When a move is blocked, "which constraint is blocking, and by how
+//! much?" is a question the UI can answer concretely: *loosening this
+//! margin by 5px frees your move*. At the projected point the KKT
+//! condition for projection says the residual `intent − projected` is a
+//! non-negative combination of the outward normals of the active
+//! constraints; the combination weights are the dual values. A dual of
+//! 3 against a constraint means the intent presses 3 units into it —
+//! loosen its bound by δ and roughly `min(δ, dual)` of the blocked
+//! motion comes free along that constraint's normal.
+//!
+//! Normals come from [`boundary_normal`](
+//! crate::constraint::Constraint::boundary_normal) when the constraint
+//! provides it, otherwise from finite-difference gradients of
+//! [`signed_distance`](crate::constraint::Constraint::signed_distance);
+//! the weights come from a small non-negative least-squares fit, which
+//! is approximate but deterministic.
+
+use crate::constraint::ConstraintSystem;
+use crate::linalg::Vector;
+use crate::project::{project_dykstra, ProjectionOptions};
+
+/// A constraint counts as active when the projected point sits within
+/// this distance of its boundary.
+pub const ACTIVATION_TOLERANCE: f64 = 1e-5;
+
+/// Coordinate-descent sweeps for the non-negative least-squares fit.
+const NNLS_SWEEPS: usize = 64;
+
+/// One active constraint's contribution to a blocked move.
+#[derive(Debug, Clone)]
+pub struct SensitivityEntry {
+    /// Index of the constraint in the system.
+    pub constraint: usize,
+    /// Unit outward normal of the constraint at the projected point
+    /// (the direction freed motion would take).
+    pub normal: Vector,
+    /// Dual value: units of blocked motion pressing against this
+    /// constraint per unit of bound change.
+    pub dual: f64,
+}
+
+/// The projected point together with the per-constraint duals.
+#[derive(Debug, Clone)]
+pub struct SensitivityReport {
+    /// Where the intent projects to.
+    pub projected: Vector,
+    /// Active constraints with a positive dual, in constraint order.
+    pub entries: Vec<SensitivityEntry>,
+}
+
+/// Unit outward normal of constraint `index` at `point`. Constraints
+/// that know their normals answer through [`boundary_normal`](
+/// crate::constraint::Constraint::boundary_normal); the rest fall back
+/// to the finite-difference gradient of the signed distance (which
+/// increases toward the interior, so the outward normal is its
+/// negation). `None` when both are degenerate there.
+pub fn constraint_normal(system: &ConstraintSystem, index: usize, point: &Vector) -> Option<Vector> {
+    let constraint = system.constraints()[index].as_ref();
+    if let Some(normal) = constraint.boundary_normal(point) {
+        return Some(normal);
+    }
+    let inward = crate::constraint::slack_gradient(constraint, point)?;
+    Some(inward.scale(-1.0))
+}
+
+/// Projects `intent` and decomposes the residual over the active
+/// constraints' outward normals, reporting each positive dual value.
+/// An intent that is already feasible yields no entries.
+pub fn sensitivity(system: &ConstraintSystem, intent: &Vector) -> SensitivityReport {
+    let projected = if system.is_feasible(intent) {
+        intent.clone()
+    } else {
+        project_dykstra(system, intent, &ProjectionOptions::default()).point
+    };
+    let residual = intent.sub(&projected);
+
+    // Active set and its normals.
+    let mut active: Vec<(usize, Vector)> = Vec::new();
+    for (index, c) in system.constraints().iter().enumerate() {
+        if c.signed_distance(&projected).abs() <= ACTIVATION_TOLERANCE {
+            if let Some(normal) = constraint_normal(system, index, &projected) {
+                active.push((index, normal));
+            }
+        }
+    }
+    if active.is_empty() || residual.norm() < crate::EPSILON {
+        return SensitivityReport {
+            projected,
+            entries: Vec::new(),
+        };
+    }
+
+    // Non-negative least squares: residual ≈ Σ λᵢ·normalᵢ, λᵢ ≥ 0, by
+    // projected coordinate descent (normals are unit length, so each
+    // coordinate step is a plain correlation update).
+    let mut duals = vec![0.0; active.len()];
+    for _ in 0..NNLS_SWEEPS {
+        for (i, (_, normal)) in active.iter().enumerate() {
+            let mut fit = Vector::zeros(residual.dim());
+            for (j, (_, n)) in active.iter().enumerate() {
+                if j != i {
+                    fit = fit.add(&n.scale(duals[j]));
+                }
+            }
+            duals[i] = normal.dot(&residual.sub(&fit)).max(0.0);
+        }
+    }
+
+    let entries = active
+        .into_iter()
+        .zip(duals)
+        .filter(|(_, dual)| *dual > crate::EPSILON)
+        .map(|((constraint, normal), dual)| SensitivityEntry {
+            constraint,
+            normal,
+            dual,
+        })
+        .collect();
+    SensitivityReport { projected, entries }
+}
+
+/// The push the nearest constraint exerts on a state.
+#[derive(Debug, Clone)]
+pub struct PressureSample {
+    /// Index of the nearest constraint in the system.
+    pub constraint: usize,
+    /// Unit direction of the push: the negative gradient of the
+    /// violation function, pointing away from the boundary into the
+    /// interior.
+    pub direction: Vector,
+    /// Dimensionless strength: `0` at the search radius, `1` at
+    /// contact, above `1` in proportion to penetration depth.
+    pub magnitude: f64,
+}
+
+/// The constraint pressure felt at `state` — the direction and
+/// strength of the nearest boundary's push — or `None` when every
+/// boundary is farther than the system's search radius (or its normal
+/// is degenerate there).
+///
+/// Unlike [`sensitivity`], which explains an already-blocked move,
+/// this is pre-emptive: hosts sample it every frame to render
+/// force-field overlays or ramp haptics *before* any violation occurs.
+/// The falloff scale is [`crate::suggest::SearchPolicy::search_radius`],
+/// the same slack scale engagement classification uses.
+pub fn pressure(system: &ConstraintSystem, state: &Vector) -> Option<PressureSample> {
+    let radius = system.search_policy().search_radius();
+    let (constraint, slack) = system
+        .constraints()
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (i, c.signed_distance(state)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))?;
+    if slack >= radius {
+        return None;
+    }
+    // The outward normal is the direction violation grows; the push is
+    // its negation.
+    let direction = constraint_normal(system, constraint, state)?.scale(-1.0);
+    Some(PressureSample {
+        constraint,
+        direction,
+        magnitude: (radius - slack) / radius,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraint::HalfspaceConstraint;
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    #[test]
+    fn single_halfspace_dual_equals_penetration() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(HalfspaceConstraint::new(v(1.0, 0.0), 10.0));
+        let report = sensitivity(&sys, &v(13.0, 4.0));
+        assert!((report.projected.get(0) - 10.0).abs() < 1e-6);
+        assert_eq!(report.entries.len(), 1);
+        let entry = &report.entries[0];
+        assert_eq!(entry.constraint, 0);
+        assert!((entry.dual - 3.0).abs() < 1e-4);
+        assert!(entry.normal.distance(&v(1.0, 0.0)) < 1e-4);
+    }
+
+    #[test]
+    fn corner_splits_duals_between_constraints() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(HalfspaceConstraint::new(v(1.0, 0.0), 10.0));
+        sys.add(HalfspaceConstraint::new(v(0.0, 1.0), 10.0));
+        let report = sensitivity(&sys, &v(13.0, 12.0));
+        assert!(report.projected.distance(&v(10.0, 10.0)) < 1e-4);
+        assert_eq!(report.entries.len(), 2);
+        assert!((report.entries[0].dual - 3.0).abs() < 1e-3);
+        assert!((report.entries[1].dual - 2.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn inactive_constraints_contribute_nothing() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(HalfspaceConstraint::new(v(1.0, 0.0), 10.0));
+        sys.add(HalfspaceConstraint::new(v(0.0, 1.0), 1000.0));
+        let report = sensitivity(&sys, &v(13.0, 0.0));
+        assert_eq!(report.entries.len(), 1);
+        assert_eq!(report.entries[0].constraint, 0);
+    }
+
+    #[test]
+    fn feasible_intent_has_no_entries() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(HalfspaceConstraint::new(v(1.0, 0.0), 10.0));
+        let report = sensitivity(&sys, &v(5.0, 5.0));
+        assert_eq!(report.projected, v(5.0, 5.0));
+        assert!(report.entries.is_empty());
+    }
+
+    #[test]
+    fn pressure_ramps_toward_the_boundary() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(HalfspaceConstraint::new(v(1.0, 0.0), 10.0));
+        let radius = sys.search_policy().search_radius();
+        // Two units of slack: a weak push back into the interior.
+        let p = pressure(&sys, &v(8.0, 0.0)).unwrap();
+        assert_eq!(p.constraint, 0);
+        assert!(p.direction.distance(&v(-1.0, 0.0)) < 1e-6);
+        assert!((p.magnitude - (radius - 2.0) / radius).abs() < 1e-9);
+        // Contact reads exactly one; penetration exceeds it.
+        assert!((pressure(&sys, &v(10.0, 0.0)).unwrap().magnitude - 1.0).abs() < 1e-9);
+        assert!(pressure(&sys, &v(13.0, 0.0)).unwrap().magnitude > 1.0);
+    }
+
+    #[test]
+    fn distant_boundaries_exert_no_pressure() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(HalfspaceConstraint::new(v(1.0, 0.0), 10.0));
+        let radius = sys.search_policy().search_radius();
+        assert!(pressure(&sys, &v(10.0 - radius - 1.0, 0.0)).is_none());
+        assert!(pressure(&ConstraintSystem::new(2), &v(0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn pressure_names_the_nearest_constraint() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(HalfspaceConstraint::new(v(1.0, 0.0), 10.0));
+        sys.add(HalfspaceConstraint::new(v(0.0, 1.0), 10.0));
+        let p = pressure(&sys, &v(0.0, 8.0)).unwrap();
+        assert_eq!(p.constraint, 1);
+        assert!(p.direction.distance(&v(0.0, -1.0)) < 1e-6);
+    }
+}
@@ -0,0 +1,284 @@
+//! Sidecar service protocol for non-Rust hosts.
+//!
+//! Hosts that cannot link the crate directly (Electron shells, JVM
+//! apps, test harnesses in other languages) can run the engine as a
+//! child process and speak this protocol over any byte stream — stdio
+//! pipes, a Unix socket, or TCP all work, since [`serve`] is generic
+//! over [`Read`] + [`Write`].
+//!
+//! Framing is deliberately dumb: every message, in both directions, is
+//! a 4-byte big-endian length followed by that many bytes of UTF-8
+//! payload. No frame may exceed [`MAX_FRAME_BYTES`]. Payloads reuse
+//! the text conventions of [`crate::scenarios`] — vectors in their
+//! canonical `Display` form, whitespace-separated arguments — so a
+//! host that can print a float can speak the protocol.
+//!
+//! Requests are one command each; every request gets exactly one
+//! response, starting `ok` on success and `err <reason>` on failure:
+//!
+//! ```text
+//! ping                          -> ok
+//! load <scenario text>          -> ok <constraint count>
+//! suggest [x, y] [x', y']       -> ok <position> <quality>
+//! quit                          -> ok, then the server closes
+//! ```
+//!
+//! `load` replaces the session's constraint system with one parsed
+//! from the scenario format (replay events in the text are ignored);
+//! `suggest` takes the current position and the intent and answers
+//! with the suggested position and its
+//! [`SuggestionQuality`](crate::suggest::SuggestionQuality) variant
+//! name. Malformed requests get an `err` response rather than killing
+//! the session; only a broken frame (truncated, oversized, or not
+//! UTF-8) ends it, because framing errors leave the stream position
+//! unknowable.
+
+use std::io::{self, Read, Write};
+
+use crate::constraint::ConstraintSystem;
+use crate::rank::RankingCriteria;
+use crate::scenarios::{parse_scenario, split_args, two_vectors};
+use crate::suggest::try_suggest;
+
+/// Largest frame either side may send: 1 MiB of payload, enough for
+/// any realistic document capture while bounding a hostile length
+/// prefix.
+pub const MAX_FRAME_BYTES: u32 = 1 << 20;
+
+/// Writes one length-prefixed frame. Panics when the payload exceeds
+/// [`MAX_FRAME_BYTES`] — the protocol cannot express it, so sending a
+/// truncation would be worse than failing loudly.
+pub fn write_frame(writer: &mut impl Write, payload: &str) -> io::Result<()> {
+    assert!(
+        payload.len() <= MAX_FRAME_BYTES as usize,
+        "frame payload exceeds the protocol maximum"
+    );
+    writer.write_all(&(payload.len() as u32).to_be_bytes())?;
+    writer.write_all(payload.as_bytes())?;
+    writer.flush()
+}
+
+/// Reads one length-prefixed frame. `Ok(None)` is a clean end of
+/// stream at a frame boundary; end of stream inside a frame, a length
+/// over [`MAX_FRAME_BYTES`], or non-UTF-8 payload are errors.
+pub fn read_frame(reader: &mut impl Read) -> io::Result<Option<String>> {
+    let mut prefix = [0u8; 4];
+    let mut filled = 0;
+    while filled < prefix.len() {
+        let n = reader.read(&mut prefix[filled..])?;
+        if n == 0 {
+            if filled == 0 {
+                return Ok(None);
+            }
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "stream ended inside a frame length",
+            ));
+        }
+        filled += n;
+    }
+    let len = u32::from_be_bytes(prefix);
+    if len > MAX_FRAME_BYTES {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "frame exceeds the protocol maximum",
+        ));
+    }
+    let mut payload = vec![0u8; len as usize];
+    reader.read_exact(&mut payload)?;
+    String::from_utf8(payload)
+        .map(Some)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "frame payload is not UTF-8"))
+}
+
+/// One host's protocol state: the loaded constraint system and the
+/// ranking criteria its suggestions use. Framing lives in
+/// [`serve`]; `handle` maps one request payload to one response
+/// payload, which is also the convenient seam for tests and for hosts
+/// that bring their own transport.
+pub struct ServiceSession {
+    system: Option<ConstraintSystem>,
+    criteria: RankingCriteria,
+}
+
+impl ServiceSession {
+    /// A fresh session with no system loaded and default criteria.
+    pub fn new() -> Self {
+        ServiceSession {
+            system: None,
+            criteria: RankingCriteria::default(),
+        }
+    }
+
+    /// Answers one request payload. Never panics on malformed input;
+    /// the reply starts `ok` or `err`.
+    pub fn handle(&mut self, request: &str) -> String {
+        let request = request.trim();
+        let (verb, rest) = match request.split_once(char::is_whitespace) {
+            Some((v, r)) => (v, r.trim()),
+            None => (request, ""),
+        };
+        match verb {
+            "ping" | "quit" => "ok".to_string(),
+            "load" => match parse_scenario(rest) {
+                Ok(scenario) => {
+                    let count = scenario.system.constraints().len();
+                    self.system = Some(scenario.system);
+                    format!("ok {count}")
+                }
+                Err(e) => format!("err bad scenario: {e:?}"),
+            },
+            "suggest" => {
+                let Some(system) = &self.system else {
+                    return "err no system loaded".to_string();
+                };
+                let Some([current, intent]) = two_vectors(&split_args(rest)) else {
+                    return "err suggest wants two vectors".to_string();
+                };
+                match try_suggest(system, &current, &intent, &self.criteria) {
+                    Ok(r) => format!("ok {} {:?}", r.position, r.quality),
+                    Err(e) => format!("err {e}"),
+                }
+            }
+            _ => format!("err unknown command: {verb}"),
+        }
+    }
+}
+
+impl Default for ServiceSession {
+    fn default() -> Self {
+        ServiceSession::new()
+    }
+}
+
+/// Serves one session over a byte stream: reads request frames,
+/// answers each with one response frame, and returns cleanly when the
+/// peer sends `quit` or closes the stream at a frame boundary.
+/// Framing errors propagate as [`io::Error`].
+pub fn serve(mut reader: impl Read, mut writer: impl Write) -> io::Result<()> {
+    let mut session = ServiceSession::new();
+    while let Some(request) = read_frame(&mut reader)? {
+        let response = session.handle(&request);
+        write_frame(&mut writer, &response)?;
+        if request.trim() == "quit" {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// [`serve`] over this process's stdin and stdout, for the plain
+/// sidecar-subprocess deployment.
+pub fn serve_stdio() -> io::Result<()> {
+    serve(io::stdin().lock(), io::stdout().lock())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Frames a batch of requests the way a host would.
+    fn framed(requests: &[&str]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for r in requests {
+            write_frame(&mut bytes, r).unwrap();
+        }
+        bytes
+    }
+
+    /// Reads back every response frame a serve call produced.
+    fn responses(bytes: &[u8]) -> Vec<String> {
+        let mut reader = io::Cursor::new(bytes);
+        let mut out = Vec::new();
+        while let Some(frame) = read_frame(&mut reader).unwrap() {
+            out.push(frame);
+        }
+        out
+    }
+
+    #[test]
+    fn frames_round_trip() {
+        let mut bytes = Vec::new();
+        write_frame(&mut bytes, "hello [1.0, 2.0]").unwrap();
+        write_frame(&mut bytes, "").unwrap();
+        let mut reader = io::Cursor::new(bytes);
+        assert_eq!(read_frame(&mut reader).unwrap().as_deref(), Some("hello [1.0, 2.0]"));
+        assert_eq!(read_frame(&mut reader).unwrap().as_deref(), Some(""));
+        assert_eq!(read_frame(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn a_whole_session_runs_over_the_wire() {
+        let input = framed(&[
+            "ping",
+            "load\ndim 2\nbox [0.0, 0.0] [100.0, 100.0]",
+            "suggest [50.0, 50.0] [120.0, 50.0]",
+            "quit",
+        ]);
+        let mut output = Vec::new();
+        serve(io::Cursor::new(input), &mut output).unwrap();
+        let replies = responses(&output);
+        assert_eq!(replies.len(), 4);
+        assert_eq!(replies[0], "ok");
+        assert_eq!(replies[1], "ok 1");
+        assert_eq!(replies[2], "ok [100.0, 50.0] Projected");
+        assert_eq!(replies[3], "ok");
+    }
+
+    #[test]
+    fn malformed_requests_get_err_not_a_dead_session() {
+        let mut session = ServiceSession::new();
+        assert_eq!(session.handle("suggest [0.0] [1.0]"), "err no system loaded");
+        assert_eq!(session.handle("frobnicate"), "err unknown command: frobnicate");
+        session.handle("load\ndim 2\nbox [0.0, 0.0] [10.0, 10.0]");
+        assert_eq!(
+            session.handle("suggest [0.0, 0.0]"),
+            "err suggest wants two vectors"
+        );
+        // Dimension mismatch surfaces as a value, not a panic.
+        assert_eq!(
+            session.handle("suggest [0.0] [1.0]"),
+            "err dimension mismatch: expected 2, got 1"
+        );
+        // And the session is still alive afterwards.
+        assert!(session.handle("suggest [5.0, 5.0] [5.0, 5.0]").starts_with("ok"));
+    }
+
+    #[test]
+    fn corrupt_scenario_values_get_err_not_a_panic() {
+        // Well-formed load frames carrying values no constraint can
+        // accept — the kind that used to die in constructor asserts
+        // rather than answer `err`.
+        let mut session = ServiceSession::new();
+        for request in [
+            "load\ndim 2\nlattice 0.0",
+            "load\ndim 2\nhalfspace [0.0, 0.0] 5.0",
+            "load\ndim 2\nbox [1.0, 1.0] [0.0, 0.0]",
+            "load\ndim 2\nbox [0.0] [1.0]",
+        ] {
+            assert!(
+                session.handle(request).starts_with("err bad scenario:"),
+                "for {request:?}"
+            );
+        }
+        // A failed load leaves no half-built system behind.
+        assert_eq!(session.handle("suggest [0.0] [1.0]"), "err no system loaded");
+        // And the session still loads a good scenario afterwards.
+        assert_eq!(session.handle("load\ndim 2\nbox [0.0, 0.0] [10.0, 10.0]"), "ok 1");
+    }
+
+    #[test]
+    fn broken_frames_are_errors() {
+        // A length prefix promising more bytes than follow.
+        let mut reader = io::Cursor::new(vec![0, 0, 0, 9, b'h', b'i']);
+        assert!(read_frame(&mut reader).is_err());
+        // A truncated length prefix.
+        let mut reader = io::Cursor::new(vec![0, 0]);
+        assert!(read_frame(&mut reader).is_err());
+        // A hostile length prefix beyond the protocol maximum.
+        let mut oversized = Vec::new();
+        oversized.extend_from_slice(&(MAX_FRAME_BYTES + 1).to_be_bytes());
+        let mut reader = io::Cursor::new(oversized);
+        assert!(read_frame(&mut reader).is_err());
+    }
+}
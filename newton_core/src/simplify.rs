@@ -0,0 +1,208 @@
+//! Constraint simplification and normalisation.
+//!
+//! Machine-generated documents routinely carry stacks of overlapping
+//! boxes and axis-aligned halfspaces that all describe one rectangle.
+//! Every extra constraint is another projection per Dykstra sweep and
+//! another chance for the corrections to fight each other, so
+//! [`simplify`] preprocesses a system before interaction: boxes are
+//! intersected into one, axis-aligned halfspaces fold into that box,
+//! parallel halfspaces keep only the tightest, and surviving halfspace
+//! normals are normalised to unit length (which conditions the
+//! alternating projections). The feasible set is unchanged; only the
+//! description shrinks. Constraint types the pass does not recognise
+//! are carried over untouched.
+
+use crate::bounds::Bounds;
+use crate::constraint::{BoxConstraint, ConstraintSystem, HalfspaceConstraint};
+use crate::linalg::Vector;
+
+/// Tolerance for treating two unit normals as parallel.
+const PARALLEL_TOLERANCE: f64 = 1e-6;
+
+/// Index of the only substantial component of `normal`, when it is
+/// axis-aligned.
+fn single_axis(normal: &Vector) -> Option<usize> {
+    let mut axis = None;
+    for i in 0..normal.dim() {
+        if normal.get(i).abs() > crate::EPSILON {
+            if axis.is_some() {
+                return None;
+            }
+            axis = Some(i);
+        }
+    }
+    axis
+}
+
+/// Produces an equivalent system with fewer, better-conditioned
+/// constraints (see the module docs for the individual rewrites).
+/// Ranking profiles are not carried over, and constraint order may
+/// change; when boxes are mutually disjoint (an authoring error —
+/// [`crate::analyze`] reports it) the system is returned unmerged.
+pub fn simplify(system: &ConstraintSystem) -> ConstraintSystem {
+    let mut boxes: Vec<Bounds> = Vec::new();
+    let mut halfspaces: Vec<(Vector, f64)> = Vec::new();
+    let mut others = Vec::new();
+
+    for c in system.constraints() {
+        let any = c.as_any();
+        if let Some(b) = any.downcast_ref::<BoxConstraint>() {
+            boxes.push(b.bounds().clone());
+        } else if let Some(h) = any.downcast_ref::<HalfspaceConstraint>() {
+            let norm = h.normal().norm();
+            halfspaces.push((h.normal().scale(1.0 / norm), h.offset() / norm));
+        } else {
+            others.push(c.clone());
+        }
+    }
+
+    // Intersect all boxes into one; bail out unchanged on disjointness.
+    let mut merged: Option<Bounds> = None;
+    for b in &boxes {
+        merged = match merged {
+            None => Some(b.clone()),
+            Some(m) => match m.intersection(b) {
+                Some(i) => Some(i),
+                None => {
+                    let mut out = ConstraintSystem::new(system.dim());
+                    for c in system.constraints() {
+                        out.add_ref(c.clone());
+                    }
+                    return out;
+                }
+            },
+        };
+    }
+
+    // Fold axis-aligned halfspaces into the merged box, when one
+    // exists to fold into.
+    let mut remaining: Vec<(Vector, f64)> = Vec::new();
+    for (normal, offset) in halfspaces {
+        match (merged.as_mut(), single_axis(&normal)) {
+            (Some(bounds), Some(axis)) => {
+                let n = normal.get(axis);
+                let mut min = bounds.min().clone();
+                let mut max = bounds.max().clone();
+                if n > 0.0 {
+                    max.set(axis, max.get(axis).min(offset / n));
+                } else {
+                    min.set(axis, min.get(axis).max(offset / n));
+                }
+                if min.get(axis) <= max.get(axis) {
+                    *bounds = Bounds::new(min, max);
+                } else {
+                    // Tightening would empty the box: leave the
+                    // contradiction visible rather than papering over
+                    // it.
+                    remaining.push((normal, offset));
+                }
+            }
+            _ => remaining.push((normal, offset)),
+        }
+    }
+
+    // Keep only the tightest of each parallel family.
+    let mut kept: Vec<(Vector, f64)> = Vec::new();
+    for (normal, offset) in remaining {
+        match kept
+            .iter_mut()
+            .find(|(n, _)| n.distance(&normal) < PARALLEL_TOLERANCE)
+        {
+            Some((_, o)) => *o = o.min(offset),
+            None => kept.push((normal, offset)),
+        }
+    }
+
+    let mut out = ConstraintSystem::new(system.dim());
+    if let Some(bounds) = merged {
+        out.add(BoxConstraint::new(bounds));
+    }
+    for (normal, offset) in kept {
+        out.add(HalfspaceConstraint::new(normal, offset));
+    }
+    for c in others {
+        out.add_ref(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraint::CollisionConstraint;
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    fn boxed(x0: f64, y0: f64, x1: f64, y1: f64) -> BoxConstraint {
+        BoxConstraint::new(Bounds::new(v(x0, y0), v(x1, y1)))
+    }
+
+    #[test]
+    fn overlapping_boxes_merge_to_their_intersection() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(boxed(0.0, 0.0, 100.0, 100.0));
+        sys.add(boxed(50.0, -10.0, 200.0, 60.0));
+        let simplified = simplify(&sys);
+        assert_eq!(simplified.len(), 1);
+        let b = simplified.constraints()[0]
+            .as_any()
+            .downcast_ref::<BoxConstraint>()
+            .unwrap();
+        assert_eq!(b.bounds().min(), &v(50.0, 0.0));
+        assert_eq!(b.bounds().max(), &v(100.0, 60.0));
+    }
+
+    #[test]
+    fn axis_aligned_halfspaces_fold_into_the_box() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(boxed(0.0, 0.0, 100.0, 100.0));
+        sys.add(HalfspaceConstraint::new(v(1.0, 0.0), 80.0));
+        sys.add(HalfspaceConstraint::new(v(0.0, -2.0), -20.0)); // y >= 10
+        let simplified = simplify(&sys);
+        assert_eq!(simplified.len(), 1);
+        let b = simplified.constraints()[0]
+            .as_any()
+            .downcast_ref::<BoxConstraint>()
+            .unwrap();
+        assert_eq!(b.bounds().min(), &v(0.0, 10.0));
+        assert_eq!(b.bounds().max(), &v(80.0, 100.0));
+    }
+
+    #[test]
+    fn parallel_halfspaces_keep_the_tightest_and_normalise() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(HalfspaceConstraint::new(v(3.0, 4.0), 50.0));
+        sys.add(HalfspaceConstraint::new(v(6.0, 8.0), 20.0));
+        let simplified = simplify(&sys);
+        assert_eq!(simplified.len(), 1);
+        let h = simplified.constraints()[0]
+            .as_any()
+            .downcast_ref::<HalfspaceConstraint>()
+            .unwrap();
+        assert!((h.normal().norm() - 1.0).abs() < 1e-9);
+        assert!((h.offset() - 2.0).abs() < 1e-9); // 20 / |(6,8)|
+    }
+
+    #[test]
+    fn disjoint_boxes_pass_through_unmerged() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(boxed(0.0, 0.0, 10.0, 10.0));
+        sys.add(boxed(20.0, 20.0, 30.0, 30.0));
+        assert_eq!(simplify(&sys).len(), 2);
+    }
+
+    #[test]
+    fn unrecognised_constraints_are_preserved() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(boxed(0.0, 0.0, 100.0, 100.0));
+        sys.add(CollisionConstraint::new(Bounds::new(v(40.0, 40.0), v(60.0, 60.0))));
+        let simplified = simplify(&sys);
+        assert_eq!(simplified.len(), 2);
+        // Feasibility is unchanged around the obstacle.
+        for p in [v(10.0, 10.0), v(50.0, 50.0), v(99.0, 99.0)] {
+            assert_eq!(sys.is_feasible(&p), simplified.is_feasible(&p));
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! Magnetic snapping.
+//!
+//! A hard [`DiscreteConstraint`](crate::constraint::DiscreteConstraint)
+//! in the system makes free-form movement impossible: every suggestion
+//! lands on a member, so the object teleports between snap points and
+//! can never rest in between. Magnetic snapping keeps the snap targets
+//! *out* of the constraint system and gives each source an attraction
+//! radius and strength instead: inside the radius the intent is pulled
+//! to the nearest target (fully at strength 1, proportionally below),
+//! outside it passes through untouched. Engagement is sticky — an
+//! engaged magnet holds until the gesture leaves a larger release
+//! radius — so an intent hovering right at the boundary does not
+//! flicker between snapped and free.
+//!
+//! The session is per-gesture state, like
+//! [`DragSession`](crate::suggest::DragSession); call
+//! [`SnapSession::reset`] when the gesture ends.
+
+use crate::constraint::{Constraint, ConstraintRef, ConstraintSystem};
+use crate::linalg::Vector;
+use crate::rank::RankingCriteria;
+use crate::suggest::{suggest, SuggestResponse};
+
+/// Multiple of the attraction radius an engaged magnet holds out to.
+/// The gap between the engage distance (the radius) and the release
+/// distance (this factor times it) is the hysteresis band that
+/// prevents flicker at the boundary.
+pub const SNAP_RELEASE_FACTOR: f64 = 1.25;
+
+/// One snap source: any constraint whose projection lands on a snap
+/// target (discrete sets, lattices, product sets).
+struct Magnet {
+    constraint: ConstraintRef,
+    radius: f64,
+    strength: f64,
+}
+
+/// Where a resolved intent ended up, and which magnet (if any) holds
+/// it — the index is stable for highlighting the active snap source.
+#[derive(Debug, Clone)]
+pub struct SnapResolution {
+    pub position: Vector,
+    pub engaged: Option<usize>,
+}
+
+/// Per-gesture magnetic snapping state.
+pub struct SnapSession {
+    dim: usize,
+    magnets: Vec<Magnet>,
+    engaged: Option<usize>,
+}
+
+impl SnapSession {
+    /// An empty session over a `dim`-dimensional space.
+    pub fn new(dim: usize) -> Self {
+        SnapSession {
+            dim,
+            magnets: Vec::new(),
+            engaged: None,
+        }
+    }
+
+    /// Registers a snap source by value. Panics on dimension mismatch,
+    /// a non-positive radius, or a strength outside `(0, 1]`.
+    pub fn add_magnet(&mut self, constraint: impl Constraint + 'static, radius: f64, strength: f64) {
+        self.add_magnet_ref(std::sync::Arc::new(constraint), radius, strength);
+    }
+
+    /// Registers a shared snap source handle.
+    pub fn add_magnet_ref(&mut self, constraint: ConstraintRef, radius: f64, strength: f64) {
+        assert_eq!(
+            constraint.dim(),
+            self.dim,
+            "magnet dimension does not match session"
+        );
+        assert!(
+            radius.is_finite() && radius > 0.0,
+            "attraction radius must be positive and finite"
+        );
+        assert!(
+            strength > 0.0 && strength <= 1.0,
+            "snap strength must lie in (0, 1]"
+        );
+        self.magnets.push(Magnet {
+            constraint,
+            radius,
+            strength,
+        });
+    }
+
+    /// Index of the magnet currently holding the gesture, if any.
+    pub fn engaged(&self) -> Option<usize> {
+        self.engaged
+    }
+
+    /// Lets go of any engaged magnet, e.g. on gesture end.
+    pub fn reset(&mut self) {
+        self.engaged = None;
+    }
+
+    /// Pulls `intent` toward the active snap target. An engaged magnet
+    /// holds while the intent stays within its release distance; a free
+    /// intent engages the magnet whose target is nearest among those
+    /// whose radius covers it. Panics on dimension mismatch.
+    pub fn resolve(&mut self, intent: &Vector) -> SnapResolution {
+        assert_eq!(intent.dim(), self.dim, "intent dimension mismatch");
+        if let Some(i) = self.engaged {
+            let m = &self.magnets[i];
+            let target = m.constraint.project(intent);
+            if intent.distance(&target) <= m.radius * SNAP_RELEASE_FACTOR {
+                return SnapResolution {
+                    position: intent.lerp(&target, m.strength),
+                    engaged: Some(i),
+                };
+            }
+            self.engaged = None;
+        }
+        let mut best: Option<(usize, Vector, f64)> = None;
+        for (i, m) in self.magnets.iter().enumerate() {
+            let target = m.constraint.project(intent);
+            let d = intent.distance(&target);
+            if d <= m.radius && best.as_ref().is_none_or(|(_, _, bd)| d < *bd) {
+                best = Some((i, target, d));
+            }
+        }
+        match best {
+            Some((i, target, _)) => {
+                self.engaged = Some(i);
+                SnapResolution {
+                    position: intent.lerp(&target, self.magnets[i].strength),
+                    engaged: Some(i),
+                }
+            }
+            None => SnapResolution {
+                position: intent.clone(),
+                engaged: None,
+            },
+        }
+    }
+}
+
+/// [`suggest`] with the intent passed through the snap session first:
+/// inside a magnet's radius the search aims at the snapped point,
+/// outside it the intent passes through untouched. The system's hard
+/// constraints still have the last word — a snap target outside the
+/// feasible set projects like any other intent.
+pub fn suggest_magnetic(
+    system: &ConstraintSystem,
+    current: &Vector,
+    intent: &Vector,
+    criteria: &RankingCriteria,
+    session: &mut SnapSession,
+) -> SuggestResponse {
+    let resolved = session.resolve(intent);
+    suggest(system, current, &resolved.position, criteria)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bounds::Bounds;
+    use crate::constraint::{BoxConstraint, DiscreteConstraint};
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    fn session_with_grid_point() -> SnapSession {
+        let mut session = SnapSession::new(2);
+        session.add_magnet(DiscreteConstraint::new(vec![v(50.0, 50.0)]), 8.0, 1.0);
+        session
+    }
+
+    #[test]
+    fn snaps_inside_the_radius_and_passes_through_outside() {
+        let mut session = session_with_grid_point();
+        let near = session.resolve(&v(55.0, 50.0));
+        assert_eq!(near.position, v(50.0, 50.0));
+        assert_eq!(near.engaged, Some(0));
+        session.reset();
+        let far = session.resolve(&v(70.0, 50.0));
+        assert_eq!(far.position, v(70.0, 50.0));
+        assert_eq!(far.engaged, None);
+    }
+
+    #[test]
+    fn hysteresis_holds_past_the_radius_without_flicker() {
+        let mut session = session_with_grid_point();
+        assert!(session.resolve(&v(55.0, 50.0)).engaged.is_some());
+        // 9 units out: beyond the engage radius, inside the release
+        // band — an engaged magnet holds.
+        assert!(session.resolve(&v(59.0, 50.0)).engaged.is_some());
+        // Past the release distance it lets go.
+        assert!(session.resolve(&v(61.0, 50.0)).engaged.is_none());
+        // The same in-band intent does *not* re-engage from free.
+        assert!(session.resolve(&v(59.0, 50.0)).engaged.is_none());
+    }
+
+    #[test]
+    fn partial_strength_pulls_proportionally() {
+        let mut session = SnapSession::new(2);
+        session.add_magnet(DiscreteConstraint::new(vec![v(50.0, 50.0)]), 8.0, 0.5);
+        let r = session.resolve(&v(54.0, 50.0));
+        assert_eq!(r.position, v(52.0, 50.0));
+    }
+
+    #[test]
+    fn nearest_covering_magnet_wins() {
+        let mut session = SnapSession::new(2);
+        session.add_magnet(DiscreteConstraint::new(vec![v(40.0, 50.0)]), 8.0, 1.0);
+        session.add_magnet(DiscreteConstraint::new(vec![v(50.0, 50.0)]), 8.0, 1.0);
+        let r = session.resolve(&v(46.0, 50.0));
+        assert_eq!(r.engaged, Some(1));
+        assert_eq!(r.position, v(50.0, 50.0));
+    }
+
+    #[test]
+    fn hard_constraints_outrank_the_magnet() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(48.0, 100.0))));
+        let mut session = session_with_grid_point();
+        // The magnet pulls to (50, 50), which the box forbids; the
+        // suggestion lands on the boundary instead.
+        let r = suggest_magnetic(
+            &sys,
+            &v(40.0, 50.0),
+            &v(55.0, 50.0),
+            &RankingCriteria::default(),
+            &mut session,
+        );
+        assert!((r.position.get(0) - 48.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,298 @@
+//! Size-aware object state.
+//!
+//! Plain positions are enough for point-like manipulation, but most UI
+//! objects are rectangles: their state is position *and* extent. This
+//! module fixes a standard stacked layout `[position..., size...]` for
+//! that state so it can travel through the vector-based engine, and
+//! provides constraint adapters that understand it — "keep the whole
+//! rectangle inside the canvas" becomes a one-liner instead of manual
+//! min/max arithmetic on raw vectors.
+
+use crate::bounds::Bounds;
+use crate::constraint::{
+    BoxConstraint, CollisionConstraint, Constraint, ConstraintSystem, FreeSpaceConstraint,
+    HalfspaceConstraint,
+};
+use crate::linalg::Vector;
+use crate::object::Anchor;
+
+/// Position plus extent of a rectangular object. `position` is
+/// expressed at a caller-chosen [`Anchor`]; `size` is always
+/// non-negative per dimension.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectState {
+    pub position: Vector,
+    pub size: Vector,
+}
+
+impl ObjectState {
+    /// Panics if the dimensions disagree or any size component is
+    /// negative.
+    pub fn new(position: Vector, size: Vector) -> Self {
+        assert_eq!(position.dim(), size.dim(), "dimension mismatch in ObjectState");
+        assert!(
+            size.as_slice().iter().all(|&s| s >= 0.0),
+            "ObjectState size must be non-negative"
+        );
+        ObjectState { position, size }
+    }
+
+    /// Spatial dimension (half the stacked vector's dimension).
+    pub fn dim(&self) -> usize {
+        self.position.dim()
+    }
+
+    /// Stacks into the standard `[position..., size...]` layout.
+    pub fn to_vector(&self) -> Vector {
+        let mut data = Vec::with_capacity(self.dim() * 2);
+        data.extend_from_slice(self.position.as_slice());
+        data.extend_from_slice(self.size.as_slice());
+        Vector::new(data)
+    }
+
+    /// Unstacks from the standard layout. Panics on odd dimension.
+    pub fn from_vector(stacked: &Vector) -> Self {
+        let d2 = stacked.dim();
+        assert!(d2.is_multiple_of(2), "stacked state must have even dimension");
+        let d = d2 / 2;
+        ObjectState {
+            position: Vector::new(stacked.as_slice()[..d].to_vec()),
+            size: Vector::new(stacked.as_slice()[d..].to_vec()),
+        }
+    }
+
+    /// The rectangle this state occupies, for a position expressed at
+    /// `anchor`.
+    pub fn bounds(&self, anchor: &Anchor) -> Bounds {
+        let min = anchor.to_min_corner(&self.position, &self.size);
+        Bounds::new(min.clone(), min.add(&self.size))
+    }
+}
+
+/// Keeps the whole rectangle of a stacked `[position, size]` state
+/// inside a canvas.
+///
+/// Operates on the stacked space, so both position and extent may be
+/// adjusted: projection first shrinks oversized extents to fit the
+/// canvas, then slides the rectangle inside. The projection is a
+/// practical clamp rather than an exact Euclidean projection in the
+/// stacked space; for fixed-size objects it is exact.
+#[derive(Debug, Clone)]
+pub struct ContainmentConstraint {
+    canvas: Bounds,
+    anchor: Anchor,
+}
+
+impl ContainmentConstraint {
+    pub fn new(canvas: Bounds, anchor: Anchor) -> Self {
+        assert_eq!(canvas.dim(), anchor.dim(), "dimension mismatch in ContainmentConstraint");
+        ContainmentConstraint { canvas, anchor }
+    }
+
+    pub fn canvas(&self) -> &Bounds {
+        &self.canvas
+    }
+}
+
+impl Constraint for ContainmentConstraint {
+    fn dim(&self) -> usize {
+        self.canvas.dim() * 2
+    }
+
+    fn contains(&self, point: &Vector) -> bool {
+        let state = ObjectState::from_vector(point);
+        let rect = state.bounds(&self.anchor);
+        self.canvas.contains(rect.min()) && self.canvas.contains(rect.max())
+    }
+
+    fn project(&self, point: &Vector) -> Vector {
+        let state = ObjectState::from_vector(point);
+        let d = state.dim();
+        // Shrink the extent to what the canvas can hold at all.
+        let mut size = Vector::zeros(d);
+        for i in 0..d {
+            let available = self.canvas.max().get(i) - self.canvas.min().get(i);
+            size.set(i, state.size.get(i).max(0.0).min(available));
+        }
+        // Slide the min corner so the rectangle fits.
+        let min = self.anchor.to_min_corner(&state.position, &size);
+        let mut fitted = Vector::zeros(d);
+        for i in 0..d {
+            let lo = self.canvas.min().get(i);
+            let hi = self.canvas.max().get(i) - size.get(i);
+            fitted.set(i, min.get(i).clamp(lo, hi));
+        }
+        let position = self.anchor.from_min_corner(&fitted, &size);
+        ObjectState::new(position, size).to_vector()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Rewrites footprint constraints into exact *center* constraints for
+/// a rectangle of the given `size` (the Minkowski construction):
+/// containers shrink by the half-extent, obstacles and free-space rooms
+/// inflate or shrink by it, and halfspaces tighten by their support
+/// over the half-extent. Collision checks on the returned system are
+/// then exact for the whole rectangle, not just its center point.
+///
+/// A container or room too small to hold the object collapses to its
+/// midpoint on the tight axes (rooms that cannot hold it at all are
+/// dropped). Constraint types without a known footprint rewrite pass
+/// through unchanged and keep their point-sample semantics.
+pub fn center_space(system: &ConstraintSystem, size: &Vector) -> ConstraintSystem {
+    assert_eq!(system.dim(), size.dim(), "dimension mismatch in center_space");
+    assert!(
+        size.as_slice().iter().all(|&s| s >= 0.0),
+        "object size must be non-negative"
+    );
+    let half = size.scale(0.5);
+    // Container shrink: tight axes collapse to their midpoint.
+    let shrink = |bounds: &Bounds| -> Bounds {
+        let mut min = Vec::with_capacity(bounds.dim());
+        let mut max = Vec::with_capacity(bounds.dim());
+        for i in 0..bounds.dim() {
+            if bounds.max().get(i) - bounds.min().get(i) < size.get(i) {
+                let mid = (bounds.min().get(i) + bounds.max().get(i)) / 2.0;
+                min.push(mid);
+                max.push(mid);
+            } else {
+                min.push(bounds.min().get(i) + half.get(i));
+                max.push(bounds.max().get(i) - half.get(i));
+            }
+        }
+        Bounds::new(Vector::new(min), Vector::new(max))
+    };
+    // Room shrink: a room the object cannot fit in is no room at all.
+    let shrink_room = |bounds: &Bounds| -> Option<Bounds> {
+        let fits = (0..bounds.dim())
+            .all(|i| bounds.max().get(i) - bounds.min().get(i) >= size.get(i));
+        fits.then(|| shrink(bounds))
+    };
+    let inflate = |bounds: &Bounds| -> Bounds {
+        Bounds::new(bounds.min().sub(&half), bounds.max().add(&half))
+    };
+
+    let mut out = ConstraintSystem::new(system.dim());
+    for c in system.constraints() {
+        let any = c.as_any();
+        if let Some(b) = any.downcast_ref::<BoxConstraint>() {
+            out.add(BoxConstraint::new(shrink(b.bounds())));
+        } else if let Some(o) = any.downcast_ref::<CollisionConstraint>() {
+            out.add(CollisionConstraint::with_margin(
+                inflate(o.obstacle()),
+                o.margin(),
+            ));
+        } else if let Some(f) = any.downcast_ref::<FreeSpaceConstraint>() {
+            let rooms: Vec<Bounds> = f.rooms().iter().filter_map(shrink_room).collect();
+            assert!(!rooms.is_empty(), "object fits in no free-space room");
+            out.add(FreeSpaceConstraint::new(rooms));
+        } else if let Some(h) = any.downcast_ref::<HalfspaceConstraint>() {
+            // Worst corner of the rectangle: the support of |n| over
+            // the half-extent.
+            let support: f64 = (0..h.normal().dim())
+                .map(|i| h.normal().get(i).abs() * half.get(i))
+                .sum();
+            out.add(HalfspaceConstraint::new(
+                h.normal().clone(),
+                h.offset() - support,
+            ));
+        } else {
+            out.add_ref(c.clone());
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraint::ConstraintSystem;
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    #[test]
+    fn stacking_round_trips() {
+        let s = ObjectState::new(v(1.0, 2.0), v(3.0, 4.0));
+        assert_eq!(ObjectState::from_vector(&s.to_vector()), s);
+        assert_eq!(s.to_vector(), Vector::new(vec![1.0, 2.0, 3.0, 4.0]));
+    }
+
+    #[test]
+    fn bounds_respect_anchor() {
+        let s = ObjectState::new(v(5.0, 2.0), v(10.0, 4.0));
+        let rect = s.bounds(&Anchor::center(2));
+        assert_eq!(rect.min(), &v(0.0, 0.0));
+        assert_eq!(rect.max(), &v(10.0, 4.0));
+    }
+
+    #[test]
+    fn containment_slides_rectangle_inside() {
+        let canvas = Bounds::new(v(0.0, 0.0), v(100.0, 100.0));
+        let c = ContainmentConstraint::new(canvas, Anchor::min_corner(2));
+        // A 20x20 rectangle poking past the right edge.
+        let state = ObjectState::new(v(95.0, 10.0), v(20.0, 20.0)).to_vector();
+        assert!(!c.contains(&state));
+        let fixed = ObjectState::from_vector(&c.project(&state));
+        assert_eq!(fixed.position, v(80.0, 10.0));
+        assert_eq!(fixed.size, v(20.0, 20.0));
+        assert!(c.contains(&fixed.to_vector()));
+    }
+
+    #[test]
+    fn center_space_shrinks_containers_and_inflates_obstacles() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(100.0, 100.0))));
+        sys.add(CollisionConstraint::new(Bounds::new(
+            v(40.0, 40.0),
+            v(60.0, 60.0),
+        )));
+        let cs = center_space(&sys, &v(20.0, 10.0));
+        // Center 9 units from the left edge: the 20-wide rectangle
+        // would poke out, so the shrunk container rejects it...
+        assert!(!cs.is_feasible(&v(9.0, 50.0)));
+        assert!(cs.is_feasible(&v(10.0, 95.0)));
+        // ...and a center 8 units left of the obstacle overlaps it by
+        // 2 through the inflated keep-out.
+        assert!(!cs.is_feasible(&v(32.0, 50.0)));
+        assert!(cs.is_feasible(&v(29.0, 50.0)));
+    }
+
+    #[test]
+    fn center_space_tightens_halfspaces_by_support() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(HalfspaceConstraint::new(v(1.0, 0.0), 50.0));
+        let cs = center_space(&sys, &v(10.0, 4.0));
+        // The rectangle's right edge sits 5 right of the center.
+        assert!(cs.is_feasible(&v(45.0, 0.0)));
+        assert!(!cs.is_feasible(&v(46.0, 0.0)));
+    }
+
+    #[test]
+    fn center_space_collapses_tight_containers() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(10.0, 100.0))));
+        // Wider than the container: the only x the center may take is
+        // the container's midline.
+        let cs = center_space(&sys, &v(30.0, 10.0));
+        assert!(cs.is_feasible(&v(5.0, 50.0)));
+        assert!(!cs.is_feasible(&v(4.0, 50.0)));
+        // The roomy y axis keeps its shrunk range.
+        assert!(cs.is_feasible(&v(5.0, 5.0)));
+        assert!(!cs.is_feasible(&v(5.0, 4.0)));
+    }
+
+    #[test]
+    fn containment_shrinks_oversized_extents() {
+        let canvas = Bounds::new(v(0.0, 0.0), v(50.0, 50.0));
+        let c = ContainmentConstraint::new(canvas, Anchor::min_corner(2));
+        let state = ObjectState::new(v(0.0, 0.0), v(80.0, 10.0)).to_vector();
+        let fixed = ObjectState::from_vector(&c.project(&state));
+        assert_eq!(fixed.size, v(50.0, 10.0));
+        assert!(c.contains(&fixed.to_vector()));
+    }
+}
@@ -0,0 +1,2737 @@
+//! The suggestion engine.
+//!
+//! Every input event the front end asks: "the gesture wants the object
+//! at `intent`; where may it actually go?" [`suggest`] answers with a
+//! feasible position, a quality tag saying how faithful that position
+//! is to the intent, and the engagement state the haptic layer needs.
+//!
+//! Strategy: project the intent, sample extra candidates around it
+//! (snap targets from discrete constraints, a deterministic ring for
+//! nonconvex escapes), keep the feasible ones and rank them.
+
+use crate::constraint::{ConstraintSystem, RateLimitConstraint};
+use crate::fgstate::FGState;
+use crate::linalg::Vector;
+use crate::project::{distance_to_intersection, project_dykstra, project_single_pass, ProjectionOptions};
+use crate::rank::{rank_candidates, RankingCriteria, ScoredCandidate};
+
+/// Default cap on candidates considered per suggest call (see
+/// [`SearchPolicy`]).
+pub const MAX_CANDIDATES: usize = 32;
+/// Default radius of the ring of fallback candidates sampled around
+/// the intent (see [`SearchPolicy`]).
+pub const SEARCH_RADIUS: f64 = 48.0;
+/// Number of ring samples (spread evenly in the first two dimensions).
+const RING_SAMPLES: usize = 12;
+/// Default number of Fibonacci-sphere samples for three-dimensional
+/// states (see [`SearchPolicy::set_sphere_samples`]).
+pub const SPHERE_SAMPLES: usize = 24;
+/// Steps the boundary walk takes in each direction from the naive
+/// projection point (see [`boundary_walk_candidates`]).
+const BOUNDARY_WALK_STEPS: usize = 6;
+/// Dimension at which shell sampling gives way to coordinate descent
+/// (see [`descent_candidates`]): a shell dense enough to matter in
+/// eight dimensions would need exponentially many points.
+const DESCENT_MIN_DIM: usize = 8;
+/// Dimensions adopted together per coordinate-descent step.
+const DESCENT_BLOCK: usize = 2;
+
+/// Runtime search policy: the candidate budget and escape radius used
+/// by every suggest entry point against a system.
+///
+/// The compile-time defaults ([`MAX_CANDIDATES`], [`SEARCH_RADIUS`])
+/// suit pixel-scale canvases; CAD documents measured in millimetres
+/// and normalized-coordinate hosts both need a different radius, and
+/// embedded hosts a different candidate budget. Stored per system via
+/// [`ConstraintSystem::set_search_policy`], so call sites do not thread
+/// it through every call.
+#[derive(Debug, Clone)]
+pub struct SearchPolicy {
+    max_candidates: usize,
+    search_radius: f64,
+    sphere_samples: usize,
+    record_quality_curve: bool,
+    record_projection_trail: bool,
+    intent_cone_half_angle: Option<f64>,
+}
+
+impl Default for SearchPolicy {
+    fn default() -> Self {
+        SearchPolicy {
+            max_candidates: MAX_CANDIDATES,
+            search_radius: SEARCH_RADIUS,
+            sphere_samples: SPHERE_SAMPLES,
+            record_quality_curve: false,
+            record_projection_trail: false,
+            intent_cone_half_angle: None,
+        }
+    }
+}
+
+impl SearchPolicy {
+    /// Candidate cap per suggest call.
+    pub fn max_candidates(&self) -> usize {
+        self.max_candidates
+    }
+
+    /// Escape-ring radius, in the host's units. Also the slack scale
+    /// used when classifying engagement.
+    pub fn search_radius(&self) -> f64 {
+        self.search_radius
+    }
+
+    /// Sets the candidate cap. Panics below two — the search always
+    /// needs room for the projection and one alternative.
+    pub fn set_max_candidates(&mut self, max_candidates: usize) {
+        assert!(max_candidates >= 2, "candidate cap must be at least two");
+        self.max_candidates = max_candidates;
+    }
+
+    /// Sets the escape radius. Panics unless strictly positive and
+    /// finite.
+    pub fn set_search_radius(&mut self, search_radius: f64) {
+        assert!(
+            search_radius.is_finite() && search_radius > 0.0,
+            "search radius must be positive and finite"
+        );
+        self.search_radius = search_radius;
+    }
+
+    /// Escape-shell samples for three-dimensional states.
+    pub fn sphere_samples(&self) -> usize {
+        self.sphere_samples
+    }
+
+    /// Sets the Fibonacci-sphere sample count used for 3D states.
+    /// More samples find tighter diagonal escapes at proportional
+    /// cost; the candidate cap still truncates generation. Panics
+    /// below four — fewer cannot cover a sphere in any useful sense.
+    pub fn set_sphere_samples(&mut self, sphere_samples: usize) {
+        assert!(sphere_samples >= 4, "sphere sampling needs at least four points");
+        self.sphere_samples = sphere_samples;
+    }
+
+    /// Whether suggest calls record a [`QualityCheckpoint`] curve in
+    /// their stats.
+    pub fn record_quality_curve(&self) -> bool {
+        self.record_quality_curve
+    }
+
+    /// Opts suggest calls in (or out) of recording the quality curve.
+    /// Off by default: the curve is telemetry, not behaviour.
+    pub fn set_record_quality_curve(&mut self, record: bool) {
+        self.record_quality_curve = record;
+    }
+
+    /// Whether suggest calls record the intent projection's Dykstra
+    /// iterate trail in their stats (debug builds only).
+    pub fn record_projection_trail(&self) -> bool {
+        self.record_projection_trail
+    }
+
+    /// Opts suggest calls in (or out) of recording the intent
+    /// projection's iterate trail
+    /// ([`crate::project::project_dykstra_with_history`]) in
+    /// [`SearchStats::projection_trail`], so developer tooling can
+    /// visualize convergence for a user-reported interaction without
+    /// ad-hoc logging. Honoured in debug builds only — release builds
+    /// never pay for the clones, opted in or not.
+    pub fn set_record_projection_trail(&mut self, record: bool) {
+        self.record_projection_trail = record;
+    }
+
+    /// Half-angle of the intent cone, when one restricts the search.
+    pub fn intent_cone_half_angle(&self) -> Option<f64> {
+        self.intent_cone_half_angle
+    }
+
+    /// Restricts candidates to a cone of the given half-angle around
+    /// the direction from the current position to the intent — or
+    /// lifts the restriction with `None`. Pointer-driven flows where a
+    /// backwards suggestion is never acceptable trade completeness for
+    /// intent preservation and latency this way: out-of-cone
+    /// candidates are dropped before verification spends a projection
+    /// on them, and a call whose only feasible answers lie outside the
+    /// cone degrades to best effort rather than moving the object
+    /// where the user did not point. Panics unless the angle lies in
+    /// `(0, π]`.
+    pub fn set_intent_cone_half_angle(&mut self, half_angle: Option<f64>) {
+        if let Some(angle) = half_angle {
+            assert!(
+                angle > 0.0 && angle <= std::f64::consts::PI,
+                "cone half-angle must lie in (0, pi]"
+            );
+        }
+        self.intent_cone_half_angle = half_angle;
+    }
+}
+
+/// How faithful a suggestion is to the raw intent.
+///
+/// Ordered best-first: an earlier variant is strictly better, so
+/// `a <= b` reads "`a` is at least as good as `b`".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SuggestionQuality {
+    /// The intent itself was feasible and is returned unchanged.
+    Exact,
+    /// A feasible position was found by projection or search.
+    Projected,
+    /// A feasible position from the fast path ([`suggest_fast`]): one
+    /// closed-form projection pass, no search. A full [`suggest`] may
+    /// find something strictly better.
+    Coarse,
+    /// No feasible candidate was found within budget; the returned
+    /// position is the best available iterate and may violate
+    /// constraints.
+    BestEffort,
+}
+
+/// Machine-readable cause attached to downgraded responses, so product
+/// code can pick UX per cause — "try again with a larger budget" reads
+/// very differently from "the document is over-constrained" — instead
+/// of keying everything off [`SuggestionQuality`] alone. The ordinary
+/// convex case (the intent hit a wall and slid to its projection)
+/// carries no reason: the quality enum already says everything there
+/// is to say about it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DowngradeReason {
+    /// The candidate cap ([`SearchPolicy::max_candidates`]) cut
+    /// generation short; the same call with a larger budget may
+    /// improve the answer.
+    BudgetExhausted,
+    /// The chosen candidate sits strictly beyond the search radius
+    /// from the intent — the answer is feasible but far from what was
+    /// asked.
+    OnlyDistantCandidates,
+    /// The straight projection lost to an escape candidate (a ring
+    /// sample, snap target, seed, or interior anchor): the local
+    /// neighbourhood is nonconvex and projection alone was poor there.
+    NonconvexFallback,
+    /// No feasible candidate was found at all; the constraint
+    /// intersection is empty (or unreachable within budget) and the
+    /// position may violate constraints.
+    InfeasibleIntersection,
+}
+
+/// Counters and phase timings describing what one suggest call
+/// actually did. The counters are deterministic; the timings are
+/// wall-clock and for tuning only.
+///
+/// The phases overlap rather than partition the call: candidate
+/// generation performs projections of its own, so `generation_time`
+/// includes part of what `projection_time` isolates.
+#[derive(Debug, Clone, Default)]
+pub struct SearchStats {
+    /// Candidates generated before feasibility filtering.
+    pub candidates_generated: usize,
+    /// Candidates that survived projection as feasible.
+    pub candidates_feasible: usize,
+    /// Candidates discarded: generated but infeasible, plus ring
+    /// samples never attempted because the candidate cap was reached.
+    pub candidates_pruned: usize,
+    /// Total Dykstra sweeps spent across all projections.
+    pub projection_iterations: usize,
+    /// Sample shells explored around the intent (currently zero on the
+    /// fast path, one when the escape ring was sampled).
+    pub shells_explored: usize,
+    /// True when the candidate cap ([`SearchPolicy::max_candidates`])
+    /// cut generation short — the answer may improve with a larger
+    /// budget.
+    pub truncated: bool,
+    /// Wall-clock time spent inside projection calls.
+    pub projection_time: std::time::Duration,
+    /// Wall-clock time spent generating candidates (includes the
+    /// projections that generation performs).
+    pub generation_time: std::time::Duration,
+    /// Wall-clock time spent on feasibility verification.
+    pub verification_time: std::time::Duration,
+    /// Wall-clock time spent ranking the survivors.
+    pub ranking_time: std::time::Duration,
+    /// Best intent preservation as a function of candidates examined,
+    /// one checkpoint per improvement. Empty unless
+    /// [`SearchPolicy::set_record_quality_curve`] opted in; covers the
+    /// generation phase, which is what the candidate cap truncates.
+    pub quality_curve: Vec<QualityCheckpoint>,
+    /// Dykstra iterate trail of the intent's primary projection, the
+    /// starting point first. Empty unless
+    /// [`SearchPolicy::set_record_projection_trail`] opted in, and in
+    /// release builds always — the trail is a debugging payload, not
+    /// behaviour.
+    pub projection_trail: Vec<Vector>,
+}
+
+/// One point on the quality-vs-count curve: how good the best feasible
+/// candidate was after examining a given number of generated ones.
+/// Telemetry over these curves says where the budget stops paying —
+/// tuning [`SearchPolicy::max_candidates`] from data instead of
+/// guesswork.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QualityCheckpoint {
+    /// Candidates examined (feasible or not) when this best was
+    /// reached.
+    pub candidates_examined: usize,
+    /// Best intent preservation so far: `1 − distance/search_radius`
+    /// of the nearest feasible candidate to the intent, clamped to
+    /// `[0, 1]`.
+    pub intent_preservation: f64,
+}
+
+/// The answer to one suggest call.
+#[derive(Debug, Clone)]
+pub struct SuggestResponse {
+    /// Best feasible position (or best effort; see `quality`).
+    pub position: Vector,
+    /// Faithfulness of `position` to the intent.
+    pub quality: SuggestionQuality,
+    /// Engagement state for haptic/visual feedback.
+    pub fg: FGState,
+    /// Score of the chosen candidate under the supplied criteria.
+    pub score: f64,
+    /// Remaining ranked candidates, best first, excluding the chosen one.
+    pub alternatives: Vec<ScoredCandidate>,
+    /// Whether the position is reachable from `current` by a feasible
+    /// drag. Only populated by reachability-aware entry points
+    /// ([`crate::sweep::suggest_reachable`]); plain suggestion does not
+    /// check.
+    pub reachability: Option<crate::sweep::Reachability>,
+    /// Feasible waypoint path from `current` to `position` (both
+    /// endpoints included), for animating around obstacles instead of
+    /// jumping. Only populated by [`crate::sweep::suggest_with_path`].
+    pub path: Option<Vec<Vector>>,
+    /// Why the answer fell short of the intent, when a specific cause
+    /// is known (see [`DowngradeReason`]). `None` for exact answers,
+    /// plain convex projections, and the fast path (which does not
+    /// diagnose).
+    pub downgrade: Option<DowngradeReason>,
+    /// What the search did to produce this answer.
+    pub stats: SearchStats,
+}
+
+/// Integrity record for a suggestion that outlives the call that made
+/// it. A position persisted to a document or sent to another process
+/// is only as good as the constraint system it was checked against;
+/// the record captures which system that was (by fingerprint), how
+/// badly the position violated it (zero when feasible), and when — so
+/// the receiving side can tell a stale suggestion from a current one
+/// instead of trusting it blindly.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VerificationRecord {
+    /// Fingerprint of the constraint system's composition
+    /// ([`crate::fingerprint::fingerprint_system`]); `None` when a
+    /// constraint opts out of structural keying, in which case the
+    /// record can never [`matches`](VerificationRecord::matches).
+    pub system_fingerprint: Option<u64>,
+    /// Deepest residual constraint violation of the position, zero
+    /// when it is feasible. Nonzero only on best-effort answers.
+    pub max_residual: f64,
+    /// Wall-clock time the record was stamped.
+    pub verified_at: std::time::SystemTime,
+}
+
+impl VerificationRecord {
+    /// Whether `system` is still the composition this record
+    /// certified. Re-fingerprints and compares; an unkeyed fingerprint
+    /// never matches, not even its own system — absence of a key is
+    /// not evidence of integrity.
+    pub fn matches(&self, system: &ConstraintSystem) -> bool {
+        self.system_fingerprint.is_some()
+            && crate::fingerprint::fingerprint_system(system) == self.system_fingerprint
+    }
+}
+
+impl SuggestResponse {
+    /// Stamps a [`VerificationRecord`] for this response against
+    /// `system` — call it at persist or transmit time, against the
+    /// same system the suggestion came from. Panics on dimension
+    /// mismatch.
+    pub fn verification_record(&self, system: &ConstraintSystem) -> VerificationRecord {
+        VerificationRecord {
+            system_fingerprint: crate::fingerprint::fingerprint_system(system),
+            max_residual: system
+                .max_violation(&self.position)
+                .map_or(0.0, |(_, depth)| depth),
+            verified_at: std::time::SystemTime::now(),
+        }
+    }
+}
+
+/// Computes the best feasible position for a gesture that wants to move
+/// an object from `current` to `intent` under `system`.
+pub fn suggest(
+    system: &ConstraintSystem,
+    current: &Vector,
+    intent: &Vector,
+    criteria: &RankingCriteria,
+) -> SuggestResponse {
+    suggest_with_seeds(system, current, intent, criteria, &[])
+}
+
+/// [`suggest`] with panics and silent fallbacks surfaced as values,
+/// for untrusted input and host bindings (see [`crate::error`]).
+/// Mismatched dimensions are an error instead of a downstream panic; a
+/// best-effort answer — the engine's honest "nothing feasible found" —
+/// is [`NewtonError::Infeasible`](crate::error::NewtonError) instead
+/// of a position that quietly violates constraints.
+pub fn try_suggest(
+    system: &ConstraintSystem,
+    current: &Vector,
+    intent: &Vector,
+    criteria: &RankingCriteria,
+) -> Result<SuggestResponse, crate::error::NewtonError> {
+    use crate::error::NewtonError;
+    for point in [current, intent] {
+        if point.dim() != system.dim() {
+            return Err(NewtonError::DimensionMismatch {
+                expected: system.dim(),
+                actual: point.dim(),
+            });
+        }
+    }
+    let response = suggest(system, current, intent, criteria);
+    if response.quality == SuggestionQuality::BestEffort {
+        return Err(NewtonError::Infeasible);
+    }
+    Ok(response)
+}
+
+/// Like [`suggest`], with caller-supplied seed candidates considered
+/// alongside the engine's own. Seeds that are already feasible enter
+/// the ranking as-is; infeasible ones are projected first. Used for
+/// ephemeral snap targets such as alignment guides
+/// ([`crate::guides`]).
+pub fn suggest_with_seeds(
+    system: &ConstraintSystem,
+    current: &Vector,
+    intent: &Vector,
+    criteria: &RankingCriteria,
+    seeds: &[Vector],
+) -> SuggestResponse {
+    suggest_with_seeds_capped(
+        system,
+        current,
+        intent,
+        criteria,
+        seeds,
+        &SearchShape::full(system),
+    )
+}
+
+/// Shape of one capped search: the knobs [`suggest_with_seeds_capped`]
+/// callers adjust without touching the system's own policy. A
+/// [`FrameBudget`] shrinks the cap and loosens the options mid-frame;
+/// a [`WarmStartSession`] turns the shell phase off when its cache
+/// already covers the neighbourhood.
+struct SearchShape {
+    cap: usize,
+    options: ProjectionOptions,
+    explore_shells: bool,
+}
+
+impl SearchShape {
+    /// The full search exactly as the system's policy configures it.
+    fn full(system: &ConstraintSystem) -> Self {
+        SearchShape {
+            cap: system.search_policy().max_candidates(),
+            options: ProjectionOptions::for_system(system),
+            explore_shells: true,
+        }
+    }
+}
+
+/// The search behind [`suggest_with_seeds`], with its shape as a
+/// parameter (see [`SearchShape`]).
+fn suggest_with_seeds_capped(
+    system: &ConstraintSystem,
+    current: &Vector,
+    intent: &Vector,
+    criteria: &RankingCriteria,
+    seeds: &[Vector],
+    shape: &SearchShape,
+) -> SuggestResponse {
+    let cap = shape.cap;
+    let options = &shape.options;
+    let mut stats = SearchStats::default();
+
+    // Angular dimensions measure the short way around: re-express the
+    // intent on the branch nearest the current position, so every
+    // distance below — ranking, engagement, the Exact test — sees a
+    // 359° → 1° rotation as 2° of motion, not 358°. Results come back
+    // with angular components wrapped to `[0, 2π)`.
+    let canonical;
+    let intent = if system.angular_dims().is_empty() {
+        intent
+    } else {
+        canonical = nearest_branch(system, current, intent);
+        &canonical
+    };
+
+    // Fast path: the intent is already allowed and no seed could beat
+    // it on intent fidelity.
+    if seeds.is_empty() && system.is_feasible(intent) {
+        return SuggestResponse {
+            position: wrap_angular(system, intent.clone()),
+            quality: SuggestionQuality::Exact,
+            fg: FGState::Slack,
+            score: 0.0,
+            alternatives: Vec::new(),
+            reachability: None,
+            path: None,
+            downgrade: None,
+            stats,
+        };
+    }
+
+    let mut candidates: Vec<Vector> = Vec::new();
+    let generation_started = std::time::Instant::now();
+
+    // Primary candidate: the intent itself when allowed, else its
+    // projection. With the trail opted in (debug builds), this is the
+    // projection the trail records — the one a developer replaying a
+    // reported interaction wants to see converge.
+    let projected = if cfg!(debug_assertions) && system.search_policy().record_projection_trail() {
+        let started = std::time::Instant::now();
+        let (result, trail) =
+            crate::project::project_dykstra_with_history(system, intent, options);
+        stats.projection_time += started.elapsed();
+        stats.projection_iterations += result.iterations;
+        stats.projection_trail = trail;
+        result
+    } else {
+        timed_project(system, intent, options, &mut stats)
+    };
+    let fallback = projected.point.clone();
+    if system.is_feasible(intent) {
+        push_candidate(&mut candidates, intent.clone(), cap);
+    }
+    push_candidate(&mut candidates, projected.point, cap);
+
+    // Caller-supplied seeds (e.g. alignment guides), projected when
+    // necessary.
+    for seed in seeds {
+        if system.is_feasible(seed) {
+            push_candidate(&mut candidates, seed.clone(), cap);
+        } else {
+            let r = timed_project(system, seed, options, &mut stats);
+            push_candidate(&mut candidates, r.point, cap);
+        }
+    }
+
+    // Snap targets advertised by discrete constraints.
+    for snap in snap_candidates(system, intent) {
+        push_candidate(&mut candidates, snap, cap);
+    }
+
+    // Escape samples around the intent, for nonconvex regions where
+    // the projection lands somewhere poor. A walk along the violated
+    // obstacle's boundary first — it rounds the corners concentric
+    // shells straddle — then shells up to three dimensions and
+    // coordinate descent beyond that, where any affordable shell is
+    // vanishingly sparse. A warm start ([`WarmStartSession`]) skips
+    // this phase entirely when its cache already covers the
+    // neighbourhood.
+    let mut samples = if shape.explore_shells {
+        boundary_walk_candidates(system, intent, system.search_policy().search_radius())
+    } else {
+        Vec::new()
+    };
+    let shells = if !shape.explore_shells {
+        Vec::new()
+    } else if system.dim() >= DESCENT_MIN_DIM {
+        descent_candidates(
+            system,
+            intent,
+            system.search_policy().search_radius(),
+            options,
+            &mut stats,
+        )
+    } else {
+        stats.shells_explored = 1;
+        ring_candidates(
+            intent,
+            system.search_policy().search_radius(),
+            system.search_policy().sphere_samples(),
+        )
+    };
+    samples.extend(shells);
+    for (i, sample) in samples.iter().enumerate() {
+        if candidates.len() >= cap {
+            stats.truncated = true;
+            stats.candidates_pruned += samples.len() - i;
+            break;
+        }
+        let r = timed_project(system, sample, options, &mut stats);
+        push_candidate(&mut candidates, r.point, cap);
+    }
+    stats.generation_time = generation_started.elapsed();
+
+    stats.candidates_generated = candidates.len();
+
+    // Intent-cone restriction: hosts that never accept a backwards
+    // answer drop out-of-cone candidates here, before verification
+    // spends a projection on each (see
+    // [`SearchPolicy::set_intent_cone_half_angle`]). The rejects land
+    // in `candidates_pruned` with the infeasible ones below.
+    let cone = system
+        .search_policy()
+        .intent_cone_half_angle()
+        .and_then(|angle| intent.sub(current).normalized().map(|d| (d, angle)));
+    let in_cone = |c: &Vector| match &cone {
+        Some((direction, half_angle)) => within_intent_cone(c, current, direction, *half_angle),
+        None => true,
+    };
+    candidates.retain(&in_cone);
+
+    let verification_started = std::time::Instant::now();
+    if system.search_policy().record_quality_curve() {
+        let radius = system.search_policy().search_radius();
+        let mut best = f64::NEG_INFINITY;
+        let mut kept = Vec::with_capacity(candidates.len());
+        for (examined, c) in candidates.into_iter().enumerate() {
+            if !system.is_feasible(&c) {
+                continue;
+            }
+            let preservation = (1.0 - intent.distance(&c) / radius).clamp(0.0, 1.0);
+            if preservation > best {
+                best = preservation;
+                stats.quality_curve.push(QualityCheckpoint {
+                    candidates_examined: examined + 1,
+                    intent_preservation: preservation,
+                });
+            }
+            kept.push(c);
+        }
+        candidates = kept;
+    } else {
+        candidates.retain(|c| system.is_feasible(c));
+    }
+    stats.verification_time = verification_started.elapsed();
+    stats.candidates_feasible = candidates.len();
+    stats.candidates_pruned += stats.candidates_generated - stats.candidates_feasible;
+
+    // Badly infeasible neighbourhood: every intent-local candidate
+    // failed, so before giving up, anchor on the constraints' own
+    // interior points (see [`Constraint::interior_point`]), which sit
+    // far from the trouble by construction.
+    if candidates.is_empty() {
+        for c in system.constraints() {
+            let Some(anchor) = c.interior_point() else {
+                continue;
+            };
+            stats.candidates_generated += 1;
+            let r = timed_project(system, &anchor, options, &mut stats);
+            if system.is_feasible(&r.point) && in_cone(&r.point) {
+                push_candidate(&mut candidates, r.point, cap);
+            } else {
+                stats.candidates_pruned += 1;
+            }
+        }
+        stats.candidates_feasible = candidates.len();
+    }
+
+    if candidates.is_empty() {
+        let f = engagement_distance(system, intent, None);
+        return SuggestResponse {
+            position: wrap_angular(system, fallback),
+            quality: SuggestionQuality::BestEffort,
+            fg: FGState::classify(f, 0.0),
+            score: f64::NEG_INFINITY,
+            alternatives: Vec::new(),
+            reachability: None,
+            path: None,
+            downgrade: Some(DowngradeReason::InfeasibleIntersection),
+            stats,
+        };
+    }
+
+    // Feasible candidates may sit on any branch (constraints over
+    // angular dimensions are periodic by declaration); re-express each
+    // on the branch nearest the intent so the ranking distances wrap.
+    if !system.angular_dims().is_empty() {
+        for c in &mut candidates {
+            *c = nearest_branch(system, intent, c);
+        }
+    }
+
+    let ranking_started = std::time::Instant::now();
+    let mut ranked = rank_candidates(candidates, intent, current, Some(system), criteria);
+    stats.ranking_time = ranking_started.elapsed();
+    let best = ranked.remove(0);
+    let quality = if intent.distance(&best.position) < crate::EPSILON {
+        SuggestionQuality::Exact
+    } else {
+        SuggestionQuality::Projected
+    };
+    let f = engagement_distance(system, intent, Some(&best.position));
+    let g = (system.search_policy().search_radius() - f).max(0.0);
+    let downgrade = if quality == SuggestionQuality::Exact {
+        None
+    } else if intent.distance(&best.position) > system.search_policy().search_radius() + crate::EPSILON
+    {
+        // Strictly beyond the radius: escape-ring winners sit exactly
+        // on it and are the nonconvex case below, not this one.
+        Some(DowngradeReason::OnlyDistantCandidates)
+    } else if best.position.distance(&fallback) > crate::EPSILON {
+        Some(DowngradeReason::NonconvexFallback)
+    } else if stats.truncated {
+        Some(DowngradeReason::BudgetExhausted)
+    } else {
+        None
+    };
+    for alt in &mut ranked {
+        alt.position = wrap_angular(system, alt.position.clone());
+    }
+    SuggestResponse {
+        fg: FGState::classify(f, g),
+        position: wrap_angular(system, best.position),
+        quality,
+        score: best.score,
+        alternatives: ranked,
+        reachability: None,
+        path: None,
+        downgrade,
+        stats,
+    }
+}
+
+/// [`suggest`] against the system shrunk by `delta` (see
+/// [`ConstraintSystem::shrunk`]): every answer keeps at least that much
+/// slack inside every constraint, so the host app's own rounding cannot
+/// push it back out. Panics on a negative margin.
+pub fn suggest_robust(
+    system: &ConstraintSystem,
+    current: &Vector,
+    intent: &Vector,
+    criteria: &RankingCriteria,
+    delta: f64,
+) -> SuggestResponse {
+    suggest(&system.shrunk(delta), current, intent, criteria)
+}
+
+/// Why a floored suggestion was refused (see [`suggest_with_floor`]):
+/// the numbers a UI needs to say "can't do that" with a reason.
+#[derive(Debug, Clone)]
+pub struct Blocked {
+    /// Intent preservation of the best valid suggestion found
+    /// (`1 − distance/search_radius`, clamped to `[0, 1]`); zero when
+    /// the search found nothing feasible at all.
+    pub achieved: f64,
+    /// The floor the caller demanded.
+    pub required: f64,
+    /// The best feasible position found — what an unfloored call would
+    /// have returned — for an optional "nearest allowed" affordance.
+    /// `None` when nothing feasible was found.
+    pub nearest_valid: Option<Vector>,
+    /// The deepest-violated constraint at the intent as
+    /// `(index, depth)`, the one to name in the refusal copy. `None`
+    /// only in the degenerate case of an intent that violates nothing
+    /// (a refusal then means the search itself found nothing valid).
+    pub limiting_constraint: Option<(usize, f64)>,
+}
+
+/// [`suggest`] with a floor on intent preservation: when even the best
+/// valid answer keeps less than `min_preservation` of the intent
+/// (measured as `1 − distance/search_radius`, clamped to `[0, 1]`),
+/// the call refuses with [`Blocked`] instead of returning the
+/// alternative — some UIs would rather say "can't do that" than move
+/// the object somewhere the user visibly did not ask for. A
+/// best-effort answer is always refused; the diagnosis carries the
+/// nearest valid position and the constraint to blame. Panics unless
+/// the floor lies in `[0, 1]`.
+pub fn suggest_with_floor(
+    system: &ConstraintSystem,
+    current: &Vector,
+    intent: &Vector,
+    criteria: &RankingCriteria,
+    min_preservation: f64,
+) -> Result<SuggestResponse, Blocked> {
+    assert!(
+        (0.0..=1.0).contains(&min_preservation),
+        "preservation floor must lie in [0, 1]"
+    );
+    let response = suggest(system, current, intent, criteria);
+    let valid = response.quality != SuggestionQuality::BestEffort;
+    let achieved = if valid {
+        let radius = system.search_policy().search_radius();
+        (1.0 - intent.distance(&response.position) / radius).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    if valid && achieved >= min_preservation {
+        return Ok(response);
+    }
+    Err(Blocked {
+        achieved,
+        required: min_preservation,
+        nearest_valid: valid.then_some(response.position),
+        limiting_constraint: system.max_violation(intent),
+    })
+}
+
+/// [`suggest`] against the system with a
+/// [`LatticeConstraint`](crate::constraint::LatticeConstraint) of the
+/// given step composed onto it: every answer is representable on the
+/// output grid *and* feasible, so renderer rounding cannot invalidate
+/// it afterwards. Prefer this over rounding a plain suggestion — or
+/// pair a rounding [`SuggestionPostProcessor`] with
+/// [`suggest_post_processed`] when the grid is a preference rather
+/// than a hard requirement. Panics unless the step is strictly
+/// positive.
+pub fn suggest_quantized(
+    system: &ConstraintSystem,
+    current: &Vector,
+    intent: &Vector,
+    criteria: &RankingCriteria,
+    step: f64,
+) -> SuggestResponse {
+    let mut quantized = ConstraintSystem::new(system.dim());
+    quantized.set_search_policy(system.search_policy().clone());
+    quantized.set_numeric_policy(system.numeric_policy().clone());
+    quantized.set_angular_dims(system.angular_dims().to_vec());
+    for c in system.constraints() {
+        quantized.add_ref(c.clone());
+    }
+    quantized.add(crate::constraint::LatticeConstraint::new(system.dim(), step));
+    // Alternating projection cycles between a boundary and the grid
+    // point just beyond it, so seed the search with the grid corners
+    // surrounding the *unquantized* answer (in the first two
+    // dimensions, rounded elsewhere — the same convention as the
+    // sample ring). One of them is almost always the right cell.
+    let plain = suggest(system, current, intent, criteria);
+    let mut seeds = Vec::new();
+    let p = &plain.position;
+    let corner_axes = p.dim().min(2);
+    for mask in 0..(1_u32 << corner_axes) {
+        let mut corner = Vector::new(
+            p.as_slice()
+                .iter()
+                .map(|x| (x / step).round() * step)
+                .collect(),
+        );
+        for axis in 0..corner_axes {
+            let snapped = if mask & (1 << axis) == 0 {
+                (p.get(axis) / step).floor()
+            } else {
+                (p.get(axis) / step).ceil()
+            };
+            corner.set(axis, snapped * step);
+        }
+        seeds.push(corner);
+    }
+    suggest_with_seeds(&quantized, current, intent, criteria, &seeds)
+}
+
+/// Work budget shared by every suggest call in one frame.
+///
+/// Each plain [`suggest`] call assumes the whole candidate budget is
+/// its to spend, which is right for a single drag and wrong for a
+/// multi-select frame issuing a dozen calls: the later ones arrive
+/// after the frame's time is already gone. A `FrameBudget` is created
+/// (or [`reset`](FrameBudget::reset)) once per frame and threaded
+/// through [`suggest_budgeted`]; as wall-clock time and candidates are
+/// consumed, later calls run with a smaller candidate cap and a looser
+/// projection tolerance instead of overrunning the frame. Degradation
+/// is graceful — even an exhausted budget still projects the intent
+/// and returns a feasible answer.
+///
+/// The candidate ledger is deterministic; the time limit is a
+/// wall-clock backstop for pathological systems. Tests and replay
+/// tooling should drive degradation through the ledger alone.
+#[derive(Debug)]
+pub struct FrameBudget {
+    started: std::time::Instant,
+    time_limit: std::time::Duration,
+    candidate_limit: usize,
+    candidates_spent: usize,
+}
+
+impl FrameBudget {
+    /// A budget of `time_limit` wall-clock and four full searches'
+    /// worth of candidates.
+    pub fn new(time_limit: std::time::Duration) -> Self {
+        FrameBudget::with_candidate_limit(time_limit, 4 * MAX_CANDIDATES)
+    }
+
+    /// A budget with an explicit candidate ledger. Panics on a zero
+    /// candidate limit, which could never answer any call.
+    pub fn with_candidate_limit(
+        time_limit: std::time::Duration,
+        candidate_limit: usize,
+    ) -> Self {
+        assert!(candidate_limit > 0, "candidate limit must be positive");
+        FrameBudget {
+            started: std::time::Instant::now(),
+            time_limit,
+            candidate_limit,
+            candidates_spent: 0,
+        }
+    }
+
+    /// Fraction of the budget still available, in `[0, 1]`: the lesser
+    /// of time remaining and candidates remaining.
+    pub fn remaining_fraction(&self) -> f64 {
+        let time = 1.0 - self.started.elapsed().as_secs_f64() / self.time_limit.as_secs_f64();
+        let candidates =
+            1.0 - self.candidates_spent as f64 / self.candidate_limit as f64;
+        time.min(candidates).clamp(0.0, 1.0)
+    }
+
+    /// Whether the budget is fully spent. Further budgeted calls still
+    /// answer, at the floor of the degradation schedule.
+    pub fn exhausted(&self) -> bool {
+        self.remaining_fraction() <= 0.0
+    }
+
+    /// Candidates charged so far.
+    pub fn candidates_spent(&self) -> usize {
+        self.candidates_spent
+    }
+
+    /// Starts a new frame: restarts the clock and clears the ledger.
+    pub fn reset(&mut self) {
+        self.started = std::time::Instant::now();
+        self.candidates_spent = 0;
+    }
+}
+
+/// [`suggest`] charged against a shared per-frame budget. The emptier
+/// the budget, the fewer candidates the search may generate and the
+/// looser its projection tolerance — later calls in a busy frame get
+/// cheaper, never skipped. Every call charges at least one candidate,
+/// so a frame of fast-path hits still drains the ledger.
+pub fn suggest_budgeted(
+    system: &ConstraintSystem,
+    current: &Vector,
+    intent: &Vector,
+    criteria: &RankingCriteria,
+    budget: &mut FrameBudget,
+) -> SuggestResponse {
+    let fraction = budget.remaining_fraction();
+    let full_cap = system.search_policy().max_candidates();
+    let cap = ((full_cap as f64 * fraction).ceil() as usize).max(2);
+    let defaults = ProjectionOptions::for_system(system);
+    let options = ProjectionOptions {
+        max_iterations: ((defaults.max_iterations as f64 * fraction) as usize).max(8),
+        // Up to 10x looser when the frame is nearly spent.
+        tolerance: defaults.tolerance * (1.0 + 9.0 * (1.0 - fraction)),
+    };
+    let shape = SearchShape {
+        cap,
+        options,
+        explore_shells: true,
+    };
+    let response = suggest_with_seeds_capped(system, current, intent, criteria, &[], &shape);
+    budget.candidates_spent += response.stats.candidates_generated.max(1);
+    response
+}
+
+/// [`suggest_budgeted`] for callers that would rather skip work than
+/// degrade: an already-exhausted budget is
+/// [`NewtonError::BudgetExceeded`](crate::error::NewtonError) instead
+/// of a floor-quality answer, and the other failure modes match
+/// [`try_suggest`].
+pub fn try_suggest_budgeted(
+    system: &ConstraintSystem,
+    current: &Vector,
+    intent: &Vector,
+    criteria: &RankingCriteria,
+    budget: &mut FrameBudget,
+) -> Result<SuggestResponse, crate::error::NewtonError> {
+    use crate::error::NewtonError;
+    if budget.exhausted() {
+        return Err(NewtonError::BudgetExceeded);
+    }
+    for point in [current, intent] {
+        if point.dim() != system.dim() {
+            return Err(NewtonError::DimensionMismatch {
+                expected: system.dim(),
+                actual: point.dim(),
+            });
+        }
+    }
+    let response = suggest_budgeted(system, current, intent, criteria, budget);
+    if response.quality == SuggestionQuality::BestEffort {
+        return Err(NewtonError::Infeasible);
+    }
+    Ok(response)
+}
+
+/// Budget-free fast path for the per-pointer-event loop.
+///
+/// Where [`suggest`] runs an iterative Dykstra projection plus a
+/// candidate search, `suggest_fast` limits itself to work with a hard
+/// structural bound: one closed-form projection pass over the
+/// constraints ([`project_single_pass`]) and feasibility checks of the
+/// caller's `precomputed` candidates (snap targets, the previous
+/// frame's answer). Nothing iterates, so the worst case is linear in
+/// the constraint and candidate counts — well under 100µs at
+/// interactive scene sizes.
+///
+/// Feasible answers that were not the raw intent are marked
+/// [`SuggestionQuality::Coarse`]: the UI should treat them as
+/// provisional and let a less frequent full [`suggest`] improve them.
+/// Engagement (`fg`) is likewise computed from candidate fidelity, not
+/// the exact distance to the intersection.
+pub fn suggest_fast(
+    system: &ConstraintSystem,
+    current: &Vector,
+    intent: &Vector,
+    criteria: &RankingCriteria,
+    precomputed: &[Vector],
+) -> SuggestResponse {
+    let mut stats = SearchStats::default();
+
+    if system.is_feasible(intent) {
+        return SuggestResponse {
+            position: intent.clone(),
+            quality: SuggestionQuality::Exact,
+            fg: FGState::Slack,
+            score: 0.0,
+            alternatives: Vec::new(),
+            reachability: None,
+            path: None,
+            downgrade: None,
+            stats,
+        };
+    }
+
+    let fallback = project_single_pass(system, intent);
+    stats.projection_iterations = 1;
+    let cap = system.search_policy().max_candidates();
+    let mut candidates: Vec<Vector> = Vec::new();
+    if system.is_feasible(&fallback) {
+        push_candidate(&mut candidates, fallback.clone(), cap);
+    }
+    for c in precomputed {
+        if system.is_feasible(c) {
+            push_candidate(&mut candidates, c.clone(), cap);
+        }
+    }
+    stats.candidates_generated = 1 + precomputed.len();
+    stats.candidates_feasible = candidates.len();
+    stats.candidates_pruned = stats.candidates_generated - stats.candidates_feasible;
+
+    if candidates.is_empty() {
+        // No iterative rescue here by design; the full search owns
+        // that. Engagement uses the cheap candidate-distance proxy.
+        let f = intent.distance(&fallback);
+        return SuggestResponse {
+            position: fallback,
+            quality: SuggestionQuality::BestEffort,
+            fg: FGState::classify(f, 0.0),
+            score: f64::NEG_INFINITY,
+            alternatives: Vec::new(),
+            reachability: None,
+            path: None,
+            downgrade: Some(DowngradeReason::InfeasibleIntersection),
+            stats,
+        };
+    }
+
+    let mut ranked = rank_candidates(candidates, intent, current, Some(system), criteria);
+    let best = ranked.remove(0);
+    let f = intent.distance(&best.position);
+    let g = (system.search_policy().search_radius() - f).max(0.0);
+    SuggestResponse {
+        fg: FGState::classify(f, g),
+        position: best.position,
+        quality: SuggestionQuality::Coarse,
+        score: best.score,
+        alternatives: ranked,
+        reachability: None,
+        path: None,
+        downgrade: None,
+        stats,
+    }
+}
+
+/// The scalar outcome of [`suggest_fast_into`]: the parts of a
+/// [`SuggestResponse`] that fit without allocating. Alternatives and
+/// stats are the allocating parts, and the hosts this variant serves
+/// do not want them.
+#[derive(Debug, Clone, Copy)]
+pub struct FastOutcome {
+    /// Faithfulness of the written position to the intent.
+    pub quality: SuggestionQuality,
+    /// Engagement state for haptic/visual feedback.
+    pub fg: FGState,
+    /// Score of the chosen candidate under the supplied criteria;
+    /// negative infinity on a best-effort answer.
+    pub score: f64,
+}
+
+/// [`suggest_fast`] writing the chosen position into a caller-provided
+/// buffer and returning only scalars, so real-time hosts with strict
+/// allocation budgets can run the per-event hot path allocation-free
+/// (for states within [`Vector`]'s inline dimension; larger states
+/// still allocate inside the vector arithmetic). Candidates are
+/// scored one at a time instead of collected and ranked; the winner
+/// and the quality/engagement contract match [`suggest_fast`]. Panics
+/// when `out`'s dimension does not match the system's.
+pub fn suggest_fast_into(
+    system: &ConstraintSystem,
+    current: &Vector,
+    intent: &Vector,
+    criteria: &RankingCriteria,
+    precomputed: &[Vector],
+    out: &mut Vector,
+) -> FastOutcome {
+    if system.is_feasible(intent) {
+        out.view_mut().copy_from(&intent.view());
+        return FastOutcome {
+            quality: SuggestionQuality::Exact,
+            fg: FGState::Slack,
+            score: 0.0,
+        };
+    }
+    let fallback = project_single_pass(system, intent);
+    let score_of = |candidate: &Vector| {
+        criteria.score(&crate::rank::ScoreContext {
+            candidate,
+            intent,
+            current,
+            system: Some(system),
+        })
+    };
+    let mut best_score = f64::NEG_INFINITY;
+    let mut found = false;
+    if system.is_feasible(&fallback) {
+        best_score = score_of(&fallback);
+        out.view_mut().copy_from(&fallback.view());
+        found = true;
+    }
+    for c in precomputed {
+        if !system.is_feasible(c) {
+            continue;
+        }
+        let score = score_of(c);
+        if !found || score > best_score {
+            best_score = score;
+            out.view_mut().copy_from(&c.view());
+            found = true;
+        }
+    }
+    if !found {
+        // Same contract as the fast path: the best-effort iterate,
+        // engagement from the cheap candidate-distance proxy.
+        let f = intent.distance(&fallback);
+        out.view_mut().copy_from(&fallback.view());
+        return FastOutcome {
+            quality: SuggestionQuality::BestEffort,
+            fg: FGState::classify(f, 0.0),
+            score: f64::NEG_INFINITY,
+        };
+    }
+    let f = intent.distance(out);
+    let g = (system.search_policy().search_radius() - f).max(0.0);
+    FastOutcome {
+        quality: SuggestionQuality::Coarse,
+        fg: FGState::classify(f, g),
+        score: best_score,
+    }
+}
+
+/// Like [`suggest`], but ranks with a named profile stored on the
+/// system (see [`ConstraintSystem::set_profile`]). Returns `None` when
+/// no profile of that name exists, so callers can distinguish a typo
+/// from a deliberate default.
+pub fn suggest_with_profile(
+    system: &ConstraintSystem,
+    current: &Vector,
+    intent: &Vector,
+    profile: &str,
+) -> Option<SuggestResponse> {
+    let criteria = system.profile(profile)?;
+    Some(suggest(system, current, intent, criteria))
+}
+
+/// A host-side transform applied to a suggestion before it is
+/// returned — rounding to device pixels, clamping precision, custom
+/// snapping. Hosts that mutate positions *after* the engine returns
+/// silently break the validity contract; running the mutation through
+/// [`suggest_post_processed`] keeps re-verification inside the engine.
+///
+/// Implemented for closures, so ad-hoc transforms do not need a named
+/// type.
+pub trait SuggestionPostProcessor: Send + Sync {
+    /// The transformed position. Must not change dimension.
+    fn process(&self, position: &Vector) -> Vector;
+}
+
+impl<F> SuggestionPostProcessor for F
+where
+    F: Fn(&Vector) -> Vector + Send + Sync,
+{
+    fn process(&self, position: &Vector) -> Vector {
+        self(position)
+    }
+}
+
+/// [`suggest`], with `post` applied to the winning position and the
+/// result re-verified against the constraints. An infeasible processed
+/// position is reflected through its projection and processed once
+/// more — for quantising transforms that mirrors the input to the
+/// feasible side of the boundary, so the same rounding now lands
+/// inside. If the retry will not verify either, the engine's own
+/// position is returned unprocessed — validity outranks the host's
+/// cosmetics. Alternatives are left untouched.
+pub fn suggest_post_processed(
+    system: &ConstraintSystem,
+    current: &Vector,
+    intent: &Vector,
+    criteria: &RankingCriteria,
+    post: &dyn SuggestionPostProcessor,
+) -> SuggestResponse {
+    let mut response = suggest(system, current, intent, criteria);
+    if response.quality == SuggestionQuality::BestEffort {
+        // No feasible answer to protect; the transform gains nothing.
+        return response;
+    }
+    let processed = post.process(&response.position);
+    assert_eq!(
+        processed.dim(),
+        response.position.dim(),
+        "post-processor changed the dimension"
+    );
+    if system.is_feasible(&processed) {
+        response.position = processed;
+        return response;
+    }
+    let reprojected = project_dykstra(system, &processed, &ProjectionOptions::for_system(system));
+    response.stats.projection_iterations += reprojected.iterations;
+    let mirrored = reprojected.point.scale(2.0).sub(&processed);
+    let retried = post.process(&mirrored);
+    if system.is_feasible(&retried) {
+        response.position = retried;
+    }
+    response
+}
+
+/// How a blocked intent is resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolutionMode {
+    /// Land on the feasible point nearest the intent (the default
+    /// behaviour of [`suggest`]).
+    Project,
+    /// Remove the blocked (normal) component of the motion and keep
+    /// the tangential one, so dragging diagonally into a wall keeps
+    /// moving along the wall instead of stopping at the contact point.
+    Slide,
+}
+
+/// [`suggest`] with an explicit resolution mode.
+///
+/// `Slide` estimates the constraint normal from the intent's direct
+/// projection, strips the motion component along it, and re-projects
+/// the remaining tangential motion from `current`. Falls back to plain
+/// projection when there is no usable normal (e.g. the intent is
+/// feasible or already on the boundary).
+pub fn suggest_with_mode(
+    system: &ConstraintSystem,
+    current: &Vector,
+    intent: &Vector,
+    criteria: &RankingCriteria,
+    mode: ResolutionMode,
+) -> SuggestResponse {
+    if mode == ResolutionMode::Project || system.is_feasible(intent) {
+        return suggest(system, current, intent, criteria);
+    }
+    let options = ProjectionOptions::for_system(system);
+    let direct = project_dykstra(system, intent, &options);
+    let Some(normal) = intent.sub(&direct.point).normalized() else {
+        return suggest(system, current, intent, criteria);
+    };
+    let delta = intent.sub(current);
+    let tangential = delta.reject_from(&normal);
+    let slide_intent = current.add(&tangential);
+
+    let mut stats = SearchStats::default();
+    stats.projection_iterations += direct.iterations;
+    let (position, converged) = if system.is_feasible(&slide_intent) {
+        (slide_intent, true)
+    } else {
+        let r = project_dykstra(system, &slide_intent, &options);
+        stats.projection_iterations += r.iterations;
+        (r.point, r.converged)
+    };
+    stats.candidates_generated = 2;
+
+    if !converged || !system.is_feasible(&position) {
+        // No clean slide available; fall back to the full search.
+        return suggest(system, current, intent, criteria);
+    }
+    stats.candidates_feasible = 1;
+    let score = rank_candidates(
+        vec![position.clone()],
+        intent,
+        current,
+        Some(system),
+        criteria,
+    )[0]
+    .score;
+    let f = engagement_distance(system, intent, Some(&position));
+    let g = (system.search_policy().search_radius() - f).max(0.0);
+    SuggestResponse {
+        fg: FGState::classify(f, g),
+        position,
+        quality: SuggestionQuality::Projected,
+        score,
+        alternatives: Vec::new(),
+        reachability: None,
+        path: None,
+        downgrade: None,
+        stats,
+    }
+}
+
+/// A per-gesture session that tracks the object's last suggested state
+/// and rate-limits every frame automatically.
+///
+/// Each call to [`DragSession::suggest`] augments the document's
+/// constraint system with a [`RateLimitConstraint`] centred on the
+/// previous frame's result, so even a huge intent moves the object at
+/// most `max_change_per_frame` — and the session, not every call site,
+/// owns that bookkeeping.
+pub struct DragSession {
+    previous: Vector,
+    max_change_per_frame: f64,
+}
+
+impl DragSession {
+    /// Starts a session at the object's current position. Panics on a
+    /// negative motion budget.
+    pub fn begin(start: Vector, max_change_per_frame: f64) -> Self {
+        assert!(
+            max_change_per_frame >= 0.0,
+            "motion budget must be non-negative"
+        );
+        DragSession {
+            previous: start,
+            max_change_per_frame,
+        }
+    }
+
+    /// Where the last frame left the object.
+    pub fn position(&self) -> &Vector {
+        &self.previous
+    }
+
+    /// One rate-limited suggest step; advances the session to the
+    /// suggested position.
+    pub fn suggest(
+        &mut self,
+        system: &ConstraintSystem,
+        intent: &Vector,
+        criteria: &RankingCriteria,
+    ) -> SuggestResponse {
+        let mut limited = ConstraintSystem::new(system.dim());
+        limited.set_search_policy(system.search_policy().clone());
+        limited.set_numeric_policy(system.numeric_policy().clone());
+        limited.set_angular_dims(system.angular_dims().to_vec());
+        for c in system.constraints() {
+            limited.add_ref(c.clone());
+        }
+        limited.add(RateLimitConstraint::new(
+            self.previous.clone(),
+            self.max_change_per_frame,
+        ));
+        let response = suggest(&limited, &self.previous, intent, criteria);
+        self.previous = response.position.clone();
+        response
+    }
+}
+
+/// Two-tier suggestion refresh: a coarse answer every event, a refined
+/// one when the host has idle time.
+///
+/// [`refresh`](TwoTierSession::refresh) answers immediately via
+/// [`suggest_fast`] and records the intent as pending refinement.
+/// [`poll`](TwoTierSession::poll) — called at end of frame, on a
+/// debounce timer, or from a worker the host owns — runs the full
+/// nonconvex [`suggest`] for the *newest* pending intent and delivers
+/// the result. A later `refresh` supersedes an unpolled intent, so
+/// stale searches are never run; `poll` with nothing pending returns
+/// `None`.
+///
+/// Contract: a delivered refinement is never worse than the coarse
+/// answer it replaces — if the full search somehow produces a lower
+/// [`SuggestionQuality`] (a best-effort iterate against a coarse
+/// feasible answer, say), `poll` re-delivers the coarse response
+/// instead. UIs can therefore always adopt what `poll` returns.
+pub struct TwoTierSession {
+    pending: Option<(Vector, Vector)>,
+    coarse: Option<SuggestResponse>,
+    last_refined: Option<Vector>,
+}
+
+impl TwoTierSession {
+    pub fn new() -> Self {
+        TwoTierSession {
+            pending: None,
+            coarse: None,
+            last_refined: None,
+        }
+    }
+
+    /// Immediate coarse answer for a new intent; marks it pending
+    /// refinement, superseding any unpolled predecessor. The previous
+    /// refined position (if any) is offered to the fast path as a
+    /// precomputed candidate, so a slow-moving gesture keeps its
+    /// refined answer between polls.
+    pub fn refresh(
+        &mut self,
+        system: &ConstraintSystem,
+        current: &Vector,
+        intent: &Vector,
+        criteria: &RankingCriteria,
+    ) -> SuggestResponse {
+        let precomputed: Vec<Vector> = self.last_refined.iter().cloned().collect();
+        let response = suggest_fast(system, current, intent, criteria, &precomputed);
+        self.pending = Some((current.clone(), intent.clone()));
+        self.coarse = Some(response.clone());
+        response
+    }
+
+    /// Runs the refined search for the newest pending intent, if any.
+    /// Returns the refinement (or the coarse answer, when the
+    /// refinement would lower quality — see the type docs).
+    pub fn poll(
+        &mut self,
+        system: &ConstraintSystem,
+        criteria: &RankingCriteria,
+    ) -> Option<SuggestResponse> {
+        let (current, intent) = self.pending.take()?;
+        let refined = suggest(system, &current, &intent, criteria);
+        let coarse = self.coarse.take();
+        let delivered = match coarse {
+            Some(c) if refined.quality > c.quality => c,
+            _ => refined,
+        };
+        self.last_refined = Some(delivered.position.clone());
+        Some(delivered)
+    }
+
+    /// Whether an intent awaits refinement.
+    pub fn has_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+}
+
+impl Default for TwoTierSession {
+    fn default() -> Self {
+        TwoTierSession::new()
+    }
+}
+
+/// Warm-started suggestion across consecutive frames of a drag.
+///
+/// Consecutive pointer events see almost the same scene, yet each plain
+/// [`suggest`] call rebuilds its candidate set from nothing — and the
+/// nonconvex escape search dominates frame cost precisely when the
+/// answer barely moves. The session caches the feasible candidates of
+/// the previous call, keyed by the system's structural fingerprint
+/// ([`crate::fingerprint::fingerprint_system`]). On the next call
+/// against the same composition the survivors are re-verified and
+/// seeded into the search; when one of them still lies within the
+/// search radius of the new intent, the shell phase — the expensive
+/// part, one projection per sample — is skipped entirely, because the
+/// cache already covers the neighbourhood shells would explore. The
+/// direct projection and snap targets always run, so a warm frame
+/// never answers worse than the fast path. When the intent jumps
+/// beyond the radius the cache stops covering it and the full search
+/// runs. A fingerprint change — or an unkeyed system — drops the
+/// cache: stale candidates must never seed the wrong scene.
+pub struct WarmStartSession {
+    fingerprint: Option<u64>,
+    warm: Vec<Vector>,
+}
+
+impl WarmStartSession {
+    pub fn new() -> Self {
+        WarmStartSession {
+            fingerprint: None,
+            warm: Vec::new(),
+        }
+    }
+
+    /// Candidates currently cached from the previous call.
+    pub fn cached_candidates(&self) -> usize {
+        self.warm.len()
+    }
+
+    /// One warm-started suggest step; refills the cache from the
+    /// response's candidates.
+    pub fn suggest(
+        &mut self,
+        system: &ConstraintSystem,
+        current: &Vector,
+        intent: &Vector,
+        criteria: &RankingCriteria,
+    ) -> SuggestResponse {
+        let fingerprint = crate::fingerprint::fingerprint_system(system);
+        let mut warm: Vec<Vector> = Vec::new();
+        if fingerprint.is_some() && fingerprint == self.fingerprint {
+            warm = self
+                .warm
+                .iter()
+                .filter(|c| system.is_feasible(c))
+                .cloned()
+                .collect();
+        }
+        let response = if warm.is_empty() || system.is_feasible(intent) {
+            // Nothing usable cached, or the fast Exact path applies;
+            // either way the plain search is the right call.
+            suggest(system, current, intent, criteria)
+        } else {
+            let radius = system.search_policy().search_radius();
+            let covered = warm
+                .iter()
+                .any(|c| intent.distance(c) <= radius + system.numeric_policy().tolerance());
+            let shape = SearchShape {
+                explore_shells: !covered,
+                ..SearchShape::full(system)
+            };
+            suggest_with_seeds_capped(system, current, intent, criteria, &warm, &shape)
+        };
+        self.fingerprint = fingerprint;
+        self.warm = std::iter::once(response.position.clone())
+            .chain(response.alternatives.iter().map(|a| a.position.clone()))
+            .filter(|c| system.is_feasible(c))
+            .collect();
+        response
+    }
+}
+
+impl Default for WarmStartSession {
+    fn default() -> Self {
+        WarmStartSession::new()
+    }
+}
+
+
+/// Collects snap positions near the intent from every discrete-style
+/// constraint in the system (currently: nearest point of each
+/// [`DiscreteConstraint`](crate::constraint::DiscreteConstraint)).
+///
+/// Generation is constraint-aware: snap points that land outside the
+/// system's axis-aligned bounds (its
+/// [`BoxConstraint`](crate::constraint::BoxConstraint)s) are rejected
+/// here rather than handed to the verification pass, which would spend
+/// a full projection on each — at fine lattice spacings those rejects
+/// are most of what a naive generator produces. When the raw snap is
+/// out of bounds the source is asked again with the intent clamped
+/// into the bounds, so a lattice near the edge of the canvas still
+/// contributes its nearest *valid* grid point instead of nothing.
+pub fn snap_candidates(system: &ConstraintSystem, intent: &Vector) -> Vec<Vector> {
+    let boxes: Vec<&crate::constraint::BoxConstraint> = system
+        .constraints()
+        .iter()
+        .filter_map(|c| c.as_any().downcast_ref())
+        .collect();
+    let in_bounds = |p: &Vector| boxes.iter().all(|b| b.bounds().contains(p));
+    // Boxes are axis-aligned, so clamping through each in turn is the
+    // exact projection onto their intersection.
+    let clamped = || {
+        boxes
+            .iter()
+            .fold(intent.clone(), |p, b| b.bounds().clamp(&p))
+    };
+    let radius = system.search_policy().search_radius();
+    let mut out: Vec<Vector> = Vec::new();
+    for c in system.constraints() {
+        // A discrete set projects everything onto itself; use that
+        // rather than downcasting.
+        let mut p = c.project(intent);
+        if c.contains(&p) && !in_bounds(&p) {
+            p = c.project(&clamped());
+        }
+        if c.contains(&p)
+            && in_bounds(&p)
+            && p.distance(intent) <= radius
+            && out.iter().all(|q| q.distance(&p) > crate::EPSILON)
+        {
+            out.push(p);
+        }
+    }
+    out
+}
+
+/// Deterministic shell of escape samples around `center`: a full
+/// Fibonacci sphere for three-dimensional states, a circle in the
+/// first two dimensions otherwise (1D falls back to the two axis
+/// offsets).
+fn ring_candidates(center: &Vector, radius: f64, sphere_samples: usize) -> Vec<Vector> {
+    let dim = center.dim();
+    let mut out = Vec::new();
+    if dim == 3 {
+        // Latitude sweep with golden-angle longitude: near-uniform
+        // coverage of the whole shell, so a valid position diagonally
+        // above an obstacle is sampled as readily as a cardinal one —
+        // which a circle in the first two dimensions never finds.
+        let golden_angle = std::f64::consts::PI * (3.0 - 5.0_f64.sqrt());
+        for k in 0..sphere_samples {
+            let z = 1.0 - 2.0 * (k as f64 + 0.5) / sphere_samples as f64;
+            let ring = (1.0 - z * z).sqrt();
+            let theta = golden_angle * k as f64;
+            let mut p = center.clone();
+            p.set(0, center.get(0) + radius * ring * theta.cos());
+            p.set(1, center.get(1) + radius * ring * theta.sin());
+            p.set(2, center.get(2) + radius * z);
+            out.push(p);
+        }
+    } else if dim >= 2 {
+        for k in 0..RING_SAMPLES {
+            let theta = std::f64::consts::TAU * (k as f64) / (RING_SAMPLES as f64);
+            let mut p = center.clone();
+            p.set(0, center.get(0) + radius * theta.cos());
+            p.set(1, center.get(1) + radius * theta.sin());
+            out.push(p);
+        }
+    } else {
+        for i in 0..dim {
+            for sign in [-1.0, 1.0] {
+                let mut p = center.clone();
+                p.set(i, center.get(i) + sign * radius);
+                out.push(p);
+            }
+        }
+    }
+    out
+}
+
+/// Coordinate-descent escape samples for high-dimensional states,
+/// where shell sampling is hopeless and the engine would otherwise
+/// degrade to whatever the convex relaxation returns. Two families,
+/// both deterministic: walk from the intent toward its full projection
+/// [`DESCENT_BLOCK`] dimensions at a time, snapshotting after every
+/// block — early snapshots keep most of the intent frozen at its
+/// requested values — then per-axis escapes at the search radius, one
+/// dimension at a time, for traps the projection cannot leave.
+/// Downstream each sample runs through the same projection and
+/// feasibility checks as a shell sample.
+fn descent_candidates(
+    system: &ConstraintSystem,
+    intent: &Vector,
+    radius: f64,
+    options: &ProjectionOptions,
+    stats: &mut SearchStats,
+) -> Vec<Vector> {
+    let dim = intent.dim();
+    let mut out = Vec::new();
+    let target = timed_project(system, intent, options, stats).point;
+    let mut cursor = intent.clone();
+    for block in (0..dim).step_by(DESCENT_BLOCK) {
+        for d in block..(block + DESCENT_BLOCK).min(dim) {
+            cursor.set(d, target.get(d));
+        }
+        out.push(cursor.clone());
+    }
+    for d in 0..dim {
+        for sign in [-1.0, 1.0] {
+            let mut p = intent.clone();
+            p.set(d, intent.get(d) + sign * radius);
+            out.push(p);
+        }
+    }
+    out
+}
+
+/// Walks along the boundary of the obstacle the intent violates most,
+/// emitting candidates. Starting from the naive projection point, the
+/// walk steps tangentially in both directions on a deterministic
+/// schedule, pulling each step back toward the boundary so it rounds
+/// corners instead of flying off along a face line — which finds the
+/// nearest valid spot around a corner far more reliably than
+/// concentric shells, whose samples straddle it. Two-dimensional
+/// states only (higher dimensions have no single tangent); feasible
+/// intents and constraints without usable normals produce nothing.
+/// Downstream each candidate runs through the same projection and
+/// feasibility checks as a shell sample.
+fn boundary_walk_candidates(system: &ConstraintSystem, intent: &Vector, radius: f64) -> Vec<Vector> {
+    if intent.dim() != 2 {
+        return Vec::new();
+    }
+    let Some((index, _)) = system.max_violation(intent) else {
+        return Vec::new();
+    };
+    let obstacle = &system.constraints()[index];
+    let start = obstacle.project(intent);
+    // Stand slightly off the boundary: normals degenerate exactly on
+    // it, and the walk only needs to hug the surface, not touch it.
+    let step = radius / BOUNDARY_WALK_STEPS as f64;
+    let standoff = step / 4.0;
+    let Some(outward) = start.sub(intent).normalized() else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    for dir in [1.0, -1.0] {
+        let mut x = start.add(&outward.scale(standoff));
+        for _ in 0..BOUNDARY_WALK_STEPS {
+            // The feasible-side normal points toward the obstacle;
+            // its perpendicular is the walking direction.
+            let Some(n) = obstacle.boundary_normal(&x) else {
+                break;
+            };
+            let tangent = Vector::new(vec![-n.get(1), n.get(0)]);
+            x = x.add(&tangent.scale(dir * step));
+            // Pull the excess beyond the standoff back in, so the
+            // walk follows the surface around corners.
+            let d = obstacle.signed_distance(&x);
+            if d > standoff {
+                if let Some(n) = obstacle.boundary_normal(&x) {
+                    x = x.add(&n.scale(d - standoff));
+                }
+            }
+            out.push(x.clone());
+        }
+    }
+    out
+}
+
+/// Whether `candidate` lies within the cone of `half_angle` around
+/// `direction` from `current`. The apex counts as inside — staying
+/// put is never backwards.
+fn within_intent_cone(
+    candidate: &Vector,
+    current: &Vector,
+    direction: &Vector,
+    half_angle: f64,
+) -> bool {
+    let Some(towards) = candidate.sub(current).normalized() else {
+        return true;
+    };
+    towards.dot(direction).clamp(-1.0, 1.0).acos() <= half_angle + crate::EPSILON
+}
+
+/// `point` with each angular component re-expressed on the branch
+/// nearest `reference`, so plain Euclidean distance between the two
+/// measures the short way around the circle.
+fn nearest_branch(system: &ConstraintSystem, reference: &Vector, point: &Vector) -> Vector {
+    let mut out = point.clone();
+    for &d in system.angular_dims() {
+        out.set(
+            d,
+            reference.get(d) + crate::constraint::angle_difference(point.get(d), reference.get(d)),
+        );
+    }
+    out
+}
+
+/// `position` with each angular component wrapped back to `[0, 2π)`,
+/// the canonical range results are reported in.
+fn wrap_angular(system: &ConstraintSystem, mut position: Vector) -> Vector {
+    for &d in system.angular_dims() {
+        position.set(d, crate::constraint::wrap_angle(position.get(d)));
+    }
+    position
+}
+
+/// Engagement distance `f` for the FG pipeline: how far the intent
+/// sits from the feasible *intersection*, not from whichever candidate
+/// the ranking happened to choose. A chosen position that is feasible
+/// still tightens the estimate from above (ranking may prefer a
+/// stable candidate far from the intent, which says nothing about how
+/// blocked the gesture is). Keeps haptic/color signals consistent
+/// between the convex and nonconvex code paths.
+fn engagement_distance(
+    system: &ConstraintSystem,
+    intent: &Vector,
+    chosen_feasible: Option<&Vector>,
+) -> f64 {
+    let bound = distance_to_intersection(system, intent);
+    let mut f = bound.upper;
+    if let Some(p) = chosen_feasible {
+        f = f.min(intent.distance(p));
+    }
+    if f.is_finite() {
+        f
+    } else {
+        bound.lower
+    }
+}
+
+/// Runs one Dykstra projection, folding its cost into `stats`.
+fn timed_project(
+    system: &ConstraintSystem,
+    point: &Vector,
+    options: &ProjectionOptions,
+    stats: &mut SearchStats,
+) -> crate::project::ProjectionResult {
+    let started = std::time::Instant::now();
+    let result = project_dykstra(system, point, options);
+    stats.projection_time += started.elapsed();
+    stats.projection_iterations += result.iterations;
+    result
+}
+
+/// Appends `candidate` unless an (almost) identical one is present or
+/// the cap is reached.
+fn push_candidate(candidates: &mut Vec<Vector>, candidate: Vector, cap: usize) {
+    if candidates.len() >= cap {
+        return;
+    }
+    if candidates
+        .iter()
+        .all(|c| c.distance(&candidate) > crate::EPSILON)
+    {
+        candidates.push(candidate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bounds::Bounds;
+    use crate::constraint::{BoxConstraint, CollisionConstraint, DiscreteConstraint};
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    fn boxed(x0: f64, y0: f64, x1: f64, y1: f64) -> Bounds {
+        Bounds::new(v(x0, y0), v(x1, y1))
+    }
+
+    #[test]
+    fn feasible_intent_is_exact() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        let r = suggest(&sys, &v(10.0, 10.0), &v(50.0, 50.0), &RankingCriteria::default());
+        assert_eq!(r.quality, SuggestionQuality::Exact);
+        assert_eq!(r.position, v(50.0, 50.0));
+        assert_eq!(r.fg, FGState::Slack);
+    }
+
+    #[test]
+    fn infeasible_intent_is_projected_feasible() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        let r = suggest(&sys, &v(50.0, 50.0), &v(120.0, 50.0), &RankingCriteria::default());
+        assert_eq!(r.quality, SuggestionQuality::Projected);
+        assert!(sys.is_feasible(&r.position));
+        assert!(r.position.distance(&v(100.0, 50.0)) < 1e-6);
+    }
+
+    #[test]
+    fn stats_break_down_the_search() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        let r = suggest(&sys, &v(50.0, 50.0), &v(150.0, 50.0), &RankingCriteria::default());
+        let stats = &r.stats;
+        assert_eq!(stats.shells_explored, 1);
+        assert!(!stats.truncated);
+        assert_eq!(
+            stats.candidates_generated,
+            stats.candidates_feasible + stats.candidates_pruned
+        );
+        assert!(stats.projection_iterations > 0);
+        // All projections here happen during candidate generation, so
+        // the generation phase must cover the projection phase.
+        assert!(stats.generation_time >= stats.projection_time);
+
+        // Fast path: nothing searched, nothing counted.
+        let easy = suggest(&sys, &v(50.0, 50.0), &v(60.0, 50.0), &RankingCriteria::default());
+        assert_eq!(easy.stats.shells_explored, 0);
+        assert_eq!(easy.stats.candidates_generated, 0);
+    }
+
+    #[test]
+    fn try_suggest_surfaces_failure_as_values() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        let criteria = RankingCriteria::default();
+        let ok = try_suggest(&sys, &v(50.0, 50.0), &v(120.0, 50.0), &criteria).unwrap();
+        assert!(sys.is_feasible(&ok.position));
+
+        let wrong_dim = Vector::new(vec![1.0, 2.0, 3.0]);
+        assert_eq!(
+            try_suggest(&sys, &v(0.0, 0.0), &wrong_dim, &criteria).unwrap_err(),
+            crate::error::NewtonError::DimensionMismatch {
+                expected: 2,
+                actual: 3
+            }
+        );
+
+        // Two disjoint boxes admit no feasible point at all.
+        sys.add(BoxConstraint::new(boxed(200.0, 200.0, 300.0, 300.0)));
+        assert_eq!(
+            try_suggest(&sys, &v(50.0, 50.0), &v(150.0, 150.0), &criteria).unwrap_err(),
+            crate::error::NewtonError::Infeasible
+        );
+    }
+
+    #[test]
+    fn exhausted_budget_is_an_error_not_a_degraded_answer() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        let criteria = RankingCriteria::default();
+        let mut budget =
+            FrameBudget::with_candidate_limit(std::time::Duration::from_secs(60), 1);
+        assert!(
+            try_suggest_budgeted(&sys, &v(50.0, 50.0), &v(150.0, 50.0), &criteria, &mut budget)
+                .is_ok()
+        );
+        assert_eq!(
+            try_suggest_budgeted(&sys, &v(50.0, 50.0), &v(150.0, 50.0), &criteria, &mut budget)
+                .unwrap_err(),
+            crate::error::NewtonError::BudgetExceeded
+        );
+    }
+
+    #[test]
+    fn search_policy_caps_the_candidate_budget() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        sys.add(CollisionConstraint::new(boxed(40.0, 40.0, 60.0, 60.0)));
+        let mut policy = SearchPolicy::default();
+        policy.set_max_candidates(2);
+        let before = sys.revision();
+        sys.set_search_policy(policy);
+        assert!(sys.revision() > before, "policy changes must invalidate caches");
+        let r = suggest(&sys, &v(10.0, 50.0), &v(120.0, 50.0), &RankingCriteria::default());
+        assert!(r.stats.candidates_generated <= 2);
+        assert!(sys.is_feasible(&r.position));
+    }
+
+    #[test]
+    #[should_panic(expected = "candidate cap")]
+    fn search_policy_rejects_degenerate_cap() {
+        SearchPolicy::default().set_max_candidates(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "search radius")]
+    fn search_policy_rejects_bad_radius() {
+        SearchPolicy::default().set_search_radius(0.0);
+    }
+
+    #[test]
+    fn projection_trail_is_recorded_only_when_opted_in() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        let criteria = RankingCriteria::default();
+        let off = suggest(&sys, &v(50.0, 50.0), &v(120.0, 50.0), &criteria);
+        assert!(off.stats.projection_trail.is_empty());
+        let mut policy = SearchPolicy::default();
+        policy.set_record_projection_trail(true);
+        sys.set_search_policy(policy);
+        let on = suggest(&sys, &v(50.0, 50.0), &v(120.0, 50.0), &criteria);
+        let trail = &on.stats.projection_trail;
+        assert!(trail.len() >= 2, "trail needs the start and at least one sweep");
+        assert_eq!(trail[0], v(120.0, 50.0));
+        assert!(trail.last().unwrap().distance(&v(100.0, 50.0)) < 1e-6);
+    }
+
+    #[test]
+    fn intent_cone_refuses_backwards_suggestions() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(DiscreteConstraint::new(vec![v(10.0, 50.0)]));
+        let criteria = RankingCriteria::default();
+        // Unrestricted, the only member wins even though it lies
+        // behind the gesture.
+        let free = suggest(&sys, &v(50.0, 50.0), &v(60.0, 50.0), &criteria);
+        assert_eq!(free.position, v(10.0, 50.0));
+        let mut policy = SearchPolicy::default();
+        policy.set_intent_cone_half_angle(Some(std::f64::consts::FRAC_PI_4));
+        sys.set_search_policy(policy);
+        let coned = suggest(&sys, &v(50.0, 50.0), &v(60.0, 50.0), &criteria);
+        assert_eq!(coned.quality, SuggestionQuality::BestEffort);
+    }
+
+    #[test]
+    fn intent_cone_keeps_forward_answers() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        let mut policy = SearchPolicy::default();
+        policy.set_intent_cone_half_angle(Some(std::f64::consts::FRAC_PI_4));
+        sys.set_search_policy(policy);
+        let r = suggest(&sys, &v(50.0, 50.0), &v(120.0, 50.0), &RankingCriteria::default());
+        assert_eq!(r.quality, SuggestionQuality::Projected);
+        assert!(r.position.distance(&v(100.0, 50.0)) < 1e-6);
+    }
+
+    #[test]
+    #[should_panic(expected = "cone half-angle")]
+    fn intent_cone_rejects_degenerate_angle() {
+        SearchPolicy::default().set_intent_cone_half_angle(Some(0.0));
+    }
+
+    #[test]
+    fn fast_path_is_coarse_but_feasible() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        let r = suggest_fast(&sys, &v(50.0, 50.0), &v(150.0, 50.0), &RankingCriteria::default(), &[]);
+        assert_eq!(r.quality, SuggestionQuality::Coarse);
+        assert!(sys.is_feasible(&r.position));
+        assert!(r.position.distance(&v(100.0, 50.0)) < 1e-6);
+        // A feasible intent is still exact, same as the full search.
+        let exact = suggest_fast(&sys, &v(50.0, 50.0), &v(60.0, 50.0), &RankingCriteria::default(), &[]);
+        assert_eq!(exact.quality, SuggestionQuality::Exact);
+    }
+
+    #[test]
+    fn fast_into_matches_the_fast_path() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        let criteria = RankingCriteria::default();
+        let mut out = Vector::zeros(2);
+        let outcome = suggest_fast_into(&sys, &v(50.0, 50.0), &v(150.0, 50.0), &criteria, &[], &mut out);
+        let boxed_path = suggest_fast(&sys, &v(50.0, 50.0), &v(150.0, 50.0), &criteria, &[]);
+        assert_eq!(outcome.quality, boxed_path.quality);
+        assert_eq!(out, boxed_path.position);
+        assert_eq!(outcome.fg, boxed_path.fg);
+        assert!((outcome.score - boxed_path.score).abs() < 1e-12);
+        // The exact and best-effort branches agree too.
+        let exact = suggest_fast_into(&sys, &v(50.0, 50.0), &v(60.0, 50.0), &criteria, &[], &mut out);
+        assert_eq!(exact.quality, SuggestionQuality::Exact);
+        assert_eq!(out, v(60.0, 50.0));
+    }
+
+    #[test]
+    fn fast_into_prefers_the_best_precomputed_candidate() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(crate::constraint::HalfspaceConstraint::new(v(1.0, 1.0), 5.0));
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 10.0, 10.0)));
+        let intent = v(-10.0, 30.0);
+        let mut out = Vector::zeros(2);
+        let bare = suggest_fast_into(&sys, &v(0.0, 0.0), &intent, &RankingCriteria::default(), &[], &mut out);
+        assert_eq!(bare.quality, SuggestionQuality::BestEffort);
+        let seeded = suggest_fast_into(
+            &sys,
+            &v(0.0, 0.0),
+            &intent,
+            &RankingCriteria::default(),
+            &[v(0.0, 5.0)],
+            &mut out,
+        );
+        assert_eq!(seeded.quality, SuggestionQuality::Coarse);
+        assert_eq!(out, v(0.0, 5.0));
+    }
+
+    #[test]
+    fn fast_path_leans_on_precomputed_candidates() {
+        // A single closed-form pass breaks the halfspace after the box
+        // clamp, so without help the fast path is best-effort; a
+        // precomputed feasible candidate rescues it.
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(crate::constraint::HalfspaceConstraint::new(v(1.0, 1.0), 5.0));
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 10.0, 10.0)));
+        let intent = v(-10.0, 30.0);
+        let bare = suggest_fast(&sys, &v(0.0, 0.0), &intent, &RankingCriteria::default(), &[]);
+        assert_eq!(bare.quality, SuggestionQuality::BestEffort);
+        let seeded = suggest_fast(
+            &sys,
+            &v(0.0, 0.0),
+            &intent,
+            &RankingCriteria::default(),
+            &[v(0.0, 5.0)],
+        );
+        assert_eq!(seeded.quality, SuggestionQuality::Coarse);
+        assert_eq!(seeded.position, v(0.0, 5.0));
+    }
+
+    #[test]
+    fn two_tier_refinement_never_lowers_quality() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        sys.add(CollisionConstraint::new(boxed(40.0, 40.0, 60.0, 60.0)));
+        let criteria = RankingCriteria::default();
+        let mut session = TwoTierSession::new();
+        let coarse = session.refresh(&sys, &v(10.0, 50.0), &v(120.0, 50.0), &criteria);
+        assert!(session.has_pending());
+        let refined = session.poll(&sys, &criteria).expect("pending refinement");
+        assert!(refined.quality <= coarse.quality);
+        assert!(sys.is_feasible(&refined.position));
+        assert!(!session.has_pending());
+        assert!(session.poll(&sys, &criteria).is_none());
+    }
+
+    #[test]
+    fn two_tier_refines_only_the_newest_intent() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        let criteria = RankingCriteria::default();
+        let mut session = TwoTierSession::new();
+        session.refresh(&sys, &v(50.0, 50.0), &v(150.0, 20.0), &criteria);
+        session.refresh(&sys, &v(50.0, 50.0), &v(150.0, 80.0), &criteria);
+        let refined = session.poll(&sys, &criteria).expect("pending refinement");
+        // The superseded (y = 20) intent is never searched.
+        assert!(refined.position.distance(&v(100.0, 80.0)) < 1e-6);
+        assert!(session.poll(&sys, &criteria).is_none());
+    }
+
+    #[test]
+    fn warm_start_answers_repeat_frames_from_the_cache() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        sys.add(CollisionConstraint::new(boxed(40.0, 40.0, 60.0, 60.0)));
+        let criteria = RankingCriteria::default();
+        let mut session = WarmStartSession::new();
+        let cold = session.suggest(&sys, &v(10.0, 50.0), &v(50.0, 50.0), &criteria);
+        assert!(sys.is_feasible(&cold.position));
+        assert_eq!(cold.stats.shells_explored, 1);
+        assert!(session.cached_candidates() > 0);
+        // Same scene, same intent: the cached winner matches the
+        // distance lower bound, so no shell is generated at all.
+        let warm = session.suggest(&sys, &v(10.0, 50.0), &v(50.0, 50.0), &criteria);
+        assert_eq!(warm.position, cold.position);
+        assert_eq!(warm.stats.shells_explored, 0);
+        assert!(warm.stats.candidates_generated <= cold.stats.candidates_generated);
+    }
+
+    #[test]
+    fn warm_start_drops_the_cache_when_the_scene_changes() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        sys.add(CollisionConstraint::new(boxed(40.0, 40.0, 60.0, 60.0)));
+        let criteria = RankingCriteria::default();
+        let mut session = WarmStartSession::new();
+        session.suggest(&sys, &v(10.0, 50.0), &v(50.0, 50.0), &criteria);
+        // A new obstacle changes the fingerprint; the stale cache must
+        // not answer, and the full search runs again.
+        sys.add(CollisionConstraint::new(boxed(20.0, 40.0, 40.0, 60.0)));
+        let r = session.suggest(&sys, &v(10.0, 50.0), &v(50.0, 50.0), &criteria);
+        assert!(sys.is_feasible(&r.position));
+        assert_eq!(r.stats.shells_explored, 1);
+    }
+
+    #[test]
+    fn frame_budget_degrades_later_calls() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        // A generous time limit so only the candidate ledger drives
+        // degradation, keeping the test deterministic.
+        let mut budget =
+            FrameBudget::with_candidate_limit(std::time::Duration::from_secs(60), 30);
+        let criteria = RankingCriteria::default();
+        let first = suggest_budgeted(&sys, &v(50.0, 50.0), &v(150.0, 50.0), &criteria, &mut budget);
+        assert!(sys.is_feasible(&first.position));
+        let mut last = first.stats.candidates_generated;
+        for _ in 0..5 {
+            let r = suggest_budgeted(&sys, &v(50.0, 50.0), &v(150.0, 50.0), &criteria, &mut budget);
+            // Degradation is monotone and never refuses to answer.
+            assert!(r.stats.candidates_generated <= last);
+            assert!(sys.is_feasible(&r.position));
+            last = r.stats.candidates_generated;
+        }
+        assert!(budget.exhausted());
+        // Even at the floor the answer is a real projection.
+        let floor = suggest_budgeted(&sys, &v(50.0, 50.0), &v(150.0, 50.0), &criteria, &mut budget);
+        assert!(floor.position.distance(&v(100.0, 50.0)) < 1e-3);
+        budget.reset();
+        assert!(!budget.exhausted());
+        assert_eq!(budget.candidates_spent(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "candidate limit")]
+    fn zero_candidate_limit_is_rejected() {
+        FrameBudget::with_candidate_limit(std::time::Duration::from_millis(8), 0);
+    }
+
+    #[test]
+    fn collision_keeps_suggestion_outside_obstacle() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        sys.add(CollisionConstraint::new(boxed(40.0, 40.0, 60.0, 60.0)));
+        let r = suggest(&sys, &v(10.0, 50.0), &v(50.0, 50.0), &RankingCriteria::default());
+        assert_eq!(r.quality, SuggestionQuality::Projected);
+        assert!(sys.is_feasible(&r.position));
+    }
+
+    #[test]
+    fn interior_anchors_rescue_a_badly_infeasible_intent() {
+        // A tall corridor whose middle is walled off by an obstacle
+        // wider than the corridor: projection cycles between the two,
+        // and the search ring around the intent cannot reach past the
+        // obstacle either. Only the constraints' own interior anchors
+        // find the feasible ends of the corridor.
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(40.0, -300.0, 60.0, 300.0)));
+        sys.add(CollisionConstraint::new(boxed(0.0, -200.0, 100.0, 200.0)));
+        let r = suggest(&sys, &v(50.0, -250.0), &v(50.0, 0.0), &RankingCriteria::default());
+        assert_eq!(r.quality, SuggestionQuality::Projected);
+        assert!(sys.is_feasible(&r.position));
+    }
+
+    #[test]
+    fn robust_suggestions_keep_their_distance() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        let criteria = RankingCriteria::default();
+        // Plain suggestion lands exactly on the boundary...
+        let plain = suggest(&sys, &v(50.0, 50.0), &v(50.0, -10.0), &criteria);
+        assert!(plain.position.get(1).abs() < 1e-6);
+        // ...a robust one stays δ inside it.
+        let robust = suggest_robust(&sys, &v(50.0, 50.0), &v(50.0, -10.0), &criteria, 5.0);
+        assert!(robust.position.distance(&v(50.0, 5.0)) < 1e-4);
+        assert!(sys.margin(&robust.position) >= 5.0 - 1e-4);
+    }
+
+    #[test]
+    fn discrete_snap_wins_when_close() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(DiscreteConstraint::new(vec![v(0.0, 0.0), v(20.0, 0.0)]));
+        let r = suggest(&sys, &v(0.0, 0.0), &v(18.0, 1.0), &RankingCriteria::default());
+        assert_eq!(r.position, v(20.0, 0.0));
+        assert!(r.quality == SuggestionQuality::Projected);
+    }
+
+    #[test]
+    fn boundary_walk_rounds_the_obstacle_corner() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(CollisionConstraint::new(boxed(40.0, 40.0, 60.0, 60.0)));
+        // Intent just inside the bottom face: the walk starts under it
+        // and, within the step budget, turns the corners onto the side
+        // faces in both directions.
+        let walk = boundary_walk_candidates(&sys, &v(50.0, 42.0), SEARCH_RADIUS);
+        assert!(!walk.is_empty());
+        assert!(walk.iter().any(|p| p.get(0) < 40.0 && p.get(1) > 40.0));
+        assert!(walk.iter().any(|p| p.get(0) > 60.0 && p.get(1) > 40.0));
+        // Every emitted point hugs the surface: feasible, near the
+        // obstacle rather than out at the shell radius.
+        assert!(walk.iter().all(|p| sys.is_feasible(p)));
+        let feasible_intent = v(10.0, 10.0);
+        assert!(boundary_walk_candidates(&sys, &feasible_intent, SEARCH_RADIUS).is_empty());
+    }
+
+    #[test]
+    fn boundary_walk_finds_the_spot_around_the_corner() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        sys.add(CollisionConstraint::new(boxed(40.0, 0.0, 60.0, 60.0)));
+        // A small search radius strands every ring sample inside the
+        // wall; the boundary walk still slips around its top corner.
+        let mut policy = SearchPolicy::default();
+        policy.set_search_radius(15.0);
+        sys.set_search_policy(policy);
+        let r = suggest(&sys, &v(50.0, 70.0), &v(50.0, 50.0), &RankingCriteria::default());
+        assert!(sys.is_feasible(&r.position));
+        assert!(r.position.distance(&v(50.0, 50.0)) <= 15.0 + 1e-6);
+    }
+
+    #[test]
+    fn snap_candidates_stay_inside_the_box_bounds() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        sys.add(crate::constraint::LatticeConstraint::new(2, 10.0));
+        // The nearest lattice point to the intent is (110, 50), out of
+        // bounds; generation clamps and offers (100, 50) instead of
+        // leaving the verification pass to discard the reject.
+        let snaps = snap_candidates(&sys, &v(108.0, 50.0));
+        assert!(snaps.iter().all(|p| sys.constraints()[0].contains(p)));
+        assert!(snaps.iter().any(|p| p.distance(&v(100.0, 50.0)) < 1e-9));
+    }
+
+    #[test]
+    fn out_of_bounds_discrete_points_are_rejected_during_generation() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        sys.add(DiscreteConstraint::new(vec![v(50.0, 50.0), v(120.0, 50.0)]));
+        // (120, 50) is nearest but can never verify; it must not be
+        // generated at all.
+        let snaps = snap_candidates(&sys, &v(110.0, 50.0));
+        assert!(snaps.iter().all(|p| p.get(0) <= 100.0));
+    }
+
+    #[test]
+    fn slide_mode_preserves_tangential_motion() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(-1000.0, -1000.0, 1000.0, 10.0)));
+        let current = v(0.0, 9.0);
+        let intent = v(5.0, 15.0);
+        let criteria = RankingCriteria::default();
+        // Plain projection lands at the contact point under the intent.
+        let projected = suggest_with_mode(&sys, &current, &intent, &criteria, ResolutionMode::Project);
+        assert!(projected.position.distance(&v(5.0, 10.0)) < 1e-6);
+        // Slide strips the blocked vertical component and keeps the
+        // full horizontal motion.
+        let slid = suggest_with_mode(&sys, &current, &intent, &criteria, ResolutionMode::Slide);
+        assert!(slid.position.distance(&v(5.0, 9.0)) < 1e-6);
+    }
+
+    #[test]
+    fn engagement_reflects_the_intersection_not_the_chosen_position() {
+        // Dragging deep under the floor while sliding sideways: the
+        // chosen position sits 42 units from the intent, but the floor
+        // itself is only 40 units away. FG must be classified from the
+        // 40 (the true press into the feasible set), which keeps this
+        // in the Engaged band instead of tipping into Exact.
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        let current = v(50.0, 2.0);
+        let intent = v(90.0, -40.0);
+        let criteria = RankingCriteria::default();
+        let slid = suggest_with_mode(&sys, &current, &intent, &criteria, ResolutionMode::Slide);
+        assert!(slid.position.distance(&v(90.0, 2.0)) < 1e-6);
+        assert_eq!(slid.fg, FGState::Engaged);
+    }
+
+    #[test]
+    fn slide_mode_equals_project_when_feasible() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        let r = suggest_with_mode(
+            &sys,
+            &v(10.0, 10.0),
+            &v(20.0, 20.0),
+            &RankingCriteria::default(),
+            ResolutionMode::Slide,
+        );
+        assert_eq!(r.quality, SuggestionQuality::Exact);
+        assert_eq!(r.position, v(20.0, 20.0));
+    }
+
+    #[test]
+    fn quantized_suggestions_live_on_the_grid() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.4, 100.0)));
+        let r = suggest_quantized(
+            &sys,
+            &v(50.0, 50.0),
+            &v(120.0, 50.3),
+            &RankingCriteria::default(),
+            0.25,
+        );
+        // Feasible for the original system and a multiple of the step
+        // on every axis: the plain projection (100.4, 50.3) satisfies
+        // neither requirement on its own.
+        assert!(sys.is_feasible(&r.position));
+        for i in 0..2 {
+            let steps = r.position.get(i) / 0.25;
+            assert!((steps - steps.round()).abs() < 1e-9);
+        }
+        assert!(r.position.distance(&v(100.25, 50.25)) < 1e-6);
+    }
+
+    #[test]
+    fn post_processor_rounds_without_breaking_validity() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.5, 100.0)));
+        let round = |p: &Vector| Vector::new(p.as_slice().iter().map(|x| x.round()).collect());
+        // The projection lands on x = 100.5; naive host-side rounding
+        // to 101 would leave the canvas. The engine rounds, catches the
+        // violation, and settles on a feasible whole-pixel position.
+        let r = suggest_post_processed(
+            &sys,
+            &v(50.0, 50.0),
+            &v(120.0, 50.0),
+            &RankingCriteria::default(),
+            &round,
+        );
+        assert!(sys.is_feasible(&r.position));
+        assert_eq!(r.position, v(100.0, 50.0));
+        // A transform that keeps the position feasible passes through.
+        let r = suggest_post_processed(
+            &sys,
+            &v(50.0, 50.0),
+            &v(60.3, 50.2),
+            &RankingCriteria::default(),
+            &round,
+        );
+        assert_eq!(r.position, v(60.0, 50.0));
+    }
+
+    #[test]
+    fn session_rate_limits_every_frame() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 1000.0, 1000.0)));
+        let mut session = DragSession::begin(v(0.0, 0.0), 10.0);
+        // A huge jump is budgeted to 10 units per frame...
+        let r = session.suggest(&sys, &v(100.0, 0.0), &RankingCriteria::default());
+        assert!(r.position.distance(&v(10.0, 0.0)) < 1e-6);
+        // ...and the next frame continues from where the last ended.
+        let r = session.suggest(&sys, &v(100.0, 0.0), &RankingCriteria::default());
+        assert!(r.position.distance(&v(20.0, 0.0)) < 1e-6);
+        assert!(session.position().distance(&v(20.0, 0.0)) < 1e-6);
+    }
+
+    #[test]
+    fn seeds_can_win_over_raw_intent() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        // Favour stability enough that the nearby seed beats the
+        // feasible intent.
+        let mut criteria = RankingCriteria::default();
+        criteria.stability_weight = 2.0;
+        let seed = v(50.0, 50.0);
+        let r = suggest_with_seeds(
+            &sys,
+            &v(50.0, 50.0),
+            &v(51.0, 50.0),
+            &criteria,
+            std::slice::from_ref(&seed),
+        );
+        assert_eq!(r.position, seed);
+        assert_eq!(r.quality, SuggestionQuality::Projected);
+    }
+
+    #[test]
+    fn profiles_select_ranking_behaviour() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        let mut precision = RankingCriteria::default();
+        precision.intent_weight = 10.0;
+        precision.margin_weight = 0.0;
+        precision.stability_weight = 0.0;
+        sys.set_profile("precision-mode", precision);
+        let r = suggest_with_profile(&sys, &v(50.0, 50.0), &v(120.0, 50.0), "precision-mode")
+            .expect("profile exists");
+        assert!(sys.is_feasible(&r.position));
+        assert!(suggest_with_profile(&sys, &v(0.0, 0.0), &v(1.0, 1.0), "missing").is_none());
+    }
+
+    #[test]
+    fn stats_are_populated() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        let r = suggest(&sys, &v(50.0, 50.0), &v(150.0, 50.0), &RankingCriteria::default());
+        assert!(r.stats.candidates_generated > 0);
+        assert!(r.stats.candidates_feasible > 0);
+        assert!(r.stats.projection_iterations > 0);
+    }
+
+    #[test]
+    fn quality_curve_is_recorded_on_request() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        // Telemetry is opt-in: nothing is recorded by default.
+        let r = suggest(&sys, &v(50.0, 50.0), &v(150.0, 50.0), &RankingCriteria::default());
+        assert!(r.stats.quality_curve.is_empty());
+
+        let mut policy = SearchPolicy::default();
+        policy.set_record_quality_curve(true);
+        sys.set_search_policy(policy);
+        let r = suggest(&sys, &v(50.0, 50.0), &v(150.0, 50.0), &RankingCriteria::default());
+        assert!(!r.stats.quality_curve.is_empty());
+        // Checkpoints mark strict improvements, in examination order.
+        for pair in r.stats.quality_curve.windows(2) {
+            assert!(pair[1].candidates_examined > pair[0].candidates_examined);
+            assert!(pair[1].intent_preservation > pair[0].intent_preservation);
+        }
+        for cp in &r.stats.quality_curve {
+            assert!((0.0..=1.0).contains(&cp.intent_preservation));
+            assert!(cp.candidates_examined >= 1);
+        }
+    }
+
+    fn v3(x: f64, y: f64, z: f64) -> Vector {
+        Vector::new(vec![x, y, z])
+    }
+
+    #[test]
+    fn three_dimensional_escapes_cover_the_whole_sphere() {
+        let mut sys = ConstraintSystem::new(3);
+        sys.add(BoxConstraint::new(Bounds::new(
+            v3(0.0, 0.0, -50.0),
+            v3(100.0, 100.0, 50.0),
+        )));
+        // A solid slab overlapping the intent in x and y, wider than
+        // the search radius: the only valid positions are diagonally
+        // above it, which a circle in the first two dimensions never
+        // samples.
+        sys.add(CollisionConstraint::new(Bounds::new(
+            v3(-10.0, -10.0, -50.0),
+            v3(110.0, 50.0, 5.0),
+        )));
+        sys.add(CollisionConstraint::new(Bounds::new(
+            v3(-10.0, 50.0, -50.0),
+            v3(110.0, 110.0, 5.0),
+        )));
+        let r = suggest(&sys, &v3(50.0, 50.0, 0.0), &v3(50.0, 50.0, 0.0), &RankingCriteria::default());
+        assert_eq!(r.quality, SuggestionQuality::Projected);
+        assert!(sys.is_feasible(&r.position));
+        assert!(
+            r.position.get(2) > 5.0,
+            "escape must rise above the slab, got {:?}",
+            r.position
+        );
+    }
+
+    #[test]
+    fn sphere_sample_count_is_policy() {
+        let mut sys = ConstraintSystem::new(3);
+        sys.add(BoxConstraint::new(Bounds::new(
+            v3(0.0, 0.0, 0.0),
+            v3(100.0, 100.0, 100.0),
+        )));
+        let current = v3(50.0, 50.0, 50.0);
+        let intent = v3(150.0, 50.0, 50.0);
+        let dense = suggest(&sys, &current, &intent, &RankingCriteria::default());
+        let mut policy = SearchPolicy::default();
+        policy.set_sphere_samples(4);
+        sys.set_search_policy(policy);
+        let sparse = suggest(&sys, &current, &intent, &RankingCriteria::default());
+        assert!(sparse.stats.candidates_generated < dense.stats.candidates_generated);
+        // Both still answer with the projection onto the near face.
+        assert_eq!(dense.position, v3(100.0, 50.0, 50.0));
+        assert_eq!(sparse.position, dense.position);
+    }
+
+    #[test]
+    #[should_panic(expected = "sphere sampling needs at least four points")]
+    fn too_few_sphere_samples_are_rejected() {
+        SearchPolicy::default().set_sphere_samples(3);
+    }
+
+    fn v8(vals: [f64; 8]) -> Vector {
+        Vector::new(vals.to_vec())
+    }
+
+    #[test]
+    fn high_dimensional_search_skips_shells() {
+        let mut sys = ConstraintSystem::new(8);
+        sys.add(BoxConstraint::new(Bounds::new(
+            v8([0.0; 8]),
+            v8([100.0; 8]),
+        )));
+        // Infeasible in two dimensions only; the rest must come back
+        // untouched, which the frozen-block descent snapshots preserve.
+        let intent = v8([120.0, 120.0, 50.0, 50.0, 50.0, 50.0, 50.0, 50.0]);
+        let r = suggest(&sys, &v8([50.0; 8]), &intent, &RankingCriteria::default());
+        assert_eq!(r.quality, SuggestionQuality::Projected);
+        assert!(sys.is_feasible(&r.position));
+        assert!(r.position.distance(&v8([100.0, 100.0, 50.0, 50.0, 50.0, 50.0, 50.0, 50.0])) < 1e-6);
+        assert_eq!(r.stats.shells_explored, 0, "dims >= 8 must not shell-sample");
+    }
+
+    #[test]
+    fn per_axis_escapes_leave_a_high_dimensional_trap() {
+        let mut sys = ConstraintSystem::new(8);
+        sys.add(BoxConstraint::new(Bounds::new(
+            v8([0.0; 8]),
+            v8([100.0; 8]),
+        )));
+        // An obstacle surrounding the intent: any single-axis escape
+        // at the search radius clears it, but the relaxed projection
+        // alone may stall on a face.
+        let mut lo = [45.0; 8];
+        let mut hi = [55.0; 8];
+        lo[0] = 30.0;
+        hi[0] = 70.0;
+        sys.add(CollisionConstraint::new(Bounds::new(v8(lo), v8(hi))));
+        let r = suggest(&sys, &v8([50.0; 8]), &v8([50.0; 8]), &RankingCriteria::default());
+        assert!(sys.is_feasible(&r.position));
+    }
+
+    #[test]
+    fn verification_records_certify_and_detect_drift() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        let r = suggest(&sys, &v(50.0, 50.0), &v(120.0, 50.0), &RankingCriteria::default());
+        let record = r.verification_record(&sys);
+        assert_eq!(record.max_residual, 0.0);
+        assert!(record.matches(&sys));
+        // Any composition change — here a new constraint — invalidates
+        // the record, so a persisted suggestion reads as stale.
+        sys.add(crate::constraint::HalfspaceConstraint::new(v(1.0, 0.0), 80.0));
+        assert!(!record.matches(&sys));
+    }
+
+    #[test]
+    fn best_effort_records_carry_their_residual() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 10.0, 10.0)));
+        sys.add(BoxConstraint::new(boxed(20.0, 20.0, 30.0, 30.0)));
+        let r = suggest(&sys, &v(5.0, 5.0), &v(5.0, 5.0), &RankingCriteria::default());
+        assert_eq!(r.quality, SuggestionQuality::BestEffort);
+        let record = r.verification_record(&sys);
+        assert!(record.max_residual > 0.0, "a violating position must say so");
+    }
+
+    #[test]
+    fn unkeyed_systems_never_certify() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(crate::hull::ConvexHullConstraint::new(vec![
+            v(0.0, 0.0),
+            v(100.0, 0.0),
+            v(0.0, 100.0),
+        ]));
+        let r = suggest(&sys, &v(10.0, 10.0), &v(20.0, 20.0), &RankingCriteria::default());
+        let record = r.verification_record(&sys);
+        assert_eq!(record.system_fingerprint, None);
+        // Absence of a fingerprint is not evidence of integrity.
+        assert!(!record.matches(&sys));
+    }
+
+    #[test]
+    fn plain_convex_projection_carries_no_downgrade_reason() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        let r = suggest(&sys, &v(50.0, 50.0), &v(120.0, 50.0), &RankingCriteria::default());
+        assert_eq!(r.quality, SuggestionQuality::Projected);
+        assert_eq!(r.downgrade, None);
+        // And an exact answer never explains itself.
+        let r = suggest(&sys, &v(50.0, 50.0), &v(60.0, 50.0), &RankingCriteria::default());
+        assert_eq!(r.downgrade, None);
+    }
+
+    #[test]
+    fn infeasible_intersection_is_named_on_best_effort() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 10.0, 10.0)));
+        sys.add(BoxConstraint::new(boxed(20.0, 20.0, 30.0, 30.0)));
+        let r = suggest(&sys, &v(5.0, 5.0), &v(5.0, 5.0), &RankingCriteria::default());
+        assert_eq!(r.quality, SuggestionQuality::BestEffort);
+        assert_eq!(r.downgrade, Some(DowngradeReason::InfeasibleIntersection));
+    }
+
+    #[test]
+    fn distant_answers_are_named_as_such() {
+        // The feasible set sits far beyond the search radius: the
+        // answer is valid but nowhere near the ask.
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(200.0, 200.0, 300.0, 300.0)));
+        let r = suggest(&sys, &v(210.0, 210.0), &v(0.0, 0.0), &RankingCriteria::default());
+        assert_eq!(r.quality, SuggestionQuality::Projected);
+        assert_eq!(r.downgrade, Some(DowngradeReason::OnlyDistantCandidates));
+    }
+
+    #[test]
+    fn nonconvex_escape_is_named_when_projection_loses() {
+        // Two stacked obstacles form a solid barrier over x ∈ [40, 60];
+        // from inside it, alternating projection bounces between the
+        // two and never settles on a valid point, so the answer comes
+        // from the escape ring instead — and says so.
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(CollisionConstraint::new(boxed(40.0, 0.0, 60.0, 50.0)));
+        sys.add(CollisionConstraint::new(boxed(40.0, 50.0, 60.0, 100.0)));
+        let r = suggest(&sys, &v(50.0, 50.0), &v(50.0, 50.0), &RankingCriteria::default());
+        assert_eq!(r.quality, SuggestionQuality::Projected);
+        assert_eq!(r.downgrade, Some(DowngradeReason::NonconvexFallback));
+    }
+
+    #[test]
+    fn budget_exhaustion_is_named_when_generation_truncates() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        let mut policy = SearchPolicy::default();
+        policy.set_max_candidates(2);
+        sys.set_search_policy(policy);
+        let r = suggest(&sys, &v(50.0, 50.0), &v(120.0, 50.0), &RankingCriteria::default());
+        assert!(r.stats.truncated);
+        assert_eq!(r.downgrade, Some(DowngradeReason::BudgetExhausted));
+    }
+
+    #[test]
+    fn floor_passes_good_suggestions_through() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        // Intent 24 out of bounds against the default radius of 48:
+        // the projection keeps exactly half the intent.
+        let r = suggest_with_floor(
+            &sys,
+            &v(50.0, 50.0),
+            &v(124.0, 50.0),
+            &RankingCriteria::default(),
+            0.4,
+        )
+        .expect("0.5 preservation clears a 0.4 floor");
+        assert_eq!(r.quality, SuggestionQuality::Projected);
+        // A feasible intent clears any floor, including 1.0.
+        assert!(suggest_with_floor(
+            &sys,
+            &v(50.0, 50.0),
+            &v(60.0, 50.0),
+            &RankingCriteria::default(),
+            1.0,
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn floor_refuses_with_a_diagnosis() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 100.0, 100.0)));
+        let blocked = suggest_with_floor(
+            &sys,
+            &v(50.0, 50.0),
+            &v(124.0, 50.0),
+            &RankingCriteria::default(),
+            0.6,
+        )
+        .expect_err("0.5 preservation misses a 0.6 floor");
+        assert!((blocked.achieved - 0.5).abs() < 1e-9);
+        assert_eq!(blocked.required, 0.6);
+        // The diagnosis carries what an unfloored call would have said
+        // and which constraint is in the way.
+        assert_eq!(blocked.nearest_valid, Some(v(100.0, 50.0)));
+        let (idx, depth) = blocked.limiting_constraint.expect("intent violates the box");
+        assert_eq!(idx, 0);
+        assert!((depth - 24.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn floor_refuses_best_effort_even_at_zero() {
+        // Two disjoint boxes: nothing is feasible, so even a floor of
+        // zero refuses rather than hand back a violating position.
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(boxed(0.0, 0.0, 10.0, 10.0)));
+        sys.add(BoxConstraint::new(boxed(20.0, 20.0, 30.0, 30.0)));
+        let blocked = suggest_with_floor(
+            &sys,
+            &v(5.0, 5.0),
+            &v(5.0, 5.0),
+            &RankingCriteria::default(),
+            0.0,
+        )
+        .expect_err("an infeasible system is always blocked");
+        assert_eq!(blocked.achieved, 0.0);
+        assert_eq!(blocked.nearest_valid, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "preservation floor must lie in [0, 1]")]
+    fn floor_outside_the_unit_interval_panics() {
+        let sys = ConstraintSystem::new(2);
+        suggest_with_floor(
+            &sys,
+            &v(0.0, 0.0),
+            &v(0.0, 0.0),
+            &RankingCriteria::default(),
+            1.5,
+        )
+        .ok();
+    }
+
+    #[test]
+    fn angular_dims_measure_the_short_way_around() {
+        use crate::constraint::AngleConstraint;
+        // Heading allowed on the arc [0, π/2]; the gesture sits just
+        // inside the arc and drifts just past 0, across the wrap seam.
+        let mut sys = ConstraintSystem::new(1);
+        sys.add(AngleConstraint::new(1, 0, 0.0, std::f64::consts::FRAC_PI_2));
+        let mut policy = SearchPolicy::default();
+        policy.set_search_radius(1.0);
+        sys.set_search_policy(policy);
+        sys.set_angular_dims(vec![0]);
+        let current = Vector::new(vec![0.2]);
+        let intent = Vector::new(vec![std::f64::consts::TAU - 0.05]); // ≈ −0.05 rad
+        let r = suggest(&sys, &current, &intent, &RankingCriteria::default());
+        assert_eq!(r.quality, SuggestionQuality::Projected);
+        assert!(r.position.get(0).abs() < 1e-6, "snaps to the near arc end");
+        // 0.05 rad of resistance against a radius of 1 — not "6.23 rad
+        // of violation" measured the long way around the circle.
+        assert_eq!(r.fg, FGState::Slack);
+    }
+
+    #[test]
+    fn angular_results_wrap_to_canonical_range() {
+        let mut sys = ConstraintSystem::new(1);
+        sys.set_angular_dims(vec![0]);
+        let r = suggest(
+            &sys,
+            &Vector::new(vec![0.0]),
+            &Vector::new(vec![std::f64::consts::TAU + 0.1]),
+            &RankingCriteria::default(),
+        );
+        assert_eq!(r.quality, SuggestionQuality::Exact);
+        assert!((r.position.get(0) - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    #[should_panic(expected = "angular dimension out of range")]
+    fn angular_dims_must_exist() {
+        ConstraintSystem::new(2).set_angular_dims(vec![2]);
+    }
+}
+
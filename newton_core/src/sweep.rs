@@ -0,0 +1,353 @@
+//! Swept-motion checking.
+//!
+//! Per-point feasibility cannot see what happens *between* frames: a
+//! large single-frame delta can start on one side of a thin obstacle
+//! and land, perfectly feasibly, on the other. Sweeping samples the
+//! segment from the current position to the intent, finds the first
+//! loss of feasibility, and refines the time of contact by bisection,
+//! so fast drags stop at the obstacle they crossed instead of
+//! tunnelling through it.
+
+use crate::constraint::ConstraintSystem;
+use crate::linalg::Vector;
+use crate::rank::RankingCriteria;
+use crate::suggest::{suggest, SuggestResponse};
+
+/// Segment samples per unit of motion distance; clamped to at least
+/// [`MIN_SWEEP_SAMPLES`]. Obstacles thinner than ~1 unit can still slip
+/// between samples, which is below input resolution in practice.
+const SAMPLES_PER_UNIT: f64 = 1.0;
+const MIN_SWEEP_SAMPLES: usize = 8;
+/// Bisection refinements of the contact parameter.
+const BISECTION_STEPS: usize = 24;
+
+/// Earliest parameter `t` in `(0, 1]` at which the straight motion
+/// `from + t * (to - from)` leaves the feasible set, refined by
+/// bisection. `None` when every sample along the segment (including
+/// `to`) is feasible. A `from` that is itself infeasible reports
+/// `Some(0.0)`.
+pub fn first_blocked(system: &ConstraintSystem, from: &Vector, to: &Vector) -> Option<f64> {
+    if !system.is_feasible(from) {
+        return Some(0.0);
+    }
+    let length = from.distance(to);
+    let samples = ((length * SAMPLES_PER_UNIT).ceil() as usize).max(MIN_SWEEP_SAMPLES);
+    let at = |t: f64| from.lerp(to, t);
+    let mut last_ok = 0.0;
+    let mut first_bad = None;
+    for k in 1..=samples {
+        let t = k as f64 / samples as f64;
+        if system.is_feasible(&at(t)) {
+            last_ok = t;
+        } else {
+            first_bad = Some(t);
+            break;
+        }
+    }
+    let mut hi = first_bad?;
+    let mut lo = last_ok;
+    for _ in 0..BISECTION_STEPS {
+        let mid = (lo + hi) / 2.0;
+        if system.is_feasible(&at(mid)) {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Some(hi)
+}
+
+/// The last feasible point along the straight motion from `from`
+/// toward `to` (which is `to` itself when nothing blocks).
+pub fn sweep_clamp(system: &ConstraintSystem, from: &Vector, to: &Vector) -> Vector {
+    match first_blocked(system, from, to) {
+        None => to.clone(),
+        Some(t) => {
+            // Land on the feasible side of the refined contact.
+            let back = (t - 1e-9).max(0.0);
+            from.lerp(to, back)
+        }
+    }
+}
+
+/// [`suggest`] with swept-motion checking: the intent is first clamped
+/// to the last reachable point along the straight motion from
+/// `current`, so a fast drag cannot tunnel through a thin obstacle and
+/// come out "valid" on the far side.
+pub fn suggest_swept(
+    system: &ConstraintSystem,
+    current: &Vector,
+    intent: &Vector,
+    criteria: &RankingCriteria,
+) -> SuggestResponse {
+    let clamped = sweep_clamp(system, current, intent);
+    suggest(system, current, &clamped, criteria)
+}
+
+/// How a suggestion can be reached from the current position by a
+/// feasible continuous drag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reachability {
+    /// The straight drag from current to the suggestion stays feasible.
+    Direct,
+    /// Reachable, but only around an obstacle (a detour exists).
+    Detour,
+    /// No feasible path was found within the search budget; the
+    /// suggestion is valid but cannot be dragged to.
+    Unreachable,
+}
+
+/// Ring sizes tried when probing for a one-waypoint detour, as
+/// multiples of the motion length.
+const DETOUR_RADII: [f64; 3] = [0.5, 1.0, 2.0];
+const DETOUR_SAMPLES: usize = 8;
+
+/// True if the straight motion from `from` to `to` stays feasible.
+pub fn segment_feasible(system: &ConstraintSystem, from: &Vector, to: &Vector) -> bool {
+    first_blocked(system, from, to).is_none()
+}
+
+/// Classifies how `to` can be reached from `from`: directly, via a
+/// single-waypoint detour (probed on deterministic rings around the
+/// midpoint), or not at all within the budget.
+pub fn reachability(system: &ConstraintSystem, from: &Vector, to: &Vector) -> Reachability {
+    if segment_feasible(system, from, to) {
+        return Reachability::Direct;
+    }
+    if detour_waypoint(system, from, to).is_some() {
+        return Reachability::Detour;
+    }
+    Reachability::Unreachable
+}
+
+/// A waypoint `w` such that `from → w → to` is fully feasible, if one
+/// of the probe rings contains one.
+pub(crate) fn detour_waypoint(
+    system: &ConstraintSystem,
+    from: &Vector,
+    to: &Vector,
+) -> Option<Vector> {
+    let dim = from.dim();
+    if dim < 2 {
+        return None;
+    }
+    let mid = from.lerp(to, 0.5);
+    let length = from.distance(to).max(1.0);
+    for scale in DETOUR_RADII {
+        let radius = length * scale;
+        for k in 0..DETOUR_SAMPLES {
+            let theta = std::f64::consts::TAU * (k as f64) / (DETOUR_SAMPLES as f64);
+            let mut w = mid.clone();
+            w.set(0, mid.get(0) + radius * theta.cos());
+            w.set(1, mid.get(1) + radius * theta.sin());
+            if system.is_feasible(&w)
+                && segment_feasible(system, from, &w)
+                && segment_feasible(system, &w, to)
+            {
+                return Some(w);
+            }
+        }
+    }
+    None
+}
+
+/// [`suggest`] with a reachability guarantee: the response is annotated
+/// with how its position can be dragged to, and when the best candidate
+/// is unreachable the search falls back to the best *reachable*
+/// alternative before giving up and reporting
+/// [`Reachability::Unreachable`].
+pub fn suggest_reachable(
+    system: &ConstraintSystem,
+    current: &Vector,
+    intent: &Vector,
+    criteria: &RankingCriteria,
+) -> SuggestResponse {
+    let mut response = suggest(system, current, intent, criteria);
+    let r = reachability(system, current, &response.position);
+    if r != Reachability::Unreachable {
+        response.reachability = Some(r);
+        return response;
+    }
+    // Prefer a reachable alternative over an unreachable optimum.
+    for (i, alt) in response.alternatives.iter().enumerate() {
+        let ar = reachability(system, current, &alt.position);
+        if ar != Reachability::Unreachable {
+            let alt = response.alternatives.remove(i);
+            response.position = alt.position;
+            response.score = alt.score;
+            response.quality = crate::suggest::SuggestionQuality::Projected;
+            response.reachability = Some(ar);
+            return response;
+        }
+    }
+    response.reachability = Some(Reachability::Unreachable);
+    response
+}
+
+/// Maximum recursive splits when building a waypoint path; bounds the
+/// path to a handful of segments ("short" by construction).
+const MAX_PATH_DEPTH: usize = 3;
+
+/// A feasible polyline from `from` to `to`, both endpoints included.
+/// Direct motions yield the two endpoints; blocked ones are split
+/// around a detour waypoint recursively, up to [`MAX_PATH_DEPTH`]
+/// levels. `None` when no path is found within that budget.
+pub fn waypoint_path(system: &ConstraintSystem, from: &Vector, to: &Vector) -> Option<Vec<Vector>> {
+    let mut path = vec![from.clone()];
+    extend_path(system, from, to, MAX_PATH_DEPTH, &mut path)?;
+    Some(path)
+}
+
+/// Appends everything after `from` (up to and including `to`) to
+/// `path`, splitting around detour waypoints as needed.
+fn extend_path(
+    system: &ConstraintSystem,
+    from: &Vector,
+    to: &Vector,
+    depth: usize,
+    path: &mut Vec<Vector>,
+) -> Option<()> {
+    if segment_feasible(system, from, to) {
+        path.push(to.clone());
+        return Some(());
+    }
+    if depth == 0 {
+        return None;
+    }
+    let w = detour_waypoint(system, from, to)?;
+    // The waypoint guarantees both legs are feasible, but recurse
+    // anyway so the structure stays correct if that ever changes.
+    extend_path(system, from, &w, depth - 1, path)?;
+    extend_path(system, &w, to, depth - 1, path)
+}
+
+/// [`suggest_reachable`] that additionally returns the waypoint path
+/// itself, so the host app can animate the object around the obstacle
+/// instead of jumping. The path starts at `current` and ends at the
+/// response's position; for direct motions it is just those two points.
+pub fn suggest_with_path(
+    system: &ConstraintSystem,
+    current: &Vector,
+    intent: &Vector,
+    criteria: &RankingCriteria,
+) -> SuggestResponse {
+    let mut response = suggest_reachable(system, current, intent, criteria);
+    if response.reachability != Some(Reachability::Unreachable) {
+        response.path = waypoint_path(system, current, &response.position);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bounds::Bounds;
+    use crate::constraint::CollisionConstraint;
+    use crate::suggest::SuggestionQuality;
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    fn thin_wall() -> ConstraintSystem {
+        let mut sys = ConstraintSystem::new(2);
+        // A wall half a unit thick at x = 10, ten units tall.
+        sys.add(CollisionConstraint::new(Bounds::new(
+            v(10.0, -5.0),
+            v(10.5, 5.0),
+        )));
+        sys
+    }
+
+    #[test]
+    fn plain_suggest_tunnels_swept_does_not() {
+        let sys = thin_wall();
+        let current = v(0.0, 0.0);
+        let intent = v(20.0, 0.0);
+        // The far side is feasible, so plain suggest happily teleports.
+        let plain = suggest(&sys, &current, &intent, &RankingCriteria::default());
+        assert_eq!(plain.position, intent);
+        // Swept motion stops at the near face of the wall.
+        let swept = suggest_swept(&sys, &current, &intent, &RankingCriteria::default());
+        assert!(swept.position.get(0) <= 10.0 + 1e-6);
+        assert!(swept.position.get(0) > 9.9, "stopped too early: {swept:?}");
+        assert_eq!(swept.quality, SuggestionQuality::Exact);
+    }
+
+    #[test]
+    fn unobstructed_motion_is_untouched() {
+        let sys = thin_wall();
+        let current = v(0.0, 0.0);
+        let intent = v(5.0, 5.0);
+        assert!(first_blocked(&sys, &current, &intent).is_none());
+        let swept = suggest_swept(&sys, &current, &intent, &RankingCriteria::default());
+        assert_eq!(swept.position, intent);
+    }
+
+    #[test]
+    fn contact_parameter_is_refined() {
+        let sys = thin_wall();
+        let t = first_blocked(&sys, &v(0.0, 0.0), &v(20.0, 0.0)).unwrap();
+        // Contact at x = 10 → t = 0.5 on a 20-unit motion.
+        assert!((t - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn reachability_classifies_direct_and_detour() {
+        let sys = thin_wall();
+        // Motion that never meets the wall is direct.
+        assert_eq!(
+            reachability(&sys, &v(0.0, 0.0), &v(5.0, 0.0)),
+            Reachability::Direct
+        );
+        // The wall is finite (|y| <= 100): the far side is reachable
+        // around its end.
+        assert_eq!(
+            reachability(&sys, &v(0.0, 0.0), &v(20.0, 0.0)),
+            Reachability::Detour
+        );
+    }
+
+    #[test]
+    fn suggest_reachable_annotates() {
+        let sys = thin_wall();
+        let r = suggest_reachable(&sys, &v(0.0, 0.0), &v(20.0, 0.0), &RankingCriteria::default());
+        assert_eq!(r.position, v(20.0, 0.0));
+        assert_eq!(r.reachability, Some(Reachability::Detour));
+        let r = suggest_reachable(&sys, &v(0.0, 0.0), &v(5.0, 0.0), &RankingCriteria::default());
+        assert_eq!(r.reachability, Some(Reachability::Direct));
+    }
+
+    #[test]
+    fn waypoint_path_routes_around_the_wall() {
+        let sys = thin_wall();
+        let from = v(0.0, 0.0);
+        let to = v(20.0, 0.0);
+        let path = waypoint_path(&sys, &from, &to).expect("path exists");
+        assert_eq!(path.first().unwrap(), &from);
+        assert_eq!(path.last().unwrap(), &to);
+        assert!(path.len() >= 3, "blocked motion needs a waypoint: {path:?}");
+        for pair in path.windows(2) {
+            assert!(segment_feasible(&sys, &pair[0], &pair[1]));
+        }
+        // Unblocked motion is just the two endpoints.
+        let direct = waypoint_path(&sys, &from, &v(5.0, 0.0)).unwrap();
+        assert_eq!(direct, vec![from.clone(), v(5.0, 0.0)]);
+    }
+
+    #[test]
+    fn suggest_with_path_returns_an_animatable_path() {
+        let sys = thin_wall();
+        let r = suggest_with_path(&sys, &v(0.0, 0.0), &v(20.0, 0.0), &RankingCriteria::default());
+        assert_eq!(r.reachability, Some(Reachability::Detour));
+        let path = r.path.expect("detour carries a path");
+        assert_eq!(path.last().unwrap(), &r.position);
+        assert!(path.len() >= 3);
+    }
+
+    #[test]
+    fn infeasible_start_reports_immediately() {
+        let sys = thin_wall();
+        assert_eq!(first_blocked(&sys, &v(10.2, 0.0), &v(20.0, 0.0)), Some(0.0));
+    }
+}
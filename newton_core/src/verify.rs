@@ -0,0 +1,255 @@
+//! Exact-arithmetic certification of feasibility.
+//!
+//! Floating-point membership checks carry an [`crate::EPSILON`] slack,
+//! which is right for interactive use and wrong for audits: a
+//! suggestion sitting within `1e-9` of a boundary is reported feasible
+//! whether it is on the good side or not. High-assurance hosts want a
+//! yes/no that cannot be a rounding artifact.
+//!
+//! This module re-checks membership in `i64` fixed-point with
+//! [`FRAC_BITS`] fractional bits. Every input — coordinates and
+//! constraint parameters alike — must be *exactly* representable on
+//! that grid (integers, halves, 1/1048576ths, …); anything else yields
+//! [`Certificate::Unsupported`] rather than a silently rounded answer.
+//! Within the grid, comparisons and dot products (accumulated in
+//! `i128`) are exact, so [`Certificate::Satisfied`] and
+//! [`Certificate::Violated`] are certificates about the ideal
+//! geometry, with no epsilon anywhere.
+//!
+//! Constraint types without an exact formulation here (hulls, angular
+//! arcs, rate limits, …) also report `Unsupported`; the caller falls
+//! back to the floating-point verdict knowing it is approximate.
+
+use crate::constraint::{
+    BoxConstraint, CollisionConstraint, Constraint, ConstraintSystem, DiscreteConstraint,
+    HalfspaceConstraint, LatticeConstraint,
+};
+use crate::linalg::Vector;
+
+/// Fractional bits of the fixed-point grid: values are integer
+/// multiples of 2⁻²⁰.
+pub const FRAC_BITS: u32 = 20;
+
+/// Outcome of an exact membership check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Certificate {
+    /// Exactly satisfied — not a rounding artifact.
+    Satisfied,
+    /// Exactly violated, even if the float check would have passed it
+    /// inside its epsilon slack.
+    Violated,
+    /// No exact answer: a value is off the fixed-point grid or a
+    /// constraint type has no exact formulation.
+    Unsupported,
+}
+
+/// `x` as a fixed-point integer, or `None` when `x` is not exactly
+/// representable with [`FRAC_BITS`] fractional bits.
+fn to_fixed(x: f64) -> Option<i64> {
+    if !x.is_finite() {
+        return None;
+    }
+    // Scaling by a power of two is exact in binary floating point, so
+    // a fractional result genuinely means the value is off the grid.
+    let scaled = x * (1u64 << FRAC_BITS) as f64;
+    if scaled.fract() != 0.0 || scaled < i64::MIN as f64 || scaled > i64::MAX as f64 {
+        return None;
+    }
+    Some(scaled as i64)
+}
+
+/// All components of `v` on the fixed-point grid, or `None`.
+fn vector_fixed(v: &Vector) -> Option<Vec<i64>> {
+    v.as_slice().iter().map(|&x| to_fixed(x)).collect()
+}
+
+fn from_bool(satisfied: bool) -> Certificate {
+    if satisfied {
+        Certificate::Satisfied
+    } else {
+        Certificate::Violated
+    }
+}
+
+/// Whether `point` lies in `[min, max]` component-wise, exactly.
+fn in_box(point: &[i64], min: &[i64], max: &[i64]) -> bool {
+    point
+        .iter()
+        .zip(min.iter().zip(max))
+        .all(|(x, (lo, hi))| lo <= x && x <= hi)
+}
+
+/// Exact membership check for one constraint. Panics on dimension
+/// mismatch, like the float-side [`Constraint::contains`].
+pub fn certify_constraint(constraint: &dyn Constraint, point: &Vector) -> Certificate {
+    assert_eq!(
+        constraint.dim(),
+        point.dim(),
+        "dimension mismatch in certify_constraint"
+    );
+    let Some(p) = vector_fixed(point) else {
+        return Certificate::Unsupported;
+    };
+    let any = constraint.as_any();
+    if let Some(b) = any.downcast_ref::<BoxConstraint>() {
+        let (Some(min), Some(max)) = (
+            vector_fixed(b.bounds().min()),
+            vector_fixed(b.bounds().max()),
+        ) else {
+            return Certificate::Unsupported;
+        };
+        from_bool(in_box(&p, &min, &max))
+    } else if let Some(o) = any.downcast_ref::<CollisionConstraint>() {
+        let (Some(min), Some(max), Some(margin)) = (
+            vector_fixed(o.obstacle().min()),
+            vector_fixed(o.obstacle().max()),
+            to_fixed(o.margin()),
+        ) else {
+            return Certificate::Unsupported;
+        };
+        let min: Vec<i64> = min.iter().map(|x| x - margin).collect();
+        let max: Vec<i64> = max.iter().map(|x| x + margin).collect();
+        from_bool(!in_box(&p, &min, &max))
+    } else if let Some(h) = any.downcast_ref::<HalfspaceConstraint>() {
+        let (Some(n), Some(offset)) = (vector_fixed(h.normal()), to_fixed(h.offset())) else {
+            return Certificate::Unsupported;
+        };
+        // The dot product lives at 2·FRAC_BITS fractional bits; lift
+        // the offset to match. i128 holds both without overflow.
+        let dot: i128 = n.iter().zip(&p).map(|(&a, &b)| a as i128 * b as i128).sum();
+        from_bool(dot <= (offset as i128) << FRAC_BITS)
+    } else if let Some(d) = any.downcast_ref::<DiscreteConstraint>() {
+        for allowed in d.points() {
+            match vector_fixed(allowed) {
+                Some(a) => {
+                    if a == p {
+                        return Certificate::Satisfied;
+                    }
+                }
+                None => return Certificate::Unsupported,
+            }
+        }
+        Certificate::Violated
+    } else if let Some(l) = any.downcast_ref::<LatticeConstraint>() {
+        let Some(step) = to_fixed(l.step()) else {
+            return Certificate::Unsupported;
+        };
+        from_bool(p.iter().all(|x| x % step == 0))
+    } else {
+        Certificate::Unsupported
+    }
+}
+
+/// Exact feasibility check against every constraint in `system`.
+///
+/// Any exactly violated constraint yields [`Certificate::Violated`];
+/// otherwise one unsupported check makes the whole answer
+/// [`Certificate::Unsupported`], since "satisfied" must mean *every*
+/// constraint was certified.
+pub fn certify(system: &ConstraintSystem, point: &Vector) -> Certificate {
+    let mut verdict = Certificate::Satisfied;
+    for c in system.constraints() {
+        match certify_constraint(c.as_ref(), point) {
+            Certificate::Violated => return Certificate::Violated,
+            Certificate::Unsupported => verdict = Certificate::Unsupported,
+            Certificate::Satisfied => {}
+        }
+    }
+    verdict
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bounds::Bounds;
+
+    fn v(x: f64, y: f64) -> Vector {
+        Vector::new(vec![x, y])
+    }
+
+    #[test]
+    fn boundary_answers_are_exact_where_floats_are_not() {
+        let b = BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(10.0, 10.0)));
+        // One grid step past the edge is exactly outside; the edge
+        // itself is exactly inside.
+        let step = 1.0 / (1u64 << FRAC_BITS) as f64;
+        let outside = v(10.0 + step, 5.0);
+        assert_eq!(certify_constraint(&b, &outside), Certificate::Violated);
+        assert_eq!(
+            certify_constraint(&b, &v(10.0, 5.0)),
+            Certificate::Satisfied
+        );
+    }
+
+    #[test]
+    fn halfspace_dot_products_are_exact() {
+        let h = HalfspaceConstraint::new(v(1.0, 1.0), 10.0);
+        assert_eq!(
+            certify_constraint(&h, &v(5.0, 5.0)),
+            Certificate::Satisfied
+        );
+        let step = 1.0 / (1u64 << FRAC_BITS) as f64;
+        assert_eq!(
+            certify_constraint(&h, &v(5.0, 5.0 + step)),
+            Certificate::Violated
+        );
+    }
+
+    #[test]
+    fn off_grid_values_are_unsupported_not_rounded() {
+        let b = BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(10.0, 10.0)));
+        // 0.1 has no finite binary expansion.
+        assert_eq!(
+            certify_constraint(&b, &v(0.1, 5.0)),
+            Certificate::Unsupported
+        );
+        let off_grid = BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(0.1, 0.1)));
+        assert_eq!(
+            certify_constraint(&off_grid, &v(0.0, 0.0)),
+            Certificate::Unsupported
+        );
+    }
+
+    #[test]
+    fn discrete_and_lattice_membership_is_exact() {
+        let d = DiscreteConstraint::new(vec![v(1.0, 2.0), v(3.0, 4.0)]);
+        assert_eq!(
+            certify_constraint(&d, &v(3.0, 4.0)),
+            Certificate::Satisfied
+        );
+        assert_eq!(
+            certify_constraint(&d, &v(3.0, 4.5)),
+            Certificate::Violated
+        );
+        let l = LatticeConstraint::new(2, 0.25);
+        assert_eq!(
+            certify_constraint(&l, &v(1.75, -0.5)),
+            Certificate::Satisfied
+        );
+        assert_eq!(
+            certify_constraint(&l, &v(1.75, 0.3125)),
+            Certificate::Violated
+        );
+    }
+
+    #[test]
+    fn system_verdict_is_conservative() {
+        let mut sys = ConstraintSystem::new(2);
+        sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(10.0, 10.0))));
+        sys.add(CollisionConstraint::new(Bounds::new(
+            v(4.0, 4.0),
+            v(6.0, 6.0),
+        )));
+        assert_eq!(certify(&sys, &v(1.0, 1.0)), Certificate::Satisfied);
+        assert_eq!(certify(&sys, &v(5.0, 5.0)), Certificate::Violated);
+        // An unsupported constraint taints "satisfied" but cannot mask
+        // a certified violation.
+        sys.add(crate::hull::ConvexHullConstraint::new(vec![
+            v(0.0, 0.0),
+            v(20.0, 0.0),
+            v(0.0, 20.0),
+        ]));
+        assert_eq!(certify(&sys, &v(1.0, 1.0)), Certificate::Unsupported);
+        assert_eq!(certify(&sys, &v(5.0, 5.0)), Certificate::Violated);
+    }
+}
@@ -0,0 +1,114 @@
+//! The suggest contract, checked over generated constraint systems.
+//!
+//! The unit tests pin individual behaviours; this suite sweeps a
+//! deterministic pseudo-random family of mixed convex/nonconvex
+//! systems — each built around an anchor the generator guarantees
+//! feasible — and asserts the properties every caller relies on:
+//! non-best-effort answers satisfy every constraint, identical inputs
+//! give identical outputs, and recorded intent preservation stays in
+//! `[0, 1]`. The crate takes no test dependencies, so generation is a
+//! seeded linear congruential sweep rather than a proptest shrink
+//! loop; a failing seed reproduces by construction.
+
+use newton_core::constraint::{BoxConstraint, CollisionConstraint, HalfspaceConstraint};
+use newton_core::suggest::SearchPolicy;
+use newton_core::{
+    suggest, Bounds, ConstraintSystem, RankingCriteria, SuggestionQuality, Vector,
+};
+
+/// Deterministic pseudo-random stream (64-bit LCG, top bits taken).
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> f64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) as f64 / (1u64 << 31) as f64
+    }
+
+    fn range(&mut self, lo: f64, hi: f64) -> f64 {
+        lo + (hi - lo) * self.next()
+    }
+}
+
+fn v(x: f64, y: f64) -> Vector {
+    Vector::new(vec![x, y])
+}
+
+/// The anchor every generated system is built to keep feasible.
+fn anchor() -> Vector {
+    v(50.0, 50.0)
+}
+
+/// A canvas box, up to three halfspaces sloped to leave slack at the
+/// anchor, and up to two collision obstacles kept clear of it.
+fn generated_system(rng: &mut Lcg) -> ConstraintSystem {
+    let mut sys = ConstraintSystem::new(2);
+    let mut policy = SearchPolicy::default();
+    policy.set_record_quality_curve(true);
+    sys.set_search_policy(policy);
+    sys.add(BoxConstraint::new(Bounds::new(v(0.0, 0.0), v(100.0, 100.0))));
+    let halfspaces = (rng.next() * 4.0) as usize;
+    for _ in 0..halfspaces {
+        let normal = v(rng.range(-1.0, 1.0), rng.range(-1.0, 1.0));
+        if normal.norm() < 1e-3 {
+            continue;
+        }
+        // normal · anchor + slack: the anchor stays strictly inside.
+        let offset = normal.dot(&anchor()) + rng.range(5.0, 40.0);
+        sys.add(HalfspaceConstraint::new(normal, offset));
+    }
+    let obstacles = (rng.next() * 3.0) as usize;
+    for _ in 0..obstacles {
+        let min = v(rng.range(0.0, 80.0), rng.range(0.0, 80.0));
+        let size = v(rng.range(5.0, 20.0), rng.range(5.0, 20.0));
+        let b = Bounds::new(min.clone(), min.add(&size));
+        if b.contains(&anchor()) {
+            continue;
+        }
+        sys.add(CollisionConstraint::new(b));
+    }
+    sys
+}
+
+#[test]
+fn suggest_contract_holds_over_generated_systems() {
+    let mut rng = Lcg(0x9E37_79B9_7F4A_7C15);
+    let criteria = RankingCriteria::default();
+    let mut solved = 0;
+    for case in 0..200 {
+        let sys = generated_system(&mut rng);
+        let current = v(rng.range(-20.0, 120.0), rng.range(-20.0, 120.0));
+        let intent = v(rng.range(-20.0, 120.0), rng.range(-20.0, 120.0));
+
+        let a = suggest(&sys, &current, &intent, &criteria);
+        let b = suggest(&sys, &current, &intent, &criteria);
+        assert_eq!(a.position, b.position, "nondeterministic case {case}");
+        assert_eq!(a.quality, b.quality, "nondeterministic case {case}");
+
+        if a.quality != SuggestionQuality::BestEffort {
+            assert!(
+                sys.is_feasible(&a.position),
+                "case {case}: {:?} returned an infeasible position",
+                a.quality
+            );
+            solved += 1;
+        }
+        if a.quality == SuggestionQuality::Exact {
+            assert!(a.position.distance(&intent) < 1e-9, "case {case}");
+        }
+        for cp in &a.stats.quality_curve {
+            assert!(
+                (0.0..=1.0).contains(&cp.intent_preservation),
+                "case {case}: intent preservation {} out of range",
+                cp.intent_preservation
+            );
+        }
+    }
+    // The generator guarantees a feasible anchor, so the sweep should
+    // mostly produce solvable cases; all-best-effort would mean the
+    // generator (or the search) regressed.
+    assert!(solved > 150, "only {solved} of 200 cases were solved");
+}